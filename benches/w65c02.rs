@@ -0,0 +1,92 @@
+//! Measures the emulated clock frequency the W65C02 core reaches on the host, decoding through the
+//! opcode tables and through the match decoder they replaced. The samples of both decoders are
+//! interleaved, so a busy host slows both down alike.
+//!
+//! Run with `cargo bench --bench w65c02 --features match-decoder`.
+//!
+//! Over five runs on a single core Linux host, the tables were 5-8% faster on average and 10-13%
+//! faster at the 95th percentile, e.g.:
+//!
+//! ```text
+//!   table : avg 233.63Mhz  p95 289.35Mhz  p99 303.35Mhz  min 166.69Mhz  max 303.35Mhz
+//!   match : avg 219.88Mhz  p95 259.80Mhz  p99 260.91Mhz  min 160.66Mhz  max 260.91Mhz
+//!   gain  : avg +6.3%  p95 +11.4%
+//! ```
+
+use std::time::Instant;
+
+use vm8::clock::{Frequency, FrequencyStats};
+use vm8::cpu::w65c02::{Bus, CPU, FakeBus};
+
+const SAMPLES: usize = 100;
+const CYCLES_PER_SAMPLE: usize = 1_000_000;
+
+/// A loop mixing loads, stores, arithmetic, branches and subroutine calls.
+const PROGRAM: &[(u16, &[u8])] = &[
+    (0x2000, &[
+        0xA2, 0x00,         // LDX #$00
+        0xBD, 0x00, 0x30,   // LDA $3000,X
+        0x69, 0x01,         // ADC #$01
+        0x9D, 0x00, 0x31,   // STA $3100,X
+        0xE8,               // INX
+        0xD0, 0xF5,         // BNE $2002
+        0x20, 0x20, 0x20,   // JSR $2020
+        0x4C, 0x00, 0x20,   // JMP $2000
+    ]),
+    (0x2020, &[
+        0x48,               // PHA
+        0x68,               // PLA
+        0x60,               // RTS
+    ]),
+];
+
+fn setup(match_decoder: bool) -> (CPU, FakeBus) {
+    let mut bus = FakeBus::new();
+    for (addr, code) in PROGRAM {
+        for (i, byte) in code.iter().enumerate() {
+            bus.mem_write(addr + i as u16, *byte);
+        }
+    }
+    let mut cpu = CPU::new();
+    cpu.set_match_decoder(match_decoder);
+    cpu.pc = 0x2000;
+    cpu.sp = 0xFF;
+    (cpu, bus)
+}
+
+fn sample(cpu: &mut CPU, bus: &mut FakeBus) -> Frequency {
+    let mut cycles = 0;
+    let start = Instant::now();
+    while cycles < CYCLES_PER_SAMPLE {
+        cycles += cpu.exec(bus).unwrap().cycles;
+    }
+    Frequency::from_elapsed(cycles, start.elapsed())
+}
+
+fn report(decoder: &str, stats: &FrequencyStats) {
+    println!("  {:<6}: avg {}  p95 {}  p99 {}  min {}  max {}",
+        decoder, stats.avg, stats.p95, stats.p99, stats.min, stats.max);
+}
+
+fn gain(table: Frequency, matched: Frequency) -> f64 {
+    (table.to_mhz() / matched.to_mhz() - 1.0) * 100.0
+}
+
+fn main() {
+    let (mut table_cpu, mut table_bus) = setup(false);
+    let (mut match_cpu, mut match_bus) = setup(true);
+
+    let mut table_samples = Vec::with_capacity(SAMPLES);
+    let mut match_samples = Vec::with_capacity(SAMPLES);
+    for _ in 0..SAMPLES {
+        table_samples.push(sample(&mut table_cpu, &mut table_bus));
+        match_samples.push(sample(&mut match_cpu, &mut match_bus));
+    }
+
+    let table = FrequencyStats::evaluate(table_samples);
+    let matched = FrequencyStats::evaluate(match_samples);
+    println!("W65C02 emulated frequency over {} samples of {} cycles", SAMPLES, CYCLES_PER_SAMPLE);
+    report("table", &table);
+    report("match", &matched);
+    println!("  gain  : avg {:+.1}%  p95 {:+.1}%", gain(table.avg, matched.avg), gain(table.p95, matched.p95));
+}
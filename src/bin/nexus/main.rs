@@ -14,7 +14,6 @@ fn main() -> Result<(), String> {
         Ok(s) => s,
         Err(err) => return Err(format!("Error: failed to load Nexus BIOS ROM from {}: {}", bios_path.display(), err)),
     };
-    sys.exec_cmd(Command::Reset);
 
     let mut rl = Editor::<()>::new();
     println!("Nexus Computer System Emulator v{}", env!("CARGO_PKG_VERSION"));
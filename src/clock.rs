@@ -16,8 +16,7 @@ impl Frequency {
     }
 
     pub fn from_elapsed(cycles: usize, duration: Duration) -> Self {
-        let elapsed_secs = duration.subsec_nanos() as f64 / 1_000_000_000.0;
-        Self::new(cycles as f64 / elapsed_secs)
+        Self::new(cycles as f64 / duration.as_secs_f64())
     }
 
     pub fn from_khz(val: f64) -> Self {
@@ -155,6 +154,12 @@ mod test {
         assert_eq!(1000.0, freq.to_mhz());
     }
 
+    #[test]
+    fn frequency_from_elapsed_over_a_second() {
+        let freq = Frequency::from_elapsed(4_000_000, Duration::from_millis(2_000));
+        assert_eq!(2.0, freq.to_mhz());
+    }
+
     #[test]
     fn frequency_period() {
         let freq = Frequency::from_mhz(3.58);
@@ -1,2 +1,6 @@
+mod error;
+
 pub mod w65c02;
 pub mod z80;
+
+pub use error::{ExecError, ExecErrorKind};
@@ -0,0 +1,44 @@
+use std::fmt;
+
+/// Reason why a CPU could not execute the next instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecErrorKind {
+    /// The opcode is not defined for this CPU.
+    InvalidOpcode,
+    /// The opcode is defined, but the emulator does not support it yet.
+    Unimplemented,
+    /// The CPU is stopped and will not execute anything until it is reset.
+    Stopped,
+}
+
+/// Error returned by `exec` when the CPU cannot execute the next instruction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecError {
+    pub kind: ExecErrorKind,
+    /// Address of the offending instruction.
+    pub pc: u16,
+    /// Opcode bytes of the offending instruction, including any prefixes.
+    pub opcode: Vec<u8>,
+}
+
+impl ExecError {
+    pub fn new(kind: ExecErrorKind, pc: u16, opcode: &[u8]) -> Self {
+        Self { kind, pc, opcode: opcode.to_vec() }
+    }
+}
+
+impl fmt::Display for ExecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let opcode = self.opcode.iter()
+            .map(|b| format!("{:02X}", b))
+            .collect::<Vec<_>>()
+            .join(" ");
+        match self.kind {
+            ExecErrorKind::InvalidOpcode => write!(f, "invalid opcode {} at {:04X}", opcode, self.pc),
+            ExecErrorKind::Unimplemented => write!(f, "unimplemented opcode {} at {:04X}", opcode, self.pc),
+            ExecErrorKind::Stopped => write!(f, "CPU stopped by opcode {} at {:04X}", opcode, self.pc),
+        }
+    }
+}
+
+impl std::error::Error for ExecError {}
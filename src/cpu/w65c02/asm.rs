@@ -0,0 +1,759 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::cpu::w65c02::{Bus, Variant, inst::{self, Mode, Opcode}};
+
+/// Maximum nesting of `.include` directives, which also catches files including themselves.
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// Error found while assembling, located at a line of the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AsmError {
+    pub file: String,
+    pub line: usize,
+    pub message: String,
+}
+
+impl AsmError {
+    fn new(line: &Line, message: impl Into<String>) -> Self {
+        Self { file: line.file.clone(), line: line.number, message: message.into() }
+    }
+}
+
+impl fmt::Display for AsmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.file, self.line, self.message)
+    }
+}
+
+impl std::error::Error for AsmError {}
+
+/// A contiguous block of assembled bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    pub addr: u16,
+    pub bytes: Vec<u8>,
+}
+
+/// An assembled program, made of one segment per `.org` directive.
+#[derive(Debug, Clone)]
+pub struct Program {
+    pub segments: Vec<Segment>,
+    pub symbols: BTreeMap<String, u16>,
+    listing: Vec<ListingLine>,
+}
+
+#[derive(Debug, Clone)]
+struct ListingLine {
+    addr: Option<u16>,
+    bytes: Vec<u8>,
+    source: String,
+}
+
+impl Program {
+    /// Write every segment to the bus at its address.
+    pub fn load(&self, bus: &mut impl Bus) {
+        for segment in &self.segments {
+            for (i, byte) in segment.bytes.iter().enumerate() {
+                bus.mem_write(segment.addr.wrapping_add(i as u16), *byte);
+            }
+        }
+    }
+
+    /// The program as a single image, from its lowest to its highest address. Gaps between
+    /// segments are filled with zeros.
+    pub fn bytes(&self) -> Vec<u8> {
+        let start = match self.segments.iter().map(|s| s.addr as usize).min() {
+            Some(start) => start,
+            None => return Vec::new(),
+        };
+        let end = self.segments.iter().map(|s| s.addr as usize + s.bytes.len()).max().unwrap();
+        let mut image = vec![0; end - start];
+        for segment in &self.segments {
+            let offset = segment.addr as usize - start;
+            image[offset..offset + segment.bytes.len()].copy_from_slice(&segment.bytes);
+        }
+        image
+    }
+
+    /// Source lines next to their addresses and bytes, followed by the symbol table.
+    pub fn listing(&self) -> String {
+        let mut out = String::new();
+        for line in &self.listing {
+            let mut chunks = line.bytes.chunks(3);
+            let first = chunks.next().unwrap_or(&[]);
+            match line.addr {
+                Some(addr) => out += &format!("{:04X}  {:<9} {}\n", addr, hex(first), line.source),
+                None => out += &format!("{:15} {}\n", "", line.source),
+            }
+            let mut addr = line.addr.unwrap_or(0).wrapping_add(3);
+            for chunk in chunks {
+                out += &format!("{:04X}  {}\n", addr, hex(chunk));
+                addr = addr.wrapping_add(3);
+            }
+        }
+        out += "\nSymbols:\n";
+        for (name, value) in &self.symbols {
+            out += &format!("  {:<24} ${:04X}\n", name, value);
+        }
+        out
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(" ")
+}
+
+/// Two-pass assembler for the instruction set of a 6502 family variant.
+///
+/// The syntax follows the usual 6502 conventions: `label:` definitions, `name = expr` constants,
+/// `$` hex, `%` binary and `'c'` character literals, `*` for the current address, and `<`/`>`
+/// for the low and high byte of an expression. The first pass assigns addresses to labels, so
+/// operands may refer to labels defined later; those take the absolute form of an instruction
+/// even if the label ends up in the zero page.
+pub struct Assembler {
+    mnemonics: HashMap<String, Opcode>,
+    encodings: HashMap<(Opcode, Mode), u8>,
+}
+
+impl Assembler {
+    pub fn new(variant: Variant) -> Self {
+        let mut mnemonics = HashMap::new();
+        let mut encodings = HashMap::new();
+        // NOP has several encodings, of which $EA is the documented one. Elsewhere the lowest
+        // opcode wins, which picks documented instructions over their undocumented aliases.
+        let codes = std::iter::once(0xEA).chain(0..=0xFF);
+        for code in codes {
+            if let Some((opcode, mode)) = inst::describe(variant, code) {
+                mnemonics.insert(format!("{:?}", opcode), opcode);
+                encodings.entry((opcode, mode)).or_insert(code);
+            }
+        }
+        Self { mnemonics, encodings }
+    }
+
+    /// Assemble source code. Included files are looked up relative to the working directory.
+    pub fn assemble(&self, source: &str) -> Result<Program, AsmError> {
+        let mut lines = Vec::new();
+        self.load(source, "<input>", Path::new(""), 0, &mut lines)?;
+        self.assemble_lines(&lines)
+    }
+
+    /// Assemble a source file. Included files are looked up relative to the including file.
+    pub fn assemble_file(&self, path: &Path) -> Result<Program, AsmError> {
+        let source = fs::read_to_string(path).map_err(|err| AsmError {
+            file: path.display().to_string(),
+            line: 0,
+            message: err.to_string(),
+        })?;
+        let mut lines = Vec::new();
+        let dir = path.parent().unwrap_or(Path::new(""));
+        self.load(&source, &path.display().to_string(), dir, 0, &mut lines)?;
+        self.assemble_lines(&lines)
+    }
+
+    /// Split the source into lines, replacing `.include` directives by the included lines.
+    fn load(&self, source: &str, file: &str, dir: &Path, depth: usize, lines: &mut Vec<Line>) -> Result<(), AsmError> {
+        for (i, text) in source.lines().enumerate() {
+            let line = Line { file: file.to_string(), number: i + 1, text: text.to_string() };
+            let code = strip_comment(text).trim();
+            let directive = code.split_whitespace().next().unwrap_or("");
+            if !directive.eq_ignore_ascii_case(".include") {
+                lines.push(line);
+                continue;
+            }
+
+            if depth >= MAX_INCLUDE_DEPTH {
+                return Err(AsmError::new(&line, "includes nested too deep"));
+            }
+            let name = parse_string(code[directive.len()..].trim()).map_err(|err| AsmError::new(&line, err))?;
+            let path = dir.join(String::from_utf8_lossy(&name).as_ref());
+            let included = fs::read_to_string(&path)
+                .map_err(|err| AsmError::new(&line, format!("cannot include {}: {}", path.display(), err)))?;
+            let included_dir = path.parent().unwrap_or(Path::new(""));
+            self.load(&included, &path.display().to_string(), included_dir, depth + 1, lines)?;
+        }
+        Ok(())
+    }
+
+    fn assemble_lines(&self, lines: &[Line]) -> Result<Program, AsmError> {
+        let statements = lines.iter()
+            .map(|line| self.parse_line(line).map_err(|err| AsmError::new(line, err)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // First pass: give every label its address and choose the addressing modes
+        let mut symbols = HashMap::new();
+        let mut modes = vec![None; lines.len()];
+        let mut pc: u16 = 0;
+        for (i, (line, parsed)) in lines.iter().zip(&statements).enumerate() {
+            let error = |err: String| AsmError::new(line, err);
+            if let Some(label) = &parsed.label {
+                if symbols.insert(label.clone(), pc as i64).is_some() {
+                    return Err(error(format!("symbol '{}' already defined", label)));
+                }
+            }
+            let size = match &parsed.statement {
+                Statement::Empty => 0,
+                Statement::Assign(name, expr) => {
+                    if let Some(value) = eval(expr, &symbols, pc).map_err(error)? {
+                        if symbols.insert(name.clone(), value).is_some() {
+                            return Err(error(format!("symbol '{}' already defined", name)));
+                        }
+                    }
+                    0
+                },
+                Statement::Org(expr) => {
+                    let value = eval(expr, &symbols, pc).map_err(error)?
+                        .ok_or_else(|| error(String::from(".org must not use symbols defined later")))?;
+                    pc = to_word(value).map_err(error)?;
+                    0
+                },
+                Statement::Byte(items) => data_len(items, 1),
+                Statement::Word(items) => data_len(items, 2),
+                Statement::Text(bytes) => bytes.len(),
+                Statement::Instruction(opcode, syntax) => {
+                    let mode = self.choose_mode(*opcode, syntax, &symbols, pc).map_err(error)?;
+                    modes[i] = Some(mode);
+                    1 + operand_len(mode)
+                },
+            };
+            pc = pc.wrapping_add(size as u16);
+        }
+
+        // Second pass: with every symbol known, evaluate the operands and emit the bytes
+        let mut segments: Vec<Segment> = Vec::new();
+        let mut listing = Vec::new();
+        let mut pc: u16 = 0;
+        let mut new_segment = true;
+        for (i, (line, parsed)) in lines.iter().zip(&statements).enumerate() {
+            let error = |err: String| AsmError::new(line, err);
+            let value = |expr: &str| -> Result<i64, AsmError> {
+                eval(expr, &symbols, pc).map_err(error)?
+                    .ok_or_else(|| error(format!("undefined symbol in '{}'", expr)))
+            };
+            let start = pc;
+            let bytes = match &parsed.statement {
+                Statement::Empty => Vec::new(),
+                Statement::Assign(name, expr) => {
+                    let value = value(expr)?;
+                    symbols.insert(name.clone(), value);
+                    Vec::new()
+                },
+                Statement::Org(expr) => {
+                    pc = to_word(value(expr)?).map_err(error)?;
+                    new_segment = true;
+                    Vec::new()
+                },
+                Statement::Byte(items) => {
+                    let mut bytes = Vec::new();
+                    for item in items {
+                        match item {
+                            Data::Bytes(data) => bytes.extend_from_slice(data),
+                            Data::Expr(expr) => bytes.push(to_byte(value(expr)?).map_err(error)?),
+                        }
+                    }
+                    bytes
+                },
+                Statement::Word(items) => {
+                    let mut bytes = Vec::new();
+                    for item in items {
+                        match item {
+                            Data::Bytes(data) => data.iter().for_each(|b| bytes.extend_from_slice(&[*b, 0])),
+                            Data::Expr(expr) => bytes.extend_from_slice(&to_word(value(expr)?).map_err(error)?.to_le_bytes()),
+                        }
+                    }
+                    bytes
+                },
+                Statement::Text(bytes) => bytes.clone(),
+                Statement::Instruction(opcode, syntax) => {
+                    let mode = modes[i].unwrap();
+                    let mut bytes = vec![self.encodings[&(*opcode, mode)]];
+                    match syntax {
+                        Syntax::None | Syntax::Accumulator => {},
+                        Syntax::ZeroPageRelative(zp, target) => {
+                            bytes.push(to_byte(value(zp)?).map_err(error)?);
+                            bytes.push(branch_offset(pc, 3, value(target)?).map_err(error)?);
+                        },
+                        Syntax::Immediate(expr) => bytes.push(to_byte(value(expr)?).map_err(error)?),
+                        Syntax::Direct(expr) | Syntax::IndexedX(expr) | Syntax::IndexedY(expr) |
+                        Syntax::Indirect(expr) | Syntax::IndirectX(expr) | Syntax::IndirectY(expr) => {
+                            let value = value(expr)?;
+                            match mode {
+                                Mode::Relative => bytes.push(branch_offset(pc, 2, value).map_err(error)?),
+                                _ if operand_len(mode) == 1 => bytes.push(to_zero_page(value).map_err(error)?),
+                                _ => bytes.extend_from_slice(&to_word(value).map_err(error)?.to_le_bytes()),
+                            }
+                        },
+                    }
+                    bytes
+                },
+            };
+
+            if !bytes.is_empty() {
+                if new_segment {
+                    segments.push(Segment { addr: pc, bytes: Vec::new() });
+                    new_segment = false;
+                }
+                segments.last_mut().unwrap().bytes.extend_from_slice(&bytes);
+                pc = pc.wrapping_add(bytes.len() as u16);
+            }
+
+            let addr = match parsed.statement {
+                Statement::Empty if parsed.label.is_none() => None,
+                Statement::Org(_) => Some(pc),
+                _ => Some(start),
+            };
+            listing.push(ListingLine { addr, bytes, source: line.text.clone() });
+        }
+
+        let symbols = symbols.into_iter().map(|(name, value)| (name, value as u16)).collect();
+        Ok(Program { segments, symbols, listing })
+    }
+
+    fn parse_line(&self, line: &Line) -> Result<Parsed, String> {
+        let mut code = strip_comment(&line.text).trim();
+
+        // A label is a symbol at the start of the line followed by a colon
+        let mut label = None;
+        let name_len = symbol_len(code);
+        if name_len > 0 && code[name_len..].starts_with(':') {
+            label = Some(code[..name_len].to_string());
+            code = code[name_len + 1..].trim();
+        }
+        if code.is_empty() {
+            return Ok(Parsed { label, statement: Statement::Empty });
+        }
+
+        let name_len = symbol_len(code);
+        let rest = code[name_len..].trim_start();
+        if name_len > 0 && rest.starts_with('=') {
+            let statement = Statement::Assign(code[..name_len].to_string(), rest[1..].trim().to_string());
+            return Ok(Parsed { label, statement });
+        }
+
+        let (word, args) = match code.find(char::is_whitespace) {
+            Some(pos) => (&code[..pos], code[pos..].trim()),
+            None => (code, ""),
+        };
+        let statement = match word.to_ascii_lowercase().as_str() {
+            ".org" => Statement::Org(args.to_string()),
+            ".byte" => Statement::Byte(parse_data(args)?),
+            ".word" => Statement::Word(parse_data(args)?),
+            ".text" => Statement::Text(parse_string(args)?),
+            directive if directive.starts_with('.') => return Err(format!("unknown directive '{}'", word)),
+            mnemonic => {
+                let opcode = *self.mnemonics.get(&mnemonic.to_ascii_uppercase())
+                    .ok_or_else(|| format!("unknown instruction '{}'", word))?;
+                Statement::Instruction(opcode, parse_operand(args)?)
+            },
+        };
+        Ok(Parsed { label, statement })
+    }
+
+    /// Pick the addressing mode for the operand syntax, preferring the zero page forms when the
+    /// operand is already known to fit in a byte.
+    fn choose_mode(&self, opcode: Opcode, syntax: &Syntax, symbols: &HashMap<String, i64>, pc: u16) -> Result<Mode, String> {
+        let has = |mode| self.encodings.contains_key(&(opcode, mode));
+        let zero_page = |expr: &str| -> Result<bool, String> {
+            Ok(matches!(eval(expr, symbols, pc)?, Some(0..=0xFF)))
+        };
+        let pick = |expr: &str, short, long| -> Result<Mode, String> {
+            Ok(if has(short) && (zero_page(expr)? || !has(long)) { short } else { long })
+        };
+        let mode = match syntax {
+            Syntax::None if has(Mode::Implied) => Mode::Implied,
+            Syntax::None | Syntax::Accumulator => Mode::Accumulator,
+            Syntax::Immediate(_) => Mode::Immediate,
+            Syntax::Direct(_) if has(Mode::Relative) => Mode::Relative,
+            Syntax::Direct(expr) => pick(expr, Mode::ZeroPage, Mode::Absolute)?,
+            Syntax::IndexedX(expr) => pick(expr, Mode::ZeroPageX, Mode::AbsoluteX)?,
+            Syntax::IndexedY(expr) => pick(expr, Mode::ZeroPageY, Mode::AbsoluteY)?,
+            Syntax::Indirect(expr) => pick(expr, Mode::ZeroPageIndirect, Mode::Indirect)?,
+            Syntax::IndirectX(expr) => pick(expr, Mode::IndirectX, Mode::AbsoluteIndirectX)?,
+            Syntax::IndirectY(_) => Mode::IndirectY,
+            Syntax::ZeroPageRelative(_, _) => Mode::ZeroPageRelative,
+        };
+        if !has(mode) {
+            return Err(format!("invalid addressing mode for {:?}", opcode));
+        }
+        Ok(mode)
+    }
+}
+
+/// A line of source code and where it comes from.
+struct Line {
+    file: String,
+    number: usize,
+    text: String,
+}
+
+struct Parsed {
+    label: Option<String>,
+    statement: Statement,
+}
+
+enum Statement {
+    Empty,
+    Assign(String, String),
+    Org(String),
+    Byte(Vec<Data>),
+    Word(Vec<Data>),
+    Text(Vec<u8>),
+    Instruction(Opcode, Syntax),
+}
+
+/// An item of a `.byte` or `.word` directive.
+enum Data {
+    Bytes(Vec<u8>),
+    Expr(String),
+}
+
+/// The shape of an instruction operand, before it is resolved to an addressing mode.
+enum Syntax {
+    None,
+    Accumulator,
+    Immediate(String),
+    Direct(String),
+    IndexedX(String),
+    IndexedY(String),
+    Indirect(String),
+    IndirectX(String),
+    IndirectY(String),
+    ZeroPageRelative(String, String),
+}
+
+fn parse_operand(args: &str) -> Result<Syntax, String> {
+    let operand = compact(args);
+    if operand.is_empty() {
+        return Ok(Syntax::None);
+    }
+    if operand.eq_ignore_ascii_case("A") {
+        return Ok(Syntax::Accumulator);
+    }
+    if let Some(expr) = operand.strip_prefix('#') {
+        return Ok(Syntax::Immediate(expr.to_string()));
+    }
+
+    let parts = split_top_level(&operand);
+    let syntax = match parts.as_slice() {
+        [expr] => match enclosed(expr) {
+            Some(inner) => match split_top_level(inner).as_slice() {
+                [ptr] => Syntax::Indirect(ptr.to_string()),
+                [ptr, index] if index.eq_ignore_ascii_case("X") => Syntax::IndirectX(ptr.to_string()),
+                _ => return Err(format!("invalid operand '{}'", args)),
+            },
+            None => Syntax::Direct(expr.to_string()),
+        },
+        [expr, index] if index.eq_ignore_ascii_case("X") => Syntax::IndexedX(expr.to_string()),
+        [expr, index] if index.eq_ignore_ascii_case("Y") => match enclosed(expr) {
+            Some(ptr) => Syntax::IndirectY(ptr.to_string()),
+            None => Syntax::IndexedY(expr.to_string()),
+        },
+        [zp, target] => Syntax::ZeroPageRelative(zp.to_string(), target.to_string()),
+        _ => return Err(format!("invalid operand '{}'", args)),
+    };
+    Ok(syntax)
+}
+
+fn parse_data(args: &str) -> Result<Vec<Data>, String> {
+    split_top_level(args).into_iter()
+        .map(|item| {
+            let item = item.trim();
+            if item.starts_with('"') {
+                parse_string(item).map(Data::Bytes)
+            } else if item.is_empty() {
+                Err(String::from("missing data value"))
+            } else {
+                Ok(Data::Expr(item.to_string()))
+            }
+        })
+        .collect()
+}
+
+/// Bytes of a double-quoted string, which may contain `\n`, `\r`, `\t`, `\0`, `\\` and `\"`.
+fn parse_string(s: &str) -> Result<Vec<u8>, String> {
+    let inner = s.strip_prefix('"').and_then(|s| s.strip_suffix('"'))
+        .ok_or_else(|| format!("expected a string, found '{}'", s))?;
+    let mut bytes = Vec::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        let c = match c {
+            '\\' => match chars.next() {
+                Some('n') => '\n',
+                Some('r') => '\r',
+                Some('t') => '\t',
+                Some('0') => '\0',
+                Some(c @ ('\\' | '"' | '\'')) => c,
+                _ => return Err(format!("invalid escape in {}", s)),
+            },
+            c => c,
+        };
+        if !c.is_ascii() {
+            return Err(format!("non-ASCII character in {}", s));
+        }
+        bytes.push(c as u8);
+    }
+    Ok(bytes)
+}
+
+/// Remove the comment that starts with `;`, unless it is quoted.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    for (i, c) in line.char_indices() {
+        match (c, quote) {
+            (';', None) => return &line[..i],
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            _ => {},
+        }
+    }
+    line
+}
+
+/// Remove the whitespace outside quotes.
+fn compact(s: &str) -> String {
+    let mut out = String::new();
+    let mut quote = None;
+    for c in s.chars() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            (c, None) if c.is_whitespace() => continue,
+            _ => {},
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Split at the commas outside parentheses and quotes.
+fn split_top_level(s: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut quote = None;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            ('(', None) => depth += 1,
+            (')', None) => depth -= 1,
+            (',', None) if depth == 0 => {
+                parts.push(&s[start..i]);
+                start = i + 1;
+            },
+            _ => {},
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+/// The inside of an expression wrapped as a whole in parentheses, as in `($12)` but not in
+/// `($12+1)*2`.
+fn enclosed(s: &str) -> Option<&str> {
+    let inner = s.strip_prefix('(')?.strip_suffix(')')?;
+    let mut depth = 0;
+    for c in inner.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return None,
+            ')' => depth -= 1,
+            _ => {},
+        }
+    }
+    Some(inner)
+}
+
+/// Length of the symbol name at the start of the string, or zero if there is none.
+fn symbol_len(s: &str) -> usize {
+    let mut len = 0;
+    for (i, c) in s.char_indices() {
+        let valid = c.is_ascii_alphabetic() || c == '_' || (i > 0 && (c.is_ascii_digit() || c == '.'));
+        if !valid {
+            break;
+        }
+        len = i + c.len_utf8();
+    }
+    len
+}
+
+fn data_len(items: &[Data], size: usize) -> usize {
+    items.iter()
+        .map(|item| match item {
+            Data::Bytes(bytes) => bytes.len() * size,
+            Data::Expr(_) => size,
+        })
+        .sum()
+}
+
+fn operand_len(mode: Mode) -> usize {
+    match mode {
+        Mode::Implied | Mode::Accumulator => 0,
+        Mode::Immediate | Mode::ZeroPage | Mode::ZeroPageX | Mode::ZeroPageY | Mode::IndirectX |
+        Mode::IndirectY | Mode::ZeroPageIndirect | Mode::Relative => 1,
+        Mode::Absolute | Mode::AbsoluteX | Mode::AbsoluteY | Mode::Indirect | Mode::AbsoluteIndirectX |
+        Mode::ZeroPageRelative => 2,
+    }
+}
+
+/// A byte value, which may be given as a negative number.
+fn to_byte(value: i64) -> Result<u8, String> {
+    match value {
+        -0x80..=0xFF => Ok(value as u8),
+        _ => Err(format!("value ${:X} does not fit in a byte", value)),
+    }
+}
+
+fn to_zero_page(value: i64) -> Result<u8, String> {
+    match value {
+        0..=0xFF => Ok(value as u8),
+        _ => Err(format!("address ${:X} is not in the zero page", value)),
+    }
+}
+
+fn to_word(value: i64) -> Result<u16, String> {
+    match value {
+        -0x8000..=0xFFFF => Ok(value as u16),
+        _ => Err(format!("value ${:X} does not fit in a word", value)),
+    }
+}
+
+/// Offset from the instruction that follows a branch of the given length to the target.
+fn branch_offset(pc: u16, len: u16, target: i64) -> Result<u8, String> {
+    let offset = target - pc.wrapping_add(len) as i64;
+    match offset {
+        -0x80..=0x7F => Ok(offset as u8),
+        _ => Err(format!("branch target ${:04X} out of range", target)),
+    }
+}
+
+/// Evaluate an expression. The result is `None` if it refers to symbols not defined yet.
+fn eval(expr: &str, symbols: &HashMap<String, i64>, pc: u16) -> Result<Option<i64>, String> {
+    let mut parser = ExprParser { input: expr.trim(), pos: 0, symbols, pc };
+    let value = parser.binary(0)?;
+    parser.skip_whitespace();
+    if parser.pos < parser.input.len() {
+        return Err(format!("invalid expression '{}'", expr.trim()));
+    }
+    Ok(value)
+}
+
+/// Binary operators, from the lowest to the highest precedence.
+const OPERATORS: &[&[&str]] = &[&["|"], &["^"], &["&"], &["<<", ">>"], &["+", "-"], &["*", "/", "%"]];
+
+struct ExprParser<'a> {
+    input: &'a str,
+    pos: usize,
+    symbols: &'a HashMap<String, i64>,
+    pc: u16,
+}
+
+impl<'a> ExprParser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.pos = self.input.len() - self.rest().trim_start().len();
+    }
+
+    fn binary(&mut self, level: usize) -> Result<Option<i64>, String> {
+        if level == OPERATORS.len() {
+            return self.unary();
+        }
+        let mut lhs = self.binary(level + 1)?;
+        loop {
+            self.skip_whitespace();
+            let op = match OPERATORS[level].iter().find(|op| self.rest().starts_with(**op)) {
+                Some(op) => *op,
+                None => return Ok(lhs),
+            };
+            self.pos += op.len();
+            let rhs = self.binary(level + 1)?;
+            lhs = match (lhs, rhs) {
+                (Some(a), Some(b)) => Some(match op {
+                    "|" => a | b,
+                    "^" => a ^ b,
+                    "&" => a & b,
+                    "<<" => a.checked_shl(b as u32).unwrap_or(0),
+                    ">>" => a.checked_shr(b as u32).unwrap_or(0),
+                    "+" => a.wrapping_add(b),
+                    "-" => a.wrapping_sub(b),
+                    "*" => a.wrapping_mul(b),
+                    "/" => a.checked_div(b).ok_or("division by zero")?,
+                    _ => a.checked_rem(b).ok_or("division by zero")?,
+                }),
+                _ => None,
+            };
+        }
+    }
+
+    fn unary(&mut self) -> Result<Option<i64>, String> {
+        self.skip_whitespace();
+        let op = match self.rest().chars().next() {
+            Some(c @ ('-' | '~' | '<' | '>')) => c,
+            _ => return self.primary(),
+        };
+        self.pos += 1;
+        let value = self.unary()?;
+        Ok(value.map(|v| match op {
+            '-' => -v,
+            '~' => !v,
+            '<' => v & 0xFF,
+            _ => (v >> 8) & 0xFF,
+        }))
+    }
+
+    fn primary(&mut self) -> Result<Option<i64>, String> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let mut chars = rest.chars();
+        let value = match chars.next() {
+            Some('(') => {
+                self.pos += 1;
+                let value = self.binary(0)?;
+                self.skip_whitespace();
+                if !self.rest().starts_with(')') {
+                    return Err(String::from("missing ')'"));
+                }
+                self.pos += 1;
+                return Ok(value);
+            },
+            Some('*') => {
+                self.pos += 1;
+                return Ok(Some(self.pc as i64));
+            },
+            Some('\'') => match (chars.next(), chars.next()) {
+                (Some(c), Some('\'')) if c.is_ascii() => {
+                    self.pos += 3;
+                    return Ok(Some(c as i64));
+                },
+                _ => return Err(format!("invalid character literal in '{}'", self.input)),
+            },
+            Some('$') => self.number(1, 16)?,
+            Some('%') => self.number(1, 2)?,
+            Some(c) if c.is_ascii_digit() => self.number(0, 10)?,
+            _ => {
+                let len = symbol_len(rest);
+                if len == 0 {
+                    return Err(format!("invalid expression '{}'", self.input));
+                }
+                self.pos += len;
+                return Ok(self.symbols.get(&rest[..len]).copied());
+            },
+        };
+        Ok(Some(value))
+    }
+
+    fn number(&mut self, prefix: usize, radix: u32) -> Result<i64, String> {
+        let digits = &self.rest()[prefix..];
+        let len = digits.find(|c: char| !c.is_ascii_alphanumeric()).unwrap_or(digits.len());
+        let value = i64::from_str_radix(&digits[..len], radix)
+            .map_err(|_| format!("invalid number '{}'", &self.rest()[..prefix + len]))?;
+        self.pos += prefix + len;
+        Ok(value)
+    }
+}
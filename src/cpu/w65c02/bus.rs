@@ -1,3 +1,22 @@
+/// Direction of a bus cycle, as signalled on the R/W line.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Access {
+    Read,
+    Write,
+}
+
+/// A single clock cycle of bus activity, as reported in cycle-accurate mode.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BusCycle {
+    pub addr: u16,
+    pub data: u8,
+    pub access: Access,
+    /// SYNC: the cycle fetches an opcode.
+    pub sync: bool,
+    /// VPB: the cycle reads an interrupt or reset vector.
+    pub vpb: bool,
+}
+
 pub trait Bus {
     fn mem_read(&self, addr: u16) -> u8;
     fn mem_write(&mut self, addr: u16, val: u8);
@@ -23,6 +42,16 @@ pub trait Bus {
         self.mem_write(addr, val as u8);
         self.mem_write((addr & 0xFF00) | (u16::wrapping_add(addr, 1) & 0xFF), (val >> 8) as u8);
     }
+
+    /// Level of the IRQ line as driven by the devices on the bus, sampled between instructions.
+    fn irq(&self) -> bool { false }
+
+    /// Level of the NMI line as driven by the devices on the bus, sampled between instructions.
+    fn nmi(&self) -> bool { false }
+
+    /// Called after every bus cycle, in order, when the CPU runs in cycle-accurate mode. This
+    /// includes the dummy reads and writes the hardware performs while computing addresses.
+    fn cycle(&mut self, _cycle: BusCycle) {}
 }
 
 pub struct FakeBus {
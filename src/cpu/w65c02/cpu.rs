@@ -1,6 +1,10 @@
+use std::io;
+
 use bitflags::bitflags;
+use rand::Rng;
 
-use crate::cpu::w65c02::{inst, Bus, inst::Instruction};
+use crate::cpu::{ExecError, ExecErrorKind};
+use crate::cpu::w65c02::{inst, Access, Bus, BusCycle, Observer, Step, inst::{Instruction, Opcode, Operand}};
 
 
 pub(crate) const VECTOR_RESET: u16 = 0xFFFC;
@@ -21,6 +25,74 @@ bitflags! {
     }
 }
 
+impl Flags {
+    /// The byte pushed to the stack for this status. Bit 5 is always set, and B tells whether
+    /// BRK or PHP pushed it (`brk` set) or a hardware interrupt did.
+    pub fn pushed(self, brk: bool) -> u8 {
+        let mut status = self | Flags::UNUSED;
+        status.set(Flags::BREAK, brk);
+        status.bits()
+    }
+
+    /// The status loaded from a byte pulled by PLP or RTI. The register has no B flag, and its
+    /// bit 5 always reads as 1, so neither is taken from the byte.
+    pub fn pulled(byte: u8) -> Self {
+        (Flags::from_bits_retain(byte) - Flags::BREAK) | Flags::UNUSED
+    }
+}
+
+/// Member of the 6502 family emulated by the CPU.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Variant {
+    /// Original NMOS 6502, with its indirect jump bug and binary flags in decimal mode.
+    NMOS6502,
+    /// Western Design Center W65C02S, with the full CMOS instruction set.
+    W65C02,
+    /// Rockwell R65C02, with the bit manipulation instructions but without WAI and STP.
+    R65C02,
+    /// 65SC02, with the CMOS instructions but without bit manipulation, WAI or STP.
+    G65SC02,
+}
+
+impl Variant {
+    /// Whether this is one of the CMOS variants, which fix the NMOS bugs and add new instructions.
+    pub const fn is_cmos(&self) -> bool {
+        !matches!(self, Variant::NMOS6502)
+    }
+
+    /// Whether the variant has the RMB, SMB, BBR and BBS instructions.
+    pub const fn has_bit_ops(&self) -> bool {
+        matches!(self, Variant::W65C02 | Variant::R65C02)
+    }
+
+    /// Whether the variant has the WAI and STP instructions.
+    pub const fn has_wai_stp(&self) -> bool {
+        matches!(self, Variant::W65C02)
+    }
+}
+
+/// Execution state of the CPU, as changed by WAI, STP and interrupts.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RunState {
+    /// Fetching and executing instructions.
+    Running,
+    /// Halted by WAI until an IRQ or NMI arrives.
+    Waiting,
+    /// Halted by STP, or by a JAM on the NMOS 6502, until the next reset.
+    Stopped,
+}
+
+/// Reason why `CPU::run_for` or `CPU::run_until` returned.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StopReason {
+    /// The cycle budget was spent.
+    Budget,
+    /// The predicate matched after an instruction.
+    Break,
+    /// The CPU was stopped by STP, or by a JAM on the NMOS 6502.
+    Stopped,
+}
+
 /// Wester Digital W65C02 CPU
 pub struct CPU {
     pub a: u8,
@@ -29,55 +101,381 @@ pub struct CPU {
     pub pc: u16,
     pub sp: u8,
     pub status: Flags,
+
+    variant: Variant,
+    pub(crate) run_state: RunState,
+    irq: bool,
+    nmi: bool,
+    nmi_bus: bool,
+    nmi_pending: bool,
+    cycle_accurate: bool,
+    #[cfg(feature = "match-decoder")]
+    match_decoder: bool,
+    observer: Option<Box<dyn Observer>>,
+    cycles: usize,
 }
 
 impl CPU {
     pub fn new() -> Self {
+        Self::with_variant(Variant::W65C02)
+    }
+
+    pub fn with_variant(variant: Variant) -> Self {
         Self {
             a: 0,
             x: 0,
             y: 0,
             pc: 0,
             sp: 0,
-            status: Flags::empty(),
+            status: Flags::UNUSED,
+            variant,
+            run_state: RunState::Running,
+            irq: false,
+            nmi: false,
+            nmi_bus: false,
+            nmi_pending: false,
+            cycle_accurate: false,
+            #[cfg(feature = "match-decoder")]
+            match_decoder: false,
+            observer: None,
+            cycles: 0,
         }
     }
 
-    pub fn reset(&mut self, bus: &mut impl Bus) {
+    /// Apply power and run the reset sequence. The registers start zeroed, which real hardware
+    /// does not guarantee: see `power_on_random` to start from arbitrary values instead.
+    pub fn power_on(&mut self, bus: &mut impl Bus) -> Instruction {
         self.a = 0;
         self.x = 0;
         self.y = 0;
-        self.sp = 0xFF;
-        self.status = Flags::INTERRUPT;
+        self.sp = 0;
+        self.status = Flags::UNUSED;
+        self.pc = 0;
+        self.power_cycle();
+        self.reset(bus)
+    }
+
+    /// Apply power with random register values, as left by the hardware, and run the reset
+    /// sequence. This exposes code that relies on registers it never initializes.
+    pub fn power_on_random(&mut self, bus: &mut impl Bus, rng: &mut impl Rng) -> Instruction {
+        self.a = rng.gen();
+        self.x = rng.gen();
+        self.y = rng.gen();
+        self.sp = rng.gen();
+        self.status = Flags::pulled(rng.gen());
+        self.pc = rng.gen();
+        self.power_cycle();
+        self.reset(bus)
+    }
+
+    /// Clear the internal state that does not survive a power cycle.
+    fn power_cycle(&mut self) {
+        self.nmi_bus = false;
+        self.nmi_pending = false;
+        self.run_state = RunState::Running;
+    }
+
+    /// Run the reset sequence, as when the reset line is released. It takes 7 cycles, like an
+    /// interrupt whose three stack pushes are turned into reads: the registers keep their
+    /// values, but the stack pointer goes down by three. Then the interrupts are disabled, the
+    /// decimal mode is cleared on CMOS variants and execution continues at the reset vector.
+    pub fn reset(&mut self, bus: &mut impl Bus) -> Instruction {
+        if self.cycle_accurate {
+            self.read_cycle(bus, self.pc, true, false);
+        }
+        self.dummy_read(bus, self.pc);
+        for _ in 0..3 {
+            self.stack_dummy_read(bus);
+            self.sp = self.sp.wrapping_sub(1);
+        }
+        self.status.insert(Flags::INTERRUPT);
+        if self.variant.is_cmos() {
+            self.status.remove(Flags::DECIMAL);
+        }
+
+        self.nmi_pending = false;
+        self.run_state = RunState::Running;
+
+        self.pc = self.read_vector(bus, VECTOR_RESET);
+        self.cycles += 7;
+
+        Instruction {
+            opcode: Opcode::RESET,
+            operand: Operand::Implied,
+            cycles: 7,
+        }
+    }
+
+    /// Cycles run since the CPU was created or `reset_cycles` was last called.
+    pub fn cycles(&self) -> usize { self.cycles }
+
+    pub fn reset_cycles(&mut self) { self.cycles = 0 }
+
+    pub fn variant(&self) -> Variant {
+        self.variant
+    }
+
+    pub fn cycle_accurate(&self) -> bool {
+        self.cycle_accurate
+    }
+
+    /// Report every bus cycle to `Bus::cycle`, including the dummy reads and writes that the
+    /// hardware performs but that have no effect on memory. It is disabled by default, since
+    /// the extra accesses slow down the emulation.
+    pub fn set_cycle_accurate(&mut self, enabled: bool) {
+        self.cycle_accurate = enabled;
+    }
+
+    #[cfg(feature = "match-decoder")]
+    pub fn match_decoder(&self) -> bool {
+        self.match_decoder
+    }
+
+    /// Decode every opcode by matching it at runtime, as the CPU did before the decoding tables,
+    /// so the benchmark can measure the difference.
+    #[cfg(feature = "match-decoder")]
+    pub fn set_match_decoder(&mut self, enabled: bool) {
+        self.match_decoder = enabled;
+    }
+
+    /// Report every executed instruction and serviced interrupt to the observer, e.g. a `Tracer`
+    /// that logs them. Instructions are disassembled before they run, which slows down the
+    /// emulation while an observer is set.
+    pub fn set_observer(&mut self, observer: impl Observer + 'static) {
+        self.observer = Some(Box::new(observer));
+    }
+
+    /// Remove the observer, returning the first error it found writing its output.
+    pub fn remove_observer(&mut self) -> io::Result<()> {
+        match self.observer.take() {
+            Some(mut observer) => observer.finish(),
+            None => Ok(()),
+        }
+    }
 
-        self.pc = bus.mem_read_word(VECTOR_RESET);
+    pub fn run_state(&self) -> RunState {
+        self.run_state
     }
 
-    pub fn exec<B: Bus>(&mut self, bus: &mut B) -> Instruction {
-        let inst = inst::decode(self, bus);
-        (inst.handler)(self, bus, &inst)
+    /// Drive the IRQ input. The line is level-triggered: the interrupt is taken before the next
+    /// instruction for as long as it is asserted and `Flags::INTERRUPT` is clear.
+    pub fn set_irq(&mut self, asserted: bool) {
+        self.irq = asserted;
+    }
+
+    /// Drive the NMI input. The line is edge-triggered: only the transition from released to
+    /// asserted raises an interrupt, which is serviced before the next instruction.
+    pub fn set_nmi(&mut self, asserted: bool) {
+        if asserted && !self.nmi {
+            self.nmi_pending = true;
+        }
+        self.nmi = asserted;
+    }
+
+    pub fn exec<B: Bus>(&mut self, bus: &mut B) -> Result<Instruction, ExecError> {
+        let nmi = bus.nmi();
+        if nmi && !self.nmi_bus {
+            self.nmi_pending = true;
+        }
+        self.nmi_bus = nmi;
+        let irq = self.irq || bus.irq();
+
+        match self.run_state {
+            RunState::Running => {},
+            // A stopped CPU ignores everything but reset
+            RunState::Stopped => {
+                let pc = self.pc.wrapping_sub(1);
+                return Err(ExecError::new(ExecErrorKind::Stopped, pc, &[bus.mem_read(pc)]));
+            },
+            // Any interrupt wakes up a waiting CPU, even a masked IRQ, in which case execution
+            // just continues after the WAI instruction
+            RunState::Waiting if self.nmi_pending || irq => self.run_state = RunState::Running,
+            RunState::Waiting => {
+                self.cycles += 1;
+                return Ok(Self::idle(Opcode::WAI));
+            },
+        }
+
+        let irq = irq && !self.status.contains(Flags::INTERRUPT);
+        let step = self.observer.is_some().then(|| Step::before(self, bus, self.nmi_pending || irq));
+
+        let inst = if self.nmi_pending {
+            self.nmi_pending = false;
+            self.interrupt(bus, Opcode::NMI, VECTOR_NMI)
+        } else if irq {
+            self.interrupt(bus, Opcode::IRQ, VECTOR_IRQ)
+        } else {
+            let inst = inst::decode(self, bus)?;
+            (inst.handler)(self, bus, &inst)
+        };
+
+        if let (Some(mut step), Some(observer)) = (step, self.observer.as_mut()) {
+            step.inst = inst;
+            observer.observe(&step);
+        }
+        self.cycles += inst.cycles;
+        Ok(inst)
+    }
+
+    /// Execute instructions until they take at least the given number of cycles, or the CPU is
+    /// stopped.
+    pub fn run_for<B: Bus>(&mut self, bus: &mut B, cycles: usize) -> Result<StopReason, ExecError> {
+        self.run_until(bus, cycles, |_| false)
+    }
+
+    /// Execute instructions until they take at least the given number of cycles, the CPU is
+    /// stopped, or the predicate returns true after an instruction, e.g. when the program
+    /// counter hits a breakpoint.
+    ///
+    /// While WAI halts the CPU, the rest of the budget is skipped at once unless an interrupt is
+    /// already asserted: the bus cannot change its interrupt lines while the CPU is idle.
+    pub fn run_until<B: Bus>(
+        &mut self,
+        bus: &mut B,
+        cycles: usize,
+        mut predicate: impl FnMut(&CPU) -> bool,
+    ) -> Result<StopReason, ExecError> {
+        let end = self.cycles + cycles;
+        while self.cycles < end {
+            match self.run_state {
+                RunState::Running => {},
+                RunState::Stopped => return Ok(StopReason::Stopped),
+                RunState::Waiting if self.interrupt_asserted(bus) => {},
+                RunState::Waiting => {
+                    self.cycles = end;
+                    break;
+                },
+            }
+            self.exec(bus)?;
+            if predicate(self) {
+                return Ok(StopReason::Break);
+            }
+        }
+        Ok(StopReason::Budget)
+    }
+
+    /// Whether an interrupt would wake up the CPU from WAI in the next `exec`.
+    fn interrupt_asserted(&self, bus: &impl Bus) -> bool {
+        self.nmi_pending || self.irq || bus.irq() || (bus.nmi() && !self.nmi_bus)
+    }
+
+    /// A single clock cycle spent halted by the given instruction.
+    fn idle(opcode: Opcode) -> Instruction {
+        Instruction {
+            opcode,
+            operand: Operand::Implied,
+            cycles: 1,
+        }
+    }
+
+    /// Push the return address and status, then jump through the given vector. The pushed status
+    /// has the B flag cleared, so the handler can tell a hardware interrupt apart from BRK.
+    fn interrupt(&mut self, bus: &mut impl Bus, opcode: Opcode, vector: u16) -> Instruction {
+        // The opcode fetched in the first cycle is discarded and the second cycle reads it again
+        if self.cycle_accurate {
+            self.read_cycle(bus, self.pc, true, false);
+        }
+        self.dummy_read(bus, self.pc);
+        self.push_word(bus, self.pc);
+        self.push_byte(bus, self.status.pushed(false));
+        self.status.insert(Flags::INTERRUPT);
+        if self.variant.is_cmos() {
+            self.status.remove(Flags::DECIMAL);
+        }
+        self.pc = self.read_vector(bus, vector);
+
+        Instruction {
+            opcode,
+            operand: Operand::Implied,
+            cycles: 7,
+        }
+    }
+
+    /// Read a byte from the bus, reporting the cycle in cycle-accurate mode.
+    fn read_cycle(&mut self, bus: &mut impl Bus, addr: u16, sync: bool, vpb: bool) -> u8 {
+        let data = bus.mem_read(addr);
+        if self.cycle_accurate {
+            bus.cycle(BusCycle { addr, data, access: Access::Read, sync, vpb });
+        }
+        data
+    }
+
+    pub fn read(&mut self, bus: &mut impl Bus, addr: u16) -> u8 {
+        self.read_cycle(bus, addr, false, false)
+    }
+
+    pub fn write(&mut self, bus: &mut impl Bus, addr: u16, data: u8) {
+        bus.mem_write(addr, data);
+        if self.cycle_accurate {
+            bus.cycle(BusCycle { addr, data, access: Access::Write, sync: false, vpb: false });
+        }
+    }
+
+    pub fn read_word(&mut self, bus: &mut impl Bus, addr: u16) -> u16 {
+        let lo = self.read(bus, addr);
+        let hi = self.read(bus, addr.wrapping_add(1));
+        (hi as u16) << 8 | lo as u16
+    }
+
+    pub fn read_word_page_wrap(&mut self, bus: &mut impl Bus, addr: u16) -> u16 {
+        let lo = self.read(bus, addr);
+        let hi = self.read(bus, (addr & 0xFF00) | (addr.wrapping_add(1) & 0xFF));
+        (hi as u16) << 8 | lo as u16
+    }
+
+    /// Read an interrupt or reset vector, with VPB asserted.
+    pub(crate) fn read_vector(&mut self, bus: &mut impl Bus, vector: u16) -> u16 {
+        let lo = self.read_cycle(bus, vector, false, true);
+        let hi = self.read_cycle(bus, vector.wrapping_add(1), false, true);
+        (hi as u16) << 8 | lo as u16
+    }
+
+    /// A read whose value the CPU discards. It only reaches the bus in cycle-accurate mode.
+    pub(crate) fn dummy_read(&mut self, bus: &mut impl Bus, addr: u16) {
+        if self.cycle_accurate {
+            self.read(bus, addr);
+        }
+    }
+
+    /// A write of the value already in memory. It only reaches the bus in cycle-accurate mode.
+    pub(crate) fn dummy_write(&mut self, bus: &mut impl Bus, addr: u16, data: u8) {
+        if self.cycle_accurate {
+            self.write(bus, addr, data);
+        }
+    }
+
+    /// Fetch the next opcode, with SYNC asserted.
+    pub(crate) fn fetch_opcode(&mut self, bus: &mut impl Bus) -> u8 {
+        let opcode = self.read_cycle(bus, self.pc, true, false);
+        self.pc += 1;
+        opcode
     }
 
     pub fn fetch_byte(&mut self, bus: &mut impl Bus) -> u8 {
-        let byte = bus.mem_read(self.pc);
+        let byte = self.read(bus, self.pc);
         self.pc += 1;
         byte
     }
 
     pub fn fetch_word(&mut self, bus: &mut impl Bus) -> u16 {
-        let word = bus.mem_read_word(self.pc);
-        self.pc += 2;
-        word
+        let lo = self.fetch_byte(bus);
+        let hi = self.fetch_byte(bus);
+        (hi as u16) << 8 | lo as u16
     }
     
     pub fn push_byte(&mut self, bus: &mut impl Bus, value: u8) {
-        bus.mem_write(0x0100 + self.sp as u16, value);
+        self.write(bus, 0x0100 + self.sp as u16, value);
         self.sp = self.sp.wrapping_sub(1);
     }
 
     pub fn pop_byte(&mut self, bus: &mut impl Bus) -> u8 {
         self.sp = self.sp.wrapping_add(1);
-        bus.mem_read(0x0100 + self.sp as u16)
+        self.read(bus, 0x0100 + self.sp as u16)
+    }
+
+    /// The dummy read of the current top of the stack that precedes a pull.
+    pub(crate) fn stack_dummy_read(&mut self, bus: &mut impl Bus) {
+        self.dummy_read(bus, 0x0100 + self.sp as u16);
     }
 
     pub fn push_word(&mut self, bus: &mut impl Bus, value: u16) {
@@ -92,19 +490,21 @@ impl CPU {
     }
     
     pub fn zeropage_read_byte(&mut self, bus: &mut impl Bus, addr: u8, offset: u8) -> u8 {
-        bus.mem_read(u8::wrapping_add(addr, offset) as u16)
+        self.read(bus, u8::wrapping_add(addr, offset) as u16)
     }
 
     pub fn zeropage_write_byte(&mut self, bus: &mut impl Bus, addr: u8, offset: u8, val: u8) {
-        bus.mem_write(u8::wrapping_add(addr, offset) as u16, val);
+        self.write(bus, u8::wrapping_add(addr, offset) as u16, val);
     }
 
     pub fn zeropage_read_word(&mut self, bus: &mut impl Bus, addr: u8, offset: u8) -> u16 {
-        bus.mem_read_word_page_wrap(u8::wrapping_add(addr, offset) as u16)
+        self.read_word_page_wrap(bus, u8::wrapping_add(addr, offset) as u16)
     }
 
     pub fn zeropage_write_word(&mut self, bus: &mut impl Bus, addr: u8, offset: u8, val: u16) {
-        bus.mem_write_word_page_wrap(u8::wrapping_add(addr, offset) as u16, val);
+        let addr = u8::wrapping_add(addr, offset);
+        self.write(bus, addr as u16, val as u8);
+        self.write(bus, addr.wrapping_add(1) as u16, (val >> 8) as u8);
     }
 
 }
\ No newline at end of file
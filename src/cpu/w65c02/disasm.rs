@@ -0,0 +1,58 @@
+use std::fmt;
+
+use crate::cpu::{ExecError, ExecErrorKind};
+use crate::cpu::w65c02::{Bus, Variant, inst::{self, Opcode, Operand}};
+
+/// An instruction decoded from memory without executing it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Disassembly {
+    pub addr: u16,
+    pub opcode: Opcode,
+    pub operand: Operand,
+    /// Length in bytes, opcode included.
+    pub len: usize,
+    /// Base cycle count, without the extra cycles for page crossing, taken branches or decimal
+    /// mode, which depend on the CPU state.
+    pub cycles: usize,
+}
+
+impl Disassembly {
+    /// Address of the instruction that follows this one in memory.
+    pub fn next_addr(&self) -> u16 {
+        self.addr.wrapping_add(self.len as u16)
+    }
+}
+
+impl fmt::Display for Disassembly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} {}", self.opcode, self.operand)
+    }
+}
+
+/// Decode the instruction at the given address. Memory is only read, so this is safe to call on
+/// a running system, e.g. to show the next instruction before stepping over it.
+pub fn disassemble<B: Bus>(bus: &B, variant: Variant, addr: u16) -> Result<Disassembly, ExecError> {
+    let opcode = bus.mem_read(addr);
+    let decoded = inst::lookup::<B>(variant, opcode)
+        .ok_or_else(|| ExecError::new(ExecErrorKind::InvalidOpcode, addr, &[opcode]))?;
+    let operand = decoded.address_mode.peek(bus, addr);
+    Ok(Disassembly {
+        addr,
+        opcode: decoded.opcode,
+        operand,
+        len: operand.len() + 1,
+        cycles: decoded.cycles,
+    })
+}
+
+/// Decode consecutive instructions, from the one at `start` to the one that contains `end`.
+pub fn disassemble_range<B: Bus>(bus: &B, variant: Variant, start: u16, end: u16) -> Result<Vec<Disassembly>, ExecError> {
+    let mut result = Vec::new();
+    let mut addr = start as u32;
+    while addr <= end as u32 {
+        let inst = disassemble(bus, variant, addr as u16)?;
+        addr += inst.len as u32;
+        result.push(inst);
+    }
+    Ok(result)
+}
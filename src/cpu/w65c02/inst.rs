@@ -1,10 +1,34 @@
-use crate::cpu::w65c02::{cpu::{Flags, VECTOR_IRQ}, Bus, CPU};
+use std::marker::PhantomData;
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+use crate::cpu::{ExecError, ExecErrorKind};
+use crate::cpu::w65c02::{cpu::{Flags, VECTOR_IRQ}, Bus, CPU, FakeBus, RunState, Variant};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Opcode {
     ADC,
+    AHX,    // Unstable, undocumented NMOS
+    ALR,    // Undocumented NMOS
+    ANC,    // Undocumented NMOS
     AND,
+    ARR,    // Undocumented NMOS
     ASL,
+    AXS,    // Undocumented NMOS
+    BBR0,
+    BBR1,
+    BBR2,
+    BBR3,
+    BBR4,
+    BBR5,
+    BBR6,
+    BBR7,
+    BBS0,
+    BBS1,
+    BBS2,
+    BBS3,
+    BBS4,
+    BBS5,
+    BBS6,
+    BBS7,
     BCC,
     BCS,
     BEQ,
@@ -12,6 +36,7 @@ pub enum Opcode {
     BMI,
     BNE,
     BPL,
+    BRA,
     BRK,
     BVC,
     BVS,
@@ -22,6 +47,7 @@ pub enum Opcode {
     CMP,
     CPX,
     CPY,
+    DCP,    // Undocumented NMOS
     DEC,
     DEX,
     DEY,
@@ -29,35 +55,85 @@ pub enum Opcode {
     INC,
     INX,
     INY,
+    IRQ,    // Hardware interrupt request, never decoded from memory
+    ISC,    // Undocumented NMOS
+    JAM,    // Undocumented NMOS
     JMP,
     JSR,
+    LAS,    // Undocumented NMOS
+    LAX,    // Undocumented NMOS
     LDA,
     LDX,
     LDY,
     LSR,
+    LXA,    // Unstable, undocumented NMOS
+    NMI,    // Non-maskable interrupt, never decoded from memory
     NOP,
     ORA,
     PHA,
     PHP,
+    PHX,
+    PHY,
     PLA,
     PLP,
+    PLX,
+    PLY,
+    RESET,  // Reset sequence, never decoded from memory
+    RLA,    // Undocumented NMOS
+    RMB0,
+    RMB1,
+    RMB2,
+    RMB3,
+    RMB4,
+    RMB5,
+    RMB6,
+    RMB7,
     ROL,
     ROR,
+    RRA,    // Undocumented NMOS
     RTI,
     RTS,
+    SAX,    // Undocumented NMOS
     SBC,
     SEC,
     SED,
     SEI,
+    SHX,    // Unstable, undocumented NMOS
+    SHY,    // Unstable, undocumented NMOS
+    SLO,    // Undocumented NMOS
+    SMB0,
+    SMB1,
+    SMB2,
+    SMB3,
+    SMB4,
+    SMB5,
+    SMB6,
+    SMB7,
+    SRE,    // Undocumented NMOS
     STA,
+    STP,
     STX,
     STY,
+    STZ,
+    TAS,    // Unstable, undocumented NMOS
     TAX,
     TAY,
+    TRB,
+    TSB,
     TSX,
     TXA,
     TXS,
     TYA,
+    WAI,
+    XAA,    // Unstable, undocumented NMOS
+}
+
+impl Opcode {
+    /// Whether this is one of the undocumented NMOS instructions whose result depends on the
+    /// analog behaviour of the chip, and therefore differs between individual parts.
+    pub fn is_unstable(&self) -> bool {
+        matches!(self, Opcode::AHX | Opcode::LXA | Opcode::SHX | Opcode::SHY | Opcode::TAS | Opcode::XAA)
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -70,9 +146,12 @@ pub enum Operand {
     AbsoluteX(u16),
     AbsoluteY(u16),
     Indirect(u16),
+    AbsoluteIndirectX(u16),
     IndirectX(u8),
     IndirectY(u8),
+    ZeroPageIndirect(u8),
     Relative(u16),
+    ZeroPageRelative(u8, u16),
     Accumulator,
     Implied,
 }
@@ -88,9 +167,12 @@ impl std::fmt::Display for Operand {
             Operand::AbsoluteX(addr) => write!(f, "${:04X},X", addr),
             Operand::AbsoluteY(addr) => write!(f, "${:04X},Y", addr),
             Operand::Indirect(addr) => write!(f, "(${:04X})", addr),
+            Operand::AbsoluteIndirectX(addr) => write!(f, "(${:04X},X)", addr),
             Operand::IndirectX(addr) => write!(f, "(${:02X},X)", addr),
             Operand::IndirectY(addr) => write!(f, "(${:02X}),Y", addr),
+            Operand::ZeroPageIndirect(addr) => write!(f, "(${:02X})", addr),
             Operand::Relative(addr) => write!(f, "${:04X}", addr),
+            Operand::ZeroPageRelative(addr, target) => write!(f, "${:02X},${:04X}", addr, target),
             Operand::Accumulator => write!(f, "A"),
             Operand::Implied => write!(f, ""),
         }
@@ -99,76 +181,159 @@ impl std::fmt::Display for Operand {
 
 impl Operand {
     pub fn read(&self, cpu: &mut CPU, bus: &mut impl Bus) -> u8 {
+        self.read_indexed(cpu, bus).0
+    }
+
+    /// Read the operand, also telling whether indexing crossed a page, which costs read
+    /// instructions an extra cycle.
+    pub fn read_indexed(&self, cpu: &mut CPU, bus: &mut impl Bus) -> (u8, bool) {
         match self {
-            Operand::Immediate(value) => *value,
-            Operand::ZeroPage(addr) => bus.mem_read(*addr as u16),
-            Operand::ZeroPageX(addr) => bus.mem_read(addr.wrapping_add(cpu.x) as u16),
-            Operand::ZeroPageY(addr) => bus.mem_read(addr.wrapping_add(cpu.y) as u16),
-            Operand::Absolute(addr) => bus.mem_read(*addr),
-            Operand::AbsoluteX(addr) => bus.mem_read(addr.wrapping_add(cpu.x as u16)),
-            Operand::AbsoluteY(addr) => bus.mem_read(addr.wrapping_add(cpu.y as u16)),
-            Operand::Indirect(addr) => {
-                let ptr = bus.mem_read_word(*addr);
-                bus.mem_read(ptr)
+            Operand::Immediate(value) => (*value, false),
+            Operand::Accumulator => (cpu.a, false),
+            Operand::Implied => panic!("read of implied operand"),
+            _ => {
+                let addr = self.address(cpu, bus, false);
+                (cpu.read(bus, addr), self.page_crossed(cpu, addr))
             },
-            Operand::IndirectX(addr) => {
-                let ptr = bus.mem_read_word((*addr as u16).wrapping_add(cpu.x as u16));
-                bus.mem_read(ptr)
+        }
+    }
+
+    pub fn write(&self, cpu: &mut CPU, bus: &mut impl Bus, val: u8) {
+        match self {
+            Operand::Accumulator => cpu.a = val,
+            Operand::Immediate(_) | Operand::Relative(_) | Operand::AbsoluteIndirectX(_) | Operand::Implied => {
+                panic!("write of invalid operand")
             },
-            Operand::IndirectY(addr) => {
-                let ptr = bus.mem_read_word(*addr as u16);
-                bus.mem_read(ptr.wrapping_add(cpu.y as u16))
+            _ => {
+                let addr = self.address(cpu, bus, true);
+                cpu.write(bus, addr, val)
             },
-            Operand::Relative(addr) => bus.mem_read(*addr),
-            Operand::Accumulator => cpu.a,
-            Operand::Implied => panic!("read of implied operand"),
         }
     }
 
-    pub fn write(&self, cpu: &mut CPU, bus: &mut impl Bus, val: u8) {
+    /// Read the operand, apply the operation and write the result back, which is returned. The
+    /// hardware spends a cycle in between, reading the operand again on the 65C02 and writing it
+    /// back unmodified on the NMOS 6502.
+    pub fn modify(&self, cpu: &mut CPU, bus: &mut impl Bus, op: impl FnOnce(&mut CPU, u8) -> u8) -> u8 {
+        self.modify_indexed(cpu, bus, true, op).0
+    }
+
+    /// Like `modify`, for shifts and rotates, also telling whether indexing crossed a page. The
+    /// 65C02 only spends their indexing cycle on page crossing, like reads do.
+    pub fn modify_shift(&self, cpu: &mut CPU, bus: &mut impl Bus, op: impl FnOnce(&mut CPU, u8) -> u8) -> (u8, bool) {
+        let always_index = !cpu.variant().is_cmos();
+        self.modify_indexed(cpu, bus, always_index, op)
+    }
+
+    fn modify_indexed(&self, cpu: &mut CPU, bus: &mut impl Bus, always_index: bool, op: impl FnOnce(&mut CPU, u8) -> u8) -> (u8, bool) {
+        if let Operand::Accumulator = self {
+            let result = op(cpu, cpu.a);
+            cpu.a = result;
+            return (result, false);
+        }
+        let addr = self.address(cpu, bus, always_index);
+        let value = cpu.read(bus, addr);
+        if cpu.variant().is_cmos() {
+            cpu.dummy_read(bus, addr);
+        } else {
+            cpu.dummy_write(bus, addr, value);
+        }
+        let result = op(cpu, value);
+        cpu.write(bus, addr, result);
+        (result, self.page_crossed(cpu, addr))
+    }
+
+    /// Effective address of a memory operand, reading any pointer from the bus. Stores and
+    /// read-modify-write instructions always spend the indexing cycle, reads only on page crossing.
+    fn address(&self, cpu: &mut CPU, bus: &mut impl Bus, write: bool) -> u16 {
         match self {
-            Operand::ZeroPage(addr) => bus.mem_write(*addr as u16, val),
-            Operand::ZeroPageX(addr) => bus.mem_write(addr.wrapping_add(cpu.x) as u16, val),
-            Operand::ZeroPageY(addr) => bus.mem_write(addr.wrapping_add(cpu.y) as u16, val),
-            Operand::Absolute(addr) => bus.mem_write(*addr, val),
-            Operand::AbsoluteX(addr) => bus.mem_write(addr.wrapping_add(cpu.x as u16), val),
-            Operand::AbsoluteY(addr) => bus.mem_write(addr.wrapping_add(cpu.y as u16), val),
-            Operand::Indirect(addr) => {
-                let ptr = bus.mem_read_word(*addr);
-                bus.mem_write(ptr, val)
+            Operand::ZeroPage(addr) => *addr as u16,
+            Operand::ZeroPageX(addr) => {
+                index_dummy_read(cpu, bus, *addr as u16);
+                addr.wrapping_add(cpu.x) as u16
+            },
+            Operand::ZeroPageY(addr) => {
+                index_dummy_read(cpu, bus, *addr as u16);
+                addr.wrapping_add(cpu.y) as u16
+            },
+            Operand::Absolute(addr) => *addr,
+            Operand::AbsoluteX(addr) => index_address(cpu, bus, *addr, cpu.x, write),
+            Operand::AbsoluteY(addr) => index_address(cpu, bus, *addr, cpu.y, write),
+            Operand::Indirect(addr) => cpu.read_word(bus, *addr),
+            Operand::AbsoluteIndirectX(addr) => {
+                index_dummy_read(cpu, bus, *addr);
+                cpu.read_word(bus, addr.wrapping_add(cpu.x as u16))
             },
             Operand::IndirectX(addr) => {
-                let ptr = bus.mem_read_word((*addr as u16).wrapping_add(cpu.x as u16));
-                bus.mem_write(ptr, val)
+                index_dummy_read(cpu, bus, *addr as u16);
+                cpu.read_word_page_wrap(bus, addr.wrapping_add(cpu.x) as u16)
             },
             Operand::IndirectY(addr) => {
-                let ptr = bus.mem_read_word(*addr as u16);
-                bus.mem_write(ptr.wrapping_add(cpu.y as u16), val)
+                let ptr = cpu.read_word_page_wrap(bus, *addr as u16);
+                index_address(cpu, bus, ptr, cpu.y, write)
+            },
+            Operand::ZeroPageIndirect(addr) => cpu.read_word_page_wrap(bus, *addr as u16),
+            Operand::Relative(addr) => *addr,
+            Operand::ZeroPageRelative(addr, _) => *addr as u16,
+            Operand::Immediate(_) | Operand::Accumulator | Operand::Implied => {
+                panic!("address of non-memory operand")
             },
-            Operand::Accumulator => cpu.a = val,
-            _ => panic!("write of invalid operand"),
         }
     }
 
+    /// Whether the effective address of an indexed operand lies in a different page than its
+    /// base address, found back from the index so the pointer of `(zp),Y` is not read again.
+    fn page_crossed(&self, cpu: &CPU, addr: u16) -> bool {
+        let index = match self {
+            Operand::AbsoluteX(_) => cpu.x,
+            Operand::AbsoluteY(_) | Operand::IndirectY(_) => cpu.y,
+            _ => return false,
+        };
+        let base = addr.wrapping_sub(index as u16);
+        (base & 0xFF00) != (addr & 0xFF00)
+    }
+
+    /// Number of bytes the operand takes after the opcode.
     pub fn len(&self) -> usize {
         match self {
-            Operand::Immediate(_) => 2,
-            Operand::ZeroPage(_) => 2,
-            Operand::ZeroPageX(_) => 2,
-            Operand::ZeroPageY(_) => 2,
-            Operand::Absolute(_) => 3,
-            Operand::AbsoluteX(_) => 3,
-            Operand::AbsoluteY(_) => 3,
-            Operand::Indirect(_) => 3,
-            Operand::IndirectX(_) => 2,
-            Operand::IndirectY(_) => 2,
-            Operand::Relative(_) => 2,
+            Operand::Immediate(_) => 1,
+            Operand::ZeroPage(_) => 1,
+            Operand::ZeroPageX(_) => 1,
+            Operand::ZeroPageY(_) => 1,
+            Operand::Absolute(_) => 2,
+            Operand::AbsoluteX(_) => 2,
+            Operand::AbsoluteY(_) => 2,
+            Operand::Indirect(_) => 2,
+            Operand::AbsoluteIndirectX(_) => 2,
+            Operand::IndirectX(_) => 1,
+            Operand::IndirectY(_) => 1,
+            Operand::ZeroPageIndirect(_) => 1,
+            Operand::Relative(_) => 1,
+            Operand::ZeroPageRelative(_, _) => 2,
             Operand::Accumulator => 0,
             Operand::Implied => 0,
         }
     }
 }
 
+/// The dummy read performed while the index is added to a base address. The 65C02 reads the
+/// last operand byte again, whereas the NMOS 6502 reads the base address itself.
+fn index_dummy_read(cpu: &mut CPU, bus: &mut impl Bus, base: u16) {
+    let addr = if cpu.variant().is_cmos() { cpu.pc.wrapping_sub(1) } else { base };
+    cpu.dummy_read(bus, addr);
+}
+
+/// Add the index to a base address. Fixing the high byte after crossing a page costs a cycle,
+/// in which the NMOS 6502 reads from the address before the fix.
+fn index_address(cpu: &mut CPU, bus: &mut impl Bus, base: u16, index: u8, write: bool) -> u16 {
+    let addr = base.wrapping_add(index as u16);
+    if write || (base & 0xFF00) != (addr & 0xFF00) {
+        index_dummy_read(cpu, bus, (base & 0xFF00) | (addr & 0x00FF));
+    }
+    addr
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Instruction {
     pub opcode: Opcode,
     pub operand: Operand,
@@ -187,7 +352,7 @@ impl Instruction {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Mode {
     Absolute,       // $4400
     AbsoluteX,      // $4400,X
@@ -196,10 +361,13 @@ pub enum Mode {
     Immediate,      // #$44
     Implied,        //
     Indirect,       // ($4400)
+    AbsoluteIndirectX, // ($4400,X)
     IndirectX,      // ($44,X)
     IndirectY,      // ($44),Y
     Relative,       // 
     ZeroPage,       // $44
+    ZeroPageIndirect, // ($44)
+    ZeroPageRelative, // $44,$4400
     ZeroPageX,      // $44,X
     ZeroPageY,      // $44,Y
 }
@@ -215,12 +383,50 @@ impl Mode {
             Mode::AbsoluteX => Operand::AbsoluteX(cpu.fetch_word(bus)),
             Mode::AbsoluteY => Operand::AbsoluteY(cpu.fetch_word(bus)),
             Mode::Indirect => Operand::Indirect(cpu.fetch_word(bus)),
+            Mode::AbsoluteIndirectX => Operand::AbsoluteIndirectX(cpu.fetch_word(bus)),
             Mode::IndirectX => Operand::IndirectX(cpu.fetch_byte(bus)),
             Mode::IndirectY => Operand::IndirectY(cpu.fetch_byte(bus)),
+            Mode::ZeroPageIndirect => Operand::ZeroPageIndirect(cpu.fetch_byte(bus)),
             Mode::Relative => {
                 let offset = cpu.fetch_byte(bus) as i8;
                 Operand::Relative(cpu.pc.wrapping_add(offset as u16))
             },
+            Mode::ZeroPageRelative => {
+                let addr = cpu.fetch_byte(bus);
+                let offset = cpu.fetch_byte(bus) as i8;
+                Operand::ZeroPageRelative(addr, cpu.pc.wrapping_add(offset as u16))
+            },
+            Mode::Accumulator => Operand::Accumulator,
+            Mode::Implied => Operand::Implied,
+        }
+    }
+
+    /// Read the operand of the instruction at the given address, like `fetch` does but without
+    /// a CPU and without any bus cycles.
+    pub fn peek(&self, bus: &impl Bus, addr: u16) -> Operand {
+        let byte = |offset: u16| bus.mem_read(addr.wrapping_add(offset));
+        let word = |offset: u16| (byte(offset + 1) as u16) << 8 | byte(offset) as u16;
+        match self {
+            Mode::Immediate => Operand::Immediate(byte(1)),
+            Mode::ZeroPage => Operand::ZeroPage(byte(1)),
+            Mode::ZeroPageX => Operand::ZeroPageX(byte(1)),
+            Mode::ZeroPageY => Operand::ZeroPageY(byte(1)),
+            Mode::Absolute => Operand::Absolute(word(1)),
+            Mode::AbsoluteX => Operand::AbsoluteX(word(1)),
+            Mode::AbsoluteY => Operand::AbsoluteY(word(1)),
+            Mode::Indirect => Operand::Indirect(word(1)),
+            Mode::AbsoluteIndirectX => Operand::AbsoluteIndirectX(word(1)),
+            Mode::IndirectX => Operand::IndirectX(byte(1)),
+            Mode::IndirectY => Operand::IndirectY(byte(1)),
+            Mode::ZeroPageIndirect => Operand::ZeroPageIndirect(byte(1)),
+            Mode::Relative => {
+                let offset = byte(1) as i8;
+                Operand::Relative(addr.wrapping_add(2).wrapping_add(offset as u16))
+            },
+            Mode::ZeroPageRelative => {
+                let offset = byte(2) as i8;
+                Operand::ZeroPageRelative(byte(1), addr.wrapping_add(3).wrapping_add(offset as u16))
+            },
             Mode::Accumulator => Operand::Accumulator,
             Mode::Implied => Operand::Implied,
         }
@@ -239,8 +445,84 @@ pub struct Decoded<B: Bus> {
     pub handler: Handler<B>,
 }
 
-pub fn decode<B: Bus>(cpu: &mut CPU, bus: &mut B) -> Decoded<B> {
-    match cpu.fetch_byte(bus) {
+impl<B: Bus> Clone for Decoded<B> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<B: Bus> Copy for Decoded<B> {}
+
+/// Decoded form of every opcode for a variant, or `None` where the opcode is undefined.
+type Table<B> = [Option<Decoded<B>>; 256];
+
+/// Decoding tables of each variant, built at compile time for every bus type.
+struct Tables<B: Bus>(PhantomData<B>);
+
+impl<B: Bus> Tables<B> {
+    const NMOS6502: Table<B> = build_table(Variant::NMOS6502);
+    const W65C02: Table<B> = build_table(Variant::W65C02);
+    const R65C02: Table<B> = build_table(Variant::R65C02);
+    const G65SC02: Table<B> = build_table(Variant::G65SC02);
+}
+
+const fn build_table<B: Bus>(variant: Variant) -> Table<B> {
+    let mut table = [None; 256];
+    let mut opcode = 0;
+    while opcode < 256 {
+        table[opcode] = decode_opcode(opcode as u8, variant);
+        opcode += 1;
+    }
+    table
+}
+
+pub fn decode<B: Bus>(cpu: &mut CPU, bus: &mut B) -> Result<Decoded<B>, ExecError> {
+    let opcode = cpu.fetch_opcode(bus);
+    #[cfg(feature = "match-decoder")]
+    let entry = if cpu.match_decoder() {
+        decode_opcode(opcode, cpu.variant())
+    } else {
+        lookup(cpu.variant(), opcode)
+    };
+    #[cfg(not(feature = "match-decoder"))]
+    let entry = lookup(cpu.variant(), opcode);
+    let decoded = match entry {
+        Some(decoded) => decoded,
+        None => {
+            let pc = cpu.pc.wrapping_sub(1);
+            return Err(ExecError::new(ExecErrorKind::InvalidOpcode, pc, &[opcode]));
+        },
+    };
+
+    // Single byte instructions still read the next byte in their second cycle, then discard it
+    if matches!(decoded.address_mode, Mode::Implied | Mode::Accumulator) && decoded.cycles > 1 {
+        cpu.dummy_read(bus, cpu.pc);
+    }
+    Ok(decoded)
+}
+
+/// Decoded form of the opcode for the given variant, or `None` if it is undefined.
+pub fn lookup<B: Bus>(variant: Variant, opcode: u8) -> Option<Decoded<B>> {
+    match variant {
+        Variant::NMOS6502 => Tables::<B>::NMOS6502[opcode as usize],
+        Variant::W65C02 => Tables::<B>::W65C02[opcode as usize],
+        Variant::R65C02 => Tables::<B>::R65C02[opcode as usize],
+        Variant::G65SC02 => Tables::<B>::G65SC02[opcode as usize],
+    }
+}
+
+/// Mnemonic and addressing mode of the opcode for the given variant, or `None` if it is undefined.
+pub fn describe(variant: Variant, opcode: u8) -> Option<(Opcode, Mode)> {
+    lookup::<FakeBus>(variant, opcode).map(|decoded| (decoded.opcode, decoded.address_mode))
+}
+
+const fn decode_opcode<B: Bus>(opcode: u8, variant: Variant) -> Option<Decoded<B>> {
+    let cmos = variant.is_cmos();
+    let nmos = !cmos;
+    let bit_ops = variant.has_bit_ops();
+    let wai_stp = variant.has_wai_stp();
+
+    let decoded = match opcode {
         // ADC
         0x69 => Decoded { opcode: Opcode::ADC, cycles: 2, address_mode: Mode::Immediate, handler: handle_adc },
         0x65 => Decoded { opcode: Opcode::ADC, cycles: 3, address_mode: Mode::ZeroPage, handler: handle_adc },
@@ -250,6 +532,7 @@ pub fn decode<B: Bus>(cpu: &mut CPU, bus: &mut B) -> Decoded<B> {
         0x79 => Decoded { opcode: Opcode::ADC, cycles: 4, address_mode: Mode::AbsoluteY, handler: handle_adc },
         0x61 => Decoded { opcode: Opcode::ADC, cycles: 6, address_mode: Mode::IndirectX, handler: handle_adc },
         0x71 => Decoded { opcode: Opcode::ADC, cycles: 5, address_mode: Mode::IndirectY, handler: handle_adc },
+        0x72 if cmos => Decoded { opcode: Opcode::ADC, cycles: 5, address_mode: Mode::ZeroPageIndirect, handler: handle_adc },
 
         // AND
         0x29 => Decoded { opcode: Opcode::AND, cycles: 2, address_mode: Mode::Immediate, handler: handle_and },
@@ -260,13 +543,15 @@ pub fn decode<B: Bus>(cpu: &mut CPU, bus: &mut B) -> Decoded<B> {
         0x39 => Decoded { opcode: Opcode::AND, cycles: 4, address_mode: Mode::AbsoluteY, handler: handle_and },
         0x21 => Decoded { opcode: Opcode::AND, cycles: 6, address_mode: Mode::IndirectX, handler: handle_and },
         0x31 => Decoded { opcode: Opcode::AND, cycles: 5, address_mode: Mode::IndirectY, handler: handle_and },
+        0x32 if cmos => Decoded { opcode: Opcode::AND, cycles: 5, address_mode: Mode::ZeroPageIndirect, handler: handle_and },
 
         // ASL
         0x0A => Decoded { opcode: Opcode::ASL, cycles: 2, address_mode: Mode::Accumulator, handler: handle_asl },
         0x06 => Decoded { opcode: Opcode::ASL, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_asl },
         0x16 => Decoded { opcode: Opcode::ASL, cycles: 6, address_mode: Mode::ZeroPageX, handler: handle_asl },
         0x0E => Decoded { opcode: Opcode::ASL, cycles: 6, address_mode: Mode::Absolute, handler: handle_asl },
-        0x1E => Decoded { opcode: Opcode::ASL, cycles: 7, address_mode: Mode::AbsoluteX, handler: handle_asl },
+        0x1E if cmos => Decoded { opcode: Opcode::ASL, cycles: 6, address_mode: Mode::AbsoluteX, handler: handle_asl },
+        0x1E if nmos => Decoded { opcode: Opcode::ASL, cycles: 7, address_mode: Mode::AbsoluteX, handler: handle_asl },
 
         // Branch instructions
         0x90 => Decoded { opcode: Opcode::BCC, cycles: 2, address_mode: Mode::Relative, handler: handle_bcc },
@@ -277,10 +562,32 @@ pub fn decode<B: Bus>(cpu: &mut CPU, bus: &mut B) -> Decoded<B> {
         0x10 => Decoded { opcode: Opcode::BPL, cycles: 2, address_mode: Mode::Relative, handler: handle_bpl },
         0x50 => Decoded { opcode: Opcode::BVC, cycles: 2, address_mode: Mode::Relative, handler: handle_bvc },
         0x70 => Decoded { opcode: Opcode::BVS, cycles: 2, address_mode: Mode::Relative, handler: handle_bvs },
+        0x80 if cmos => Decoded { opcode: Opcode::BRA, cycles: 2, address_mode: Mode::Relative, handler: handle_bra },
+
+        // Branch on bit reset/set
+        0x0F if bit_ops => Decoded { opcode: Opcode::BBR0, cycles: 5, address_mode: Mode::ZeroPageRelative, handler: handle_bbr::<B, 0> },
+        0x1F if bit_ops => Decoded { opcode: Opcode::BBR1, cycles: 5, address_mode: Mode::ZeroPageRelative, handler: handle_bbr::<B, 1> },
+        0x2F if bit_ops => Decoded { opcode: Opcode::BBR2, cycles: 5, address_mode: Mode::ZeroPageRelative, handler: handle_bbr::<B, 2> },
+        0x3F if bit_ops => Decoded { opcode: Opcode::BBR3, cycles: 5, address_mode: Mode::ZeroPageRelative, handler: handle_bbr::<B, 3> },
+        0x4F if bit_ops => Decoded { opcode: Opcode::BBR4, cycles: 5, address_mode: Mode::ZeroPageRelative, handler: handle_bbr::<B, 4> },
+        0x5F if bit_ops => Decoded { opcode: Opcode::BBR5, cycles: 5, address_mode: Mode::ZeroPageRelative, handler: handle_bbr::<B, 5> },
+        0x6F if bit_ops => Decoded { opcode: Opcode::BBR6, cycles: 5, address_mode: Mode::ZeroPageRelative, handler: handle_bbr::<B, 6> },
+        0x7F if bit_ops => Decoded { opcode: Opcode::BBR7, cycles: 5, address_mode: Mode::ZeroPageRelative, handler: handle_bbr::<B, 7> },
+        0x8F if bit_ops => Decoded { opcode: Opcode::BBS0, cycles: 5, address_mode: Mode::ZeroPageRelative, handler: handle_bbs::<B, 0> },
+        0x9F if bit_ops => Decoded { opcode: Opcode::BBS1, cycles: 5, address_mode: Mode::ZeroPageRelative, handler: handle_bbs::<B, 1> },
+        0xAF if bit_ops => Decoded { opcode: Opcode::BBS2, cycles: 5, address_mode: Mode::ZeroPageRelative, handler: handle_bbs::<B, 2> },
+        0xBF if bit_ops => Decoded { opcode: Opcode::BBS3, cycles: 5, address_mode: Mode::ZeroPageRelative, handler: handle_bbs::<B, 3> },
+        0xCF if bit_ops => Decoded { opcode: Opcode::BBS4, cycles: 5, address_mode: Mode::ZeroPageRelative, handler: handle_bbs::<B, 4> },
+        0xDF if bit_ops => Decoded { opcode: Opcode::BBS5, cycles: 5, address_mode: Mode::ZeroPageRelative, handler: handle_bbs::<B, 5> },
+        0xEF if bit_ops => Decoded { opcode: Opcode::BBS6, cycles: 5, address_mode: Mode::ZeroPageRelative, handler: handle_bbs::<B, 6> },
+        0xFF if bit_ops => Decoded { opcode: Opcode::BBS7, cycles: 5, address_mode: Mode::ZeroPageRelative, handler: handle_bbs::<B, 7> },
 
         // BIT
         0x24 => Decoded { opcode: Opcode::BIT, cycles: 3, address_mode: Mode::ZeroPage, handler: handle_bit },
         0x2C => Decoded { opcode: Opcode::BIT, cycles: 4, address_mode: Mode::Absolute, handler: handle_bit },
+        0x89 if cmos => Decoded { opcode: Opcode::BIT, cycles: 2, address_mode: Mode::Immediate, handler: handle_bit },
+        0x34 if cmos => Decoded { opcode: Opcode::BIT, cycles: 4, address_mode: Mode::ZeroPageX, handler: handle_bit },
+        0x3C if cmos => Decoded { opcode: Opcode::BIT, cycles: 4, address_mode: Mode::AbsoluteX, handler: handle_bit },
 
         // BRK
         0x00 => Decoded { opcode: Opcode::BRK, cycles: 7, address_mode: Mode::Implied, handler: handle_brk },
@@ -300,6 +607,7 @@ pub fn decode<B: Bus>(cpu: &mut CPU, bus: &mut B) -> Decoded<B> {
         0xD9 => Decoded { opcode: Opcode::CMP, cycles: 4, address_mode: Mode::AbsoluteY, handler: handle_cmp },
         0xC1 => Decoded { opcode: Opcode::CMP, cycles: 6, address_mode: Mode::IndirectX, handler: handle_cmp },
         0xD1 => Decoded { opcode: Opcode::CMP, cycles: 5, address_mode: Mode::IndirectY, handler: handle_cmp },
+        0xD2 if cmos => Decoded { opcode: Opcode::CMP, cycles: 5, address_mode: Mode::ZeroPageIndirect, handler: handle_cmp },
 
         // CPX
         0xE0 => Decoded { opcode: Opcode::CPX, cycles: 2, address_mode: Mode::Immediate, handler: handle_cpx },
@@ -312,6 +620,7 @@ pub fn decode<B: Bus>(cpu: &mut CPU, bus: &mut B) -> Decoded<B> {
         0xCC => Decoded { opcode: Opcode::CPY, cycles: 4, address_mode: Mode::Absolute, handler: handle_cpy },
 
         // DEC
+        0x3A if cmos => Decoded { opcode: Opcode::DEC, cycles: 2, address_mode: Mode::Accumulator, handler: handle_dec },
         0xC6 => Decoded { opcode: Opcode::DEC, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_dec },
         0xD6 => Decoded { opcode: Opcode::DEC, cycles: 6, address_mode: Mode::ZeroPageX, handler: handle_dec },
         0xCE => Decoded { opcode: Opcode::DEC, cycles: 6, address_mode: Mode::Absolute, handler: handle_dec },
@@ -330,8 +639,10 @@ pub fn decode<B: Bus>(cpu: &mut CPU, bus: &mut B) -> Decoded<B> {
         0x59 => Decoded { opcode: Opcode::EOR, cycles: 4, address_mode: Mode::AbsoluteY, handler: handle_eor },
         0x41 => Decoded { opcode: Opcode::EOR, cycles: 6, address_mode: Mode::IndirectX, handler: handle_eor },
         0x51 => Decoded { opcode: Opcode::EOR, cycles: 5, address_mode: Mode::IndirectY, handler: handle_eor },
+        0x52 if cmos => Decoded { opcode: Opcode::EOR, cycles: 5, address_mode: Mode::ZeroPageIndirect, handler: handle_eor },
 
         // INC
+        0x1A if cmos => Decoded { opcode: Opcode::INC, cycles: 2, address_mode: Mode::Accumulator, handler: handle_inc },
         0xE6 => Decoded { opcode: Opcode::INC, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_inc },
         0xF6 => Decoded { opcode: Opcode::INC, cycles: 6, address_mode: Mode::ZeroPageX, handler: handle_inc },
         0xEE => Decoded { opcode: Opcode::INC, cycles: 6, address_mode: Mode::Absolute, handler: handle_inc },
@@ -343,7 +654,9 @@ pub fn decode<B: Bus>(cpu: &mut CPU, bus: &mut B) -> Decoded<B> {
 
         // JMP
         0x4C => Decoded { opcode: Opcode::JMP, cycles: 3, address_mode: Mode::Absolute, handler: handle_jmp },
+        0x6C if cmos => Decoded { opcode: Opcode::JMP, cycles: 6, address_mode: Mode::Indirect, handler: handle_jmp },
         0x6C => Decoded { opcode: Opcode::JMP, cycles: 5, address_mode: Mode::Indirect, handler: handle_jmp },
+        0x7C if cmos => Decoded { opcode: Opcode::JMP, cycles: 6, address_mode: Mode::AbsoluteIndirectX, handler: handle_jmp },
 
         // JSR
         0x20 => Decoded { opcode: Opcode::JSR, cycles: 6, address_mode: Mode::Absolute, handler: handle_jsr },
@@ -357,6 +670,7 @@ pub fn decode<B: Bus>(cpu: &mut CPU, bus: &mut B) -> Decoded<B> {
         0xB9 => Decoded { opcode: Opcode::LDA, cycles: 4, address_mode: Mode::AbsoluteY, handler: handle_lda },
         0xA1 => Decoded { opcode: Opcode::LDA, cycles: 6, address_mode: Mode::IndirectX, handler: handle_lda },
         0xB1 => Decoded { opcode: Opcode::LDA, cycles: 5, address_mode: Mode::IndirectY, handler: handle_lda },
+        0xB2 if cmos => Decoded { opcode: Opcode::LDA, cycles: 5, address_mode: Mode::ZeroPageIndirect, handler: handle_lda },
 
         // LDX
         0xA2 => Decoded { opcode: Opcode::LDX, cycles: 2, address_mode: Mode::Immediate, handler: handle_ldx },
@@ -377,11 +691,16 @@ pub fn decode<B: Bus>(cpu: &mut CPU, bus: &mut B) -> Decoded<B> {
         0x46 => Decoded { opcode: Opcode::LSR, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_lsr },
         0x56 => Decoded { opcode: Opcode::LSR, cycles: 6, address_mode: Mode::ZeroPageX, handler: handle_lsr },
         0x4E => Decoded { opcode: Opcode::LSR, cycles: 6, address_mode: Mode::Absolute, handler: handle_lsr },
-        0x5E => Decoded { opcode: Opcode::LSR, cycles: 7, address_mode: Mode::AbsoluteX, handler: handle_lsr },
+        0x5E if cmos => Decoded { opcode: Opcode::LSR, cycles: 6, address_mode: Mode::AbsoluteX, handler: handle_lsr },
+        0x5E if nmos => Decoded { opcode: Opcode::LSR, cycles: 7, address_mode: Mode::AbsoluteX, handler: handle_lsr },
 
         // NOP
         0xEA => Decoded { opcode: Opcode::NOP, cycles: 2, address_mode: Mode::Implied, handler: handle_nop },
 
+        // Wait and stop
+        0xCB if wai_stp => Decoded { opcode: Opcode::WAI, cycles: 3, address_mode: Mode::Implied, handler: handle_wai },
+        0xDB if wai_stp => Decoded { opcode: Opcode::STP, cycles: 3, address_mode: Mode::Implied, handler: handle_stp },
+
         // ORA
         0x09 => Decoded { opcode: Opcode::ORA, cycles: 2, address_mode: Mode::Immediate, handler: handle_ora },
         0x05 => Decoded { opcode: Opcode::ORA, cycles: 3, address_mode: Mode::ZeroPage, handler: handle_ora },
@@ -391,26 +710,33 @@ pub fn decode<B: Bus>(cpu: &mut CPU, bus: &mut B) -> Decoded<B> {
         0x19 => Decoded { opcode: Opcode::ORA, cycles: 4, address_mode: Mode::AbsoluteY, handler: handle_ora },
         0x01 => Decoded { opcode: Opcode::ORA, cycles: 6, address_mode: Mode::IndirectX, handler: handle_ora },
         0x11 => Decoded { opcode: Opcode::ORA, cycles: 5, address_mode: Mode::IndirectY, handler: handle_ora },
+        0x12 if cmos => Decoded { opcode: Opcode::ORA, cycles: 5, address_mode: Mode::ZeroPageIndirect, handler: handle_ora },
 
         // Stack operations
         0x48 => Decoded { opcode: Opcode::PHA, cycles: 3, address_mode: Mode::Implied, handler: handle_pha },
         0x08 => Decoded { opcode: Opcode::PHP, cycles: 3, address_mode: Mode::Implied, handler: handle_php },
         0x68 => Decoded { opcode: Opcode::PLA, cycles: 4, address_mode: Mode::Implied, handler: handle_pla },
         0x28 => Decoded { opcode: Opcode::PLP, cycles: 4, address_mode: Mode::Implied, handler: handle_plp },
+        0xDA if cmos => Decoded { opcode: Opcode::PHX, cycles: 3, address_mode: Mode::Implied, handler: handle_phx },
+        0x5A if cmos => Decoded { opcode: Opcode::PHY, cycles: 3, address_mode: Mode::Implied, handler: handle_phy },
+        0xFA if cmos => Decoded { opcode: Opcode::PLX, cycles: 4, address_mode: Mode::Implied, handler: handle_plx },
+        0x7A if cmos => Decoded { opcode: Opcode::PLY, cycles: 4, address_mode: Mode::Implied, handler: handle_ply },
 
         // ROL
         0x2A => Decoded { opcode: Opcode::ROL, cycles: 2, address_mode: Mode::Accumulator, handler: handle_rol },
         0x26 => Decoded { opcode: Opcode::ROL, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_rol },
         0x36 => Decoded { opcode: Opcode::ROL, cycles: 6, address_mode: Mode::ZeroPageX, handler: handle_rol },
         0x2E => Decoded { opcode: Opcode::ROL, cycles: 6, address_mode: Mode::Absolute, handler: handle_rol },
-        0x3E => Decoded { opcode: Opcode::ROL, cycles: 7, address_mode: Mode::AbsoluteX, handler: handle_rol },
+        0x3E if cmos => Decoded { opcode: Opcode::ROL, cycles: 6, address_mode: Mode::AbsoluteX, handler: handle_rol },
+        0x3E if nmos => Decoded { opcode: Opcode::ROL, cycles: 7, address_mode: Mode::AbsoluteX, handler: handle_rol },
 
         // ROR
         0x6A => Decoded { opcode: Opcode::ROR, cycles: 2, address_mode: Mode::Accumulator, handler: handle_ror },
         0x66 => Decoded { opcode: Opcode::ROR, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_ror },
         0x76 => Decoded { opcode: Opcode::ROR, cycles: 6, address_mode: Mode::ZeroPageX, handler: handle_ror },
         0x6E => Decoded { opcode: Opcode::ROR, cycles: 6, address_mode: Mode::Absolute, handler: handle_ror },
-        0x7E => Decoded { opcode: Opcode::ROR, cycles: 7, address_mode: Mode::AbsoluteX, handler: handle_ror },
+        0x7E if cmos => Decoded { opcode: Opcode::ROR, cycles: 6, address_mode: Mode::AbsoluteX, handler: handle_ror },
+        0x7E if nmos => Decoded { opcode: Opcode::ROR, cycles: 7, address_mode: Mode::AbsoluteX, handler: handle_ror },
 
         // RTI
         0x40 => Decoded { opcode: Opcode::RTI, cycles: 6, address_mode: Mode::Implied, handler: handle_rti },
@@ -427,6 +753,7 @@ pub fn decode<B: Bus>(cpu: &mut CPU, bus: &mut B) -> Decoded<B> {
         0xF9 => Decoded { opcode: Opcode::SBC, cycles: 4, address_mode: Mode::AbsoluteY, handler: handle_sbc },
         0xE1 => Decoded { opcode: Opcode::SBC, cycles: 6, address_mode: Mode::IndirectX, handler: handle_sbc },
         0xF1 => Decoded { opcode: Opcode::SBC, cycles: 5, address_mode: Mode::IndirectY, handler: handle_sbc },
+        0xF2 if cmos => Decoded { opcode: Opcode::SBC, cycles: 5, address_mode: Mode::ZeroPageIndirect, handler: handle_sbc },
 
         // Set flags
         0x38 => Decoded { opcode: Opcode::SEC, cycles: 2, address_mode: Mode::Implied, handler: handle_sec },
@@ -441,6 +768,7 @@ pub fn decode<B: Bus>(cpu: &mut CPU, bus: &mut B) -> Decoded<B> {
         0x99 => Decoded { opcode: Opcode::STA, cycles: 5, address_mode: Mode::AbsoluteY, handler: handle_sta },
         0x81 => Decoded { opcode: Opcode::STA, cycles: 6, address_mode: Mode::IndirectX, handler: handle_sta },
         0x91 => Decoded { opcode: Opcode::STA, cycles: 6, address_mode: Mode::IndirectY, handler: handle_sta },
+        0x92 if cmos => Decoded { opcode: Opcode::STA, cycles: 5, address_mode: Mode::ZeroPageIndirect, handler: handle_sta },
 
         0x86 => Decoded { opcode: Opcode::STX, cycles: 3, address_mode: Mode::ZeroPage, handler: handle_stx },
         0x96 => Decoded { opcode: Opcode::STX, cycles: 4, address_mode: Mode::ZeroPageY, handler: handle_stx },
@@ -450,6 +778,35 @@ pub fn decode<B: Bus>(cpu: &mut CPU, bus: &mut B) -> Decoded<B> {
         0x94 => Decoded { opcode: Opcode::STY, cycles: 4, address_mode: Mode::ZeroPageX, handler: handle_sty },
         0x8C => Decoded { opcode: Opcode::STY, cycles: 4, address_mode: Mode::Absolute, handler: handle_sty },
 
+        0x64 if cmos => Decoded { opcode: Opcode::STZ, cycles: 3, address_mode: Mode::ZeroPage, handler: handle_stz },
+        0x74 if cmos => Decoded { opcode: Opcode::STZ, cycles: 4, address_mode: Mode::ZeroPageX, handler: handle_stz },
+        0x9C if cmos => Decoded { opcode: Opcode::STZ, cycles: 4, address_mode: Mode::Absolute, handler: handle_stz },
+        0x9E if cmos => Decoded { opcode: Opcode::STZ, cycles: 5, address_mode: Mode::AbsoluteX, handler: handle_stz },
+
+        // Test and set/reset bits
+        0x14 if cmos => Decoded { opcode: Opcode::TRB, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_trb },
+        0x1C if cmos => Decoded { opcode: Opcode::TRB, cycles: 6, address_mode: Mode::Absolute, handler: handle_trb },
+        0x04 if cmos => Decoded { opcode: Opcode::TSB, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_tsb },
+        0x0C if cmos => Decoded { opcode: Opcode::TSB, cycles: 6, address_mode: Mode::Absolute, handler: handle_tsb },
+
+        // Reset and set memory bits
+        0x07 if bit_ops => Decoded { opcode: Opcode::RMB0, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_rmb::<B, 0> },
+        0x17 if bit_ops => Decoded { opcode: Opcode::RMB1, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_rmb::<B, 1> },
+        0x27 if bit_ops => Decoded { opcode: Opcode::RMB2, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_rmb::<B, 2> },
+        0x37 if bit_ops => Decoded { opcode: Opcode::RMB3, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_rmb::<B, 3> },
+        0x47 if bit_ops => Decoded { opcode: Opcode::RMB4, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_rmb::<B, 4> },
+        0x57 if bit_ops => Decoded { opcode: Opcode::RMB5, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_rmb::<B, 5> },
+        0x67 if bit_ops => Decoded { opcode: Opcode::RMB6, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_rmb::<B, 6> },
+        0x77 if bit_ops => Decoded { opcode: Opcode::RMB7, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_rmb::<B, 7> },
+        0x87 if bit_ops => Decoded { opcode: Opcode::SMB0, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_smb::<B, 0> },
+        0x97 if bit_ops => Decoded { opcode: Opcode::SMB1, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_smb::<B, 1> },
+        0xA7 if bit_ops => Decoded { opcode: Opcode::SMB2, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_smb::<B, 2> },
+        0xB7 if bit_ops => Decoded { opcode: Opcode::SMB3, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_smb::<B, 3> },
+        0xC7 if bit_ops => Decoded { opcode: Opcode::SMB4, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_smb::<B, 4> },
+        0xD7 if bit_ops => Decoded { opcode: Opcode::SMB5, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_smb::<B, 5> },
+        0xE7 if bit_ops => Decoded { opcode: Opcode::SMB6, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_smb::<B, 6> },
+        0xF7 if bit_ops => Decoded { opcode: Opcode::SMB7, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_smb::<B, 7> },
+
         // Transfer instructions
         0xAA => Decoded { opcode: Opcode::TAX, cycles: 2, address_mode: Mode::Implied, handler: handle_tax },
         0xA8 => Decoded { opcode: Opcode::TAY, cycles: 2, address_mode: Mode::Implied, handler: handle_tay },
@@ -458,57 +815,259 @@ pub fn decode<B: Bus>(cpu: &mut CPU, bus: &mut B) -> Decoded<B> {
         0x9A => Decoded { opcode: Opcode::TXS, cycles: 2, address_mode: Mode::Implied, handler: handle_txs },
         0x98 => Decoded { opcode: Opcode::TYA, cycles: 2, address_mode: Mode::Implied, handler: handle_tya },
 
-        _ => panic!("Invalid opcode: {:02X}", cpu.pc - 1),
-    }
+        // Undocumented NMOS instructions
+        0x07 if nmos => Decoded { opcode: Opcode::SLO, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_slo },
+        0x17 if nmos => Decoded { opcode: Opcode::SLO, cycles: 6, address_mode: Mode::ZeroPageX, handler: handle_slo },
+        0x0F if nmos => Decoded { opcode: Opcode::SLO, cycles: 6, address_mode: Mode::Absolute, handler: handle_slo },
+        0x1F if nmos => Decoded { opcode: Opcode::SLO, cycles: 7, address_mode: Mode::AbsoluteX, handler: handle_slo },
+        0x1B if nmos => Decoded { opcode: Opcode::SLO, cycles: 7, address_mode: Mode::AbsoluteY, handler: handle_slo },
+        0x03 if nmos => Decoded { opcode: Opcode::SLO, cycles: 8, address_mode: Mode::IndirectX, handler: handle_slo },
+        0x13 if nmos => Decoded { opcode: Opcode::SLO, cycles: 8, address_mode: Mode::IndirectY, handler: handle_slo },
+
+        0x27 if nmos => Decoded { opcode: Opcode::RLA, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_rla },
+        0x37 if nmos => Decoded { opcode: Opcode::RLA, cycles: 6, address_mode: Mode::ZeroPageX, handler: handle_rla },
+        0x2F if nmos => Decoded { opcode: Opcode::RLA, cycles: 6, address_mode: Mode::Absolute, handler: handle_rla },
+        0x3F if nmos => Decoded { opcode: Opcode::RLA, cycles: 7, address_mode: Mode::AbsoluteX, handler: handle_rla },
+        0x3B if nmos => Decoded { opcode: Opcode::RLA, cycles: 7, address_mode: Mode::AbsoluteY, handler: handle_rla },
+        0x23 if nmos => Decoded { opcode: Opcode::RLA, cycles: 8, address_mode: Mode::IndirectX, handler: handle_rla },
+        0x33 if nmos => Decoded { opcode: Opcode::RLA, cycles: 8, address_mode: Mode::IndirectY, handler: handle_rla },
+
+        0x47 if nmos => Decoded { opcode: Opcode::SRE, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_sre },
+        0x57 if nmos => Decoded { opcode: Opcode::SRE, cycles: 6, address_mode: Mode::ZeroPageX, handler: handle_sre },
+        0x4F if nmos => Decoded { opcode: Opcode::SRE, cycles: 6, address_mode: Mode::Absolute, handler: handle_sre },
+        0x5F if nmos => Decoded { opcode: Opcode::SRE, cycles: 7, address_mode: Mode::AbsoluteX, handler: handle_sre },
+        0x5B if nmos => Decoded { opcode: Opcode::SRE, cycles: 7, address_mode: Mode::AbsoluteY, handler: handle_sre },
+        0x43 if nmos => Decoded { opcode: Opcode::SRE, cycles: 8, address_mode: Mode::IndirectX, handler: handle_sre },
+        0x53 if nmos => Decoded { opcode: Opcode::SRE, cycles: 8, address_mode: Mode::IndirectY, handler: handle_sre },
+
+        0x67 if nmos => Decoded { opcode: Opcode::RRA, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_rra },
+        0x77 if nmos => Decoded { opcode: Opcode::RRA, cycles: 6, address_mode: Mode::ZeroPageX, handler: handle_rra },
+        0x6F if nmos => Decoded { opcode: Opcode::RRA, cycles: 6, address_mode: Mode::Absolute, handler: handle_rra },
+        0x7F if nmos => Decoded { opcode: Opcode::RRA, cycles: 7, address_mode: Mode::AbsoluteX, handler: handle_rra },
+        0x7B if nmos => Decoded { opcode: Opcode::RRA, cycles: 7, address_mode: Mode::AbsoluteY, handler: handle_rra },
+        0x63 if nmos => Decoded { opcode: Opcode::RRA, cycles: 8, address_mode: Mode::IndirectX, handler: handle_rra },
+        0x73 if nmos => Decoded { opcode: Opcode::RRA, cycles: 8, address_mode: Mode::IndirectY, handler: handle_rra },
+
+        0xC7 if nmos => Decoded { opcode: Opcode::DCP, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_dcp },
+        0xD7 if nmos => Decoded { opcode: Opcode::DCP, cycles: 6, address_mode: Mode::ZeroPageX, handler: handle_dcp },
+        0xCF if nmos => Decoded { opcode: Opcode::DCP, cycles: 6, address_mode: Mode::Absolute, handler: handle_dcp },
+        0xDF if nmos => Decoded { opcode: Opcode::DCP, cycles: 7, address_mode: Mode::AbsoluteX, handler: handle_dcp },
+        0xDB if nmos => Decoded { opcode: Opcode::DCP, cycles: 7, address_mode: Mode::AbsoluteY, handler: handle_dcp },
+        0xC3 if nmos => Decoded { opcode: Opcode::DCP, cycles: 8, address_mode: Mode::IndirectX, handler: handle_dcp },
+        0xD3 if nmos => Decoded { opcode: Opcode::DCP, cycles: 8, address_mode: Mode::IndirectY, handler: handle_dcp },
+
+        0xE7 if nmos => Decoded { opcode: Opcode::ISC, cycles: 5, address_mode: Mode::ZeroPage, handler: handle_isc },
+        0xF7 if nmos => Decoded { opcode: Opcode::ISC, cycles: 6, address_mode: Mode::ZeroPageX, handler: handle_isc },
+        0xEF if nmos => Decoded { opcode: Opcode::ISC, cycles: 6, address_mode: Mode::Absolute, handler: handle_isc },
+        0xFF if nmos => Decoded { opcode: Opcode::ISC, cycles: 7, address_mode: Mode::AbsoluteX, handler: handle_isc },
+        0xFB if nmos => Decoded { opcode: Opcode::ISC, cycles: 7, address_mode: Mode::AbsoluteY, handler: handle_isc },
+        0xE3 if nmos => Decoded { opcode: Opcode::ISC, cycles: 8, address_mode: Mode::IndirectX, handler: handle_isc },
+        0xF3 if nmos => Decoded { opcode: Opcode::ISC, cycles: 8, address_mode: Mode::IndirectY, handler: handle_isc },
+
+        0x87 if nmos => Decoded { opcode: Opcode::SAX, cycles: 3, address_mode: Mode::ZeroPage, handler: handle_sax },
+        0x97 if nmos => Decoded { opcode: Opcode::SAX, cycles: 4, address_mode: Mode::ZeroPageY, handler: handle_sax },
+        0x8F if nmos => Decoded { opcode: Opcode::SAX, cycles: 4, address_mode: Mode::Absolute, handler: handle_sax },
+        0x83 if nmos => Decoded { opcode: Opcode::SAX, cycles: 6, address_mode: Mode::IndirectX, handler: handle_sax },
+
+        0xA7 if nmos => Decoded { opcode: Opcode::LAX, cycles: 3, address_mode: Mode::ZeroPage, handler: handle_lax },
+        0xB7 if nmos => Decoded { opcode: Opcode::LAX, cycles: 4, address_mode: Mode::ZeroPageY, handler: handle_lax },
+        0xAF if nmos => Decoded { opcode: Opcode::LAX, cycles: 4, address_mode: Mode::Absolute, handler: handle_lax },
+        0xBF if nmos => Decoded { opcode: Opcode::LAX, cycles: 4, address_mode: Mode::AbsoluteY, handler: handle_lax },
+        0xA3 if nmos => Decoded { opcode: Opcode::LAX, cycles: 6, address_mode: Mode::IndirectX, handler: handle_lax },
+        0xB3 if nmos => Decoded { opcode: Opcode::LAX, cycles: 5, address_mode: Mode::IndirectY, handler: handle_lax },
+
+        0x0B if nmos => Decoded { opcode: Opcode::ANC, cycles: 2, address_mode: Mode::Immediate, handler: handle_anc },
+        0x2B if nmos => Decoded { opcode: Opcode::ANC, cycles: 2, address_mode: Mode::Immediate, handler: handle_anc },
+        0x4B if nmos => Decoded { opcode: Opcode::ALR, cycles: 2, address_mode: Mode::Immediate, handler: handle_alr },
+        0x6B if nmos => Decoded { opcode: Opcode::ARR, cycles: 2, address_mode: Mode::Immediate, handler: handle_arr },
+        0xCB if nmos => Decoded { opcode: Opcode::AXS, cycles: 2, address_mode: Mode::Immediate, handler: handle_axs },
+        0xEB if nmos => Decoded { opcode: Opcode::SBC, cycles: 2, address_mode: Mode::Immediate, handler: handle_sbc },
+        0xBB if nmos => Decoded { opcode: Opcode::LAS, cycles: 4, address_mode: Mode::AbsoluteY, handler: handle_las },
+
+        0x1A if nmos => Decoded { opcode: Opcode::NOP, cycles: 2, address_mode: Mode::Implied, handler: handle_nop },
+        0x3A if nmos => Decoded { opcode: Opcode::NOP, cycles: 2, address_mode: Mode::Implied, handler: handle_nop },
+        0x5A if nmos => Decoded { opcode: Opcode::NOP, cycles: 2, address_mode: Mode::Implied, handler: handle_nop },
+        0x7A if nmos => Decoded { opcode: Opcode::NOP, cycles: 2, address_mode: Mode::Implied, handler: handle_nop },
+        0xDA if nmos => Decoded { opcode: Opcode::NOP, cycles: 2, address_mode: Mode::Implied, handler: handle_nop },
+        0xFA if nmos => Decoded { opcode: Opcode::NOP, cycles: 2, address_mode: Mode::Implied, handler: handle_nop },
+        0x80 if nmos => Decoded { opcode: Opcode::NOP, cycles: 2, address_mode: Mode::Immediate, handler: handle_nop },
+        0x82 if nmos => Decoded { opcode: Opcode::NOP, cycles: 2, address_mode: Mode::Immediate, handler: handle_nop },
+        0x89 if nmos => Decoded { opcode: Opcode::NOP, cycles: 2, address_mode: Mode::Immediate, handler: handle_nop },
+        0xC2 if nmos => Decoded { opcode: Opcode::NOP, cycles: 2, address_mode: Mode::Immediate, handler: handle_nop },
+        0xE2 if nmos => Decoded { opcode: Opcode::NOP, cycles: 2, address_mode: Mode::Immediate, handler: handle_nop },
+        0x04 if nmos => Decoded { opcode: Opcode::NOP, cycles: 3, address_mode: Mode::ZeroPage, handler: handle_nop },
+        0x44 if nmos => Decoded { opcode: Opcode::NOP, cycles: 3, address_mode: Mode::ZeroPage, handler: handle_nop },
+        0x64 if nmos => Decoded { opcode: Opcode::NOP, cycles: 3, address_mode: Mode::ZeroPage, handler: handle_nop },
+        0x14 if nmos => Decoded { opcode: Opcode::NOP, cycles: 4, address_mode: Mode::ZeroPageX, handler: handle_nop },
+        0x34 if nmos => Decoded { opcode: Opcode::NOP, cycles: 4, address_mode: Mode::ZeroPageX, handler: handle_nop },
+        0x54 if nmos => Decoded { opcode: Opcode::NOP, cycles: 4, address_mode: Mode::ZeroPageX, handler: handle_nop },
+        0x74 if nmos => Decoded { opcode: Opcode::NOP, cycles: 4, address_mode: Mode::ZeroPageX, handler: handle_nop },
+        0xD4 if nmos => Decoded { opcode: Opcode::NOP, cycles: 4, address_mode: Mode::ZeroPageX, handler: handle_nop },
+        0xF4 if nmos => Decoded { opcode: Opcode::NOP, cycles: 4, address_mode: Mode::ZeroPageX, handler: handle_nop },
+        0x0C if nmos => Decoded { opcode: Opcode::NOP, cycles: 4, address_mode: Mode::Absolute, handler: handle_nop },
+        0x1C if nmos => Decoded { opcode: Opcode::NOP, cycles: 4, address_mode: Mode::AbsoluteX, handler: handle_nop },
+        0x3C if nmos => Decoded { opcode: Opcode::NOP, cycles: 4, address_mode: Mode::AbsoluteX, handler: handle_nop },
+        0x5C if nmos => Decoded { opcode: Opcode::NOP, cycles: 4, address_mode: Mode::AbsoluteX, handler: handle_nop },
+        0x7C if nmos => Decoded { opcode: Opcode::NOP, cycles: 4, address_mode: Mode::AbsoluteX, handler: handle_nop },
+        0xDC if nmos => Decoded { opcode: Opcode::NOP, cycles: 4, address_mode: Mode::AbsoluteX, handler: handle_nop },
+        0xFC if nmos => Decoded { opcode: Opcode::NOP, cycles: 4, address_mode: Mode::AbsoluteX, handler: handle_nop },
+
+        0x02 if nmos => Decoded { opcode: Opcode::JAM, cycles: 2, address_mode: Mode::Implied, handler: handle_jam },
+        0x12 if nmos => Decoded { opcode: Opcode::JAM, cycles: 2, address_mode: Mode::Implied, handler: handle_jam },
+        0x22 if nmos => Decoded { opcode: Opcode::JAM, cycles: 2, address_mode: Mode::Implied, handler: handle_jam },
+        0x32 if nmos => Decoded { opcode: Opcode::JAM, cycles: 2, address_mode: Mode::Implied, handler: handle_jam },
+        0x42 if nmos => Decoded { opcode: Opcode::JAM, cycles: 2, address_mode: Mode::Implied, handler: handle_jam },
+        0x52 if nmos => Decoded { opcode: Opcode::JAM, cycles: 2, address_mode: Mode::Implied, handler: handle_jam },
+        0x62 if nmos => Decoded { opcode: Opcode::JAM, cycles: 2, address_mode: Mode::Implied, handler: handle_jam },
+        0x72 if nmos => Decoded { opcode: Opcode::JAM, cycles: 2, address_mode: Mode::Implied, handler: handle_jam },
+        0x92 if nmos => Decoded { opcode: Opcode::JAM, cycles: 2, address_mode: Mode::Implied, handler: handle_jam },
+        0xB2 if nmos => Decoded { opcode: Opcode::JAM, cycles: 2, address_mode: Mode::Implied, handler: handle_jam },
+        0xD2 if nmos => Decoded { opcode: Opcode::JAM, cycles: 2, address_mode: Mode::Implied, handler: handle_jam },
+        0xF2 if nmos => Decoded { opcode: Opcode::JAM, cycles: 2, address_mode: Mode::Implied, handler: handle_jam },
+
+        // Unstable undocumented NMOS instructions
+        0x8B if nmos => Decoded { opcode: Opcode::XAA, cycles: 2, address_mode: Mode::Immediate, handler: handle_xaa },
+        0xAB if nmos => Decoded { opcode: Opcode::LXA, cycles: 2, address_mode: Mode::Immediate, handler: handle_lxa },
+        0x93 if nmos => Decoded { opcode: Opcode::AHX, cycles: 6, address_mode: Mode::IndirectY, handler: handle_ahx },
+        0x9F if nmos => Decoded { opcode: Opcode::AHX, cycles: 5, address_mode: Mode::AbsoluteY, handler: handle_ahx },
+        0x9C if nmos => Decoded { opcode: Opcode::SHY, cycles: 5, address_mode: Mode::AbsoluteX, handler: handle_shy },
+        0x9E if nmos => Decoded { opcode: Opcode::SHX, cycles: 5, address_mode: Mode::AbsoluteY, handler: handle_shx },
+        0x9B if nmos => Decoded { opcode: Opcode::TAS, cycles: 5, address_mode: Mode::AbsoluteY, handler: handle_tas },
+
+        _ if cmos => decode_reserved(opcode),
+        _ => return None,
+    };
+    Some(decoded)
+}
+
+/// Opcodes left undefined on the CMOS variants are no-ops of a fixed length and duration.
+const fn decode_reserved<B: Bus>(opcode: u8) -> Decoded<B> {
+    let (cycles, address_mode) = match opcode {
+        0x02 | 0x22 | 0x42 | 0x62 | 0x82 | 0xC2 | 0xE2 => (2, Mode::Immediate),
+        0x44 => (3, Mode::ZeroPage),
+        0x54 | 0xD4 | 0xF4 => (4, Mode::ZeroPageX),
+        0x5C => return Decoded { opcode: Opcode::NOP, cycles: 8, address_mode: Mode::Absolute, handler: handle_nop_5c },
+        0xDC | 0xFC => (4, Mode::Absolute),
+        _ => (1, Mode::Implied),
+    };
+    Decoded { opcode: Opcode::NOP, cycles, address_mode, handler: handle_nop }
 }
 
 // Handler stubs for each opcode
 fn handle_adc<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
     let operand = inst.address_mode.fetch(cpu, bus);
-    let value = operand.read(cpu, bus);
+    let (value, page_crossed) = operand.read_indexed(cpu, bus);
+    let mut cycles = inst.cycles;
+    if page_crossed {
+        cycles += 1;
+    }
+    // The 65C02 takes an extra cycle in decimal mode to compute valid flags
+    if cpu.status.contains(Flags::DECIMAL) && cpu.variant().is_cmos() {
+        cpu.dummy_read(bus, cpu.pc);
+        cycles += 1;
+    }
+    add_with_carry(cpu, value);
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles,
+    }
+}
+
+/// Add the value and the carry to the accumulator, honouring decimal mode.
+fn add_with_carry(cpu: &mut CPU, value: u8) {
     let carry = if cpu.status.contains(Flags::CARRY) { 1 } else { 0 };
-    
-    // First do binary addition
-    let binary_result = cpu.a.wrapping_add(value).wrapping_add(carry);
-    
-    // Calculate flags based on binary result
-    Flags::set(&mut cpu.status, Flags::CARRY, binary_result < cpu.a || (binary_result == cpu.a && carry == 1));
-    Flags::set(&mut cpu.status, Flags::OVERFLOW, (cpu.a ^ binary_result) & (value ^ binary_result) & 0x80 != 0);
-    Flags::set(&mut cpu.status, Flags::NEGATIVE, binary_result & 0x80 != 0);
-    Flags::set(&mut cpu.status, Flags::ZERO, binary_result == 0);
-
-    // If in BCD mode, adjust the result
+
     let result = if cpu.status.contains(Flags::DECIMAL) {
-        let mut al = binary_result & 0x0F;
-        let mut ah = binary_result >> 4;
-        
-        if al > 9 {
-            al += 6;
-            ah += 1;
+        // Add the low digits first, adjusting and carrying into the high digits
+        let mut al = (cpu.a & 0x0F) as u16 + (value & 0x0F) as u16 + carry;
+        if al >= 0x0A {
+            al = ((al + 0x06) & 0x0F) + 0x10;
+        }
+
+        // Overflow comes from the signed sum before the high digits are adjusted
+        let signed = (cpu.a & 0xF0) as i8 as i16 + (value & 0xF0) as i8 as i16 + al as i16;
+        Flags::set(&mut cpu.status, Flags::OVERFLOW, !(-128..=127).contains(&signed));
+
+        let mut sum = (cpu.a & 0xF0) as u16 + (value & 0xF0) as u16 + al;
+
+        // The NMOS 6502 takes N from the unadjusted sum and Z from the binary sum
+        if !cpu.variant().is_cmos() {
+            Flags::set(&mut cpu.status, Flags::NEGATIVE, sum & 0x80 != 0);
+            Flags::set(&mut cpu.status, Flags::ZERO, cpu.a.wrapping_add(value).wrapping_add(carry as u8) == 0);
+        }
+
+        if sum >= 0xA0 {
+            sum += 0x60;
+        }
+        Flags::set(&mut cpu.status, Flags::CARRY, sum > 0xFF);
+        sum as u8
+    } else {
+        let sum = cpu.a as u16 + value as u16 + carry;
+        let result = sum as u8;
+        Flags::set(&mut cpu.status, Flags::CARRY, sum > 0xFF);
+        Flags::set(&mut cpu.status, Flags::OVERFLOW, (cpu.a ^ result) & (value ^ result) & 0x80 != 0);
+        result
+    };
+
+    // The 65C02 sets N and Z from the final result, even in decimal mode
+    if cpu.variant().is_cmos() || !cpu.status.contains(Flags::DECIMAL) {
+        Flags::set(&mut cpu.status, Flags::NEGATIVE, result & 0x80 != 0);
+        Flags::set(&mut cpu.status, Flags::ZERO, result == 0);
+    }
+
+    cpu.a = result;
+}
+
+/// Subtract the value and the borrow from the accumulator, honouring decimal mode.
+fn subtract_with_borrow(cpu: &mut CPU, value: u8) {
+    let borrow = if cpu.status.contains(Flags::CARRY) { 0 } else { 1 };
+
+    // Carry and overflow come from the binary subtraction in both modes
+    let binary_result = cpu.a.wrapping_sub(value).wrapping_sub(borrow);
+    Flags::set(&mut cpu.status, Flags::CARRY, cpu.a as u16 >= value as u16 + borrow as u16);
+    Flags::set(&mut cpu.status, Flags::OVERFLOW, (cpu.a ^ value) & (cpu.a ^ binary_result) & 0x80 != 0);
+
+    let result = if cpu.status.contains(Flags::DECIMAL) && !cpu.variant().is_cmos() {
+        let mut al = (cpu.a & 0x0F) as i16 - (value & 0x0F) as i16 - borrow as i16;
+        if al < 0 {
+            al = ((al - 0x06) & 0x0F) - 0x10;
+        }
+        let mut diff = (cpu.a & 0xF0) as i16 - (value & 0xF0) as i16 + al;
+        if diff < 0 {
+            diff -= 0x60;
         }
-        
-        if ah > 9 {
-            ah += 6;
-            cpu.status.insert(Flags::CARRY);
+        diff as u8
+    } else if cpu.status.contains(Flags::DECIMAL) {
+        let al = (cpu.a & 0x0F) as i16 - (value & 0x0F) as i16 - borrow as i16;
+        let mut diff = cpu.a as i16 - value as i16 - borrow as i16;
+        if diff < 0 {
+            diff -= 0x60;
         }
-        
-        ((ah & 0x0F) << 4) | (al & 0x0F)
+        if al < 0 {
+            diff -= 0x06;
+        }
+        diff as u8
     } else {
         binary_result
     };
 
-    cpu.a = result;
+    // The 65C02 sets N and Z from the final result, even in decimal mode, whereas the NMOS 6502
+    // takes them from the binary subtraction
+    let nz = if cpu.variant().is_cmos() { result } else { binary_result };
+    Flags::set(&mut cpu.status, Flags::NEGATIVE, nz & 0x80 != 0);
+    Flags::set(&mut cpu.status, Flags::ZERO, nz == 0);
 
-    Instruction {
-        opcode: inst.opcode,
-        operand,
-        cycles: inst.cycles,
-    }
+    cpu.a = result;
 }
 
 fn handle_and<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
     let operand = inst.address_mode.fetch(cpu, bus);
-    let value = operand.read(cpu, bus);
+    let (value, page_crossed) = operand.read_indexed(cpu, bus);
+    let mut cycles = inst.cycles;
+    if page_crossed {
+        cycles += 1;
+    }
     cpu.a &= value;
 
     Flags::set(&mut cpu.status, Flags::NEGATIVE, cpu.a & 0x80 != 0);
@@ -517,28 +1076,43 @@ fn handle_and<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruct
     Instruction {
         opcode: inst.opcode,
         operand,
-        cycles: inst.cycles,
+        cycles,
     }
 }
 
 fn handle_asl<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
     let operand = inst.address_mode.fetch(cpu, bus);
-    let value = operand.read(cpu, bus);
-    let result = value << 1;
-
-    Flags::set(&mut cpu.status, Flags::CARRY, value & 0x80 != 0);
-    Flags::set(&mut cpu.status, Flags::NEGATIVE, result & 0x80 != 0);
-    Flags::set(&mut cpu.status, Flags::ZERO, result == 0);
-
-    match inst.address_mode {
-        Mode::Accumulator => cpu.a = result,
-        _ => operand.write(cpu, bus, result),
+    let (_, page_crossed) = operand.modify_shift(cpu, bus, |cpu, value| {
+        let result = value << 1;
+
+        Flags::set(&mut cpu.status, Flags::CARRY, value & 0x80 != 0);
+        Flags::set(&mut cpu.status, Flags::NEGATIVE, result & 0x80 != 0);
+        Flags::set(&mut cpu.status, Flags::ZERO, result == 0);
+        result
+    });
+    let mut cycles = inst.cycles;
+    if cpu.variant().is_cmos() && page_crossed {
+        cycles += 1;
     }
 
     Instruction {
         opcode: inst.opcode,
         operand,
-        cycles: inst.cycles,
+        cycles,
+    }
+}
+
+/// Jump to the target of a taken branch, returning the extra cycles spent. The CPU reads the
+/// next opcode while adding the offset, and reads again while fixing the high byte of the target.
+fn branch(cpu: &mut CPU, bus: &mut impl Bus, target: u16) -> usize {
+    let old_pc = cpu.pc;
+    cpu.dummy_read(bus, old_pc);
+    cpu.pc = target;
+    if (old_pc & 0xFF00) != (target & 0xFF00) {
+        cpu.dummy_read(bus, (old_pc & 0xFF00) | (target & 0x00FF));
+        2
+    } else {
+        1
     }
 }
 
@@ -547,9 +1121,7 @@ fn handle_bcc<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruct
     let mut cycles = inst.cycles;
     if !cpu.status.contains(Flags::CARRY) {
         if let Operand::Relative(addr) = operand {
-            let old_pc = cpu.pc;
-            cpu.pc = addr;
-            cycles += if (old_pc & 0xFF00) != (cpu.pc & 0xFF00) { 2 } else { 1 };
+            cycles += branch(cpu, bus, addr);
         }
     }
     Instruction {
@@ -564,9 +1136,7 @@ fn handle_bcs<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruct
     let mut cycles = inst.cycles;
     if cpu.status.contains(Flags::CARRY) {
         if let Operand::Relative(addr) = operand {
-            let old_pc = cpu.pc;
-            cpu.pc = addr;
-            cycles += if (old_pc & 0xFF00) != (cpu.pc & 0xFF00) { 2 } else { 1 };
+            cycles += branch(cpu, bus, addr);
         }
     }
     Instruction {
@@ -581,9 +1151,7 @@ fn handle_beq<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruct
     let mut cycles = inst.cycles;
     if cpu.status.contains(Flags::ZERO) {
         if let Operand::Relative(addr) = operand {
-            let old_pc = cpu.pc;
-            cpu.pc = addr;
-            cycles += if (old_pc & 0xFF00) != (cpu.pc & 0xFF00) { 2 } else { 1 };
+            cycles += branch(cpu, bus, addr);
         }
     }
     Instruction {
@@ -598,9 +1166,7 @@ fn handle_bmi<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruct
     let mut cycles = inst.cycles;
     if cpu.status.contains(Flags::NEGATIVE) {
         if let Operand::Relative(addr) = operand {
-            let old_pc = cpu.pc;
-            cpu.pc = addr;
-            cycles += if (old_pc & 0xFF00) != (cpu.pc & 0xFF00) { 2 } else { 1 };
+            cycles += branch(cpu, bus, addr);
         }
     }
     Instruction {
@@ -615,9 +1181,7 @@ fn handle_bne<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruct
     let mut cycles = inst.cycles;
     if !cpu.status.contains(Flags::ZERO) {
         if let Operand::Relative(addr) = operand {
-            let old_pc = cpu.pc;
-            cpu.pc = addr;
-            cycles += if (old_pc & 0xFF00) != (cpu.pc & 0xFF00) { 2 } else { 1 };
+            cycles += branch(cpu, bus, addr);
         }
     }
     Instruction {
@@ -632,9 +1196,7 @@ fn handle_bpl<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruct
     let mut cycles = inst.cycles;
     if !cpu.status.contains(Flags::NEGATIVE) {
         if let Operand::Relative(addr) = operand {
-            let old_pc = cpu.pc;
-            cpu.pc = addr;
-            cycles += if (old_pc & 0xFF00) != (cpu.pc & 0xFF00) { 2 } else { 1 };
+            cycles += branch(cpu, bus, addr);
         }
     }
     Instruction {
@@ -649,9 +1211,7 @@ fn handle_bvc<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruct
     let mut cycles = inst.cycles;
     if !cpu.status.contains(Flags::OVERFLOW) {
         if let Operand::Relative(addr) = operand {
-            let old_pc = cpu.pc;
-            cpu.pc = addr;
-            cycles += if (old_pc & 0xFF00) != (cpu.pc & 0xFF00) { 2 } else { 1 };
+            cycles += branch(cpu, bus, addr);
         }
     }
     Instruction {
@@ -666,9 +1226,7 @@ fn handle_bvs<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruct
     let mut cycles = inst.cycles;
     if cpu.status.contains(Flags::OVERFLOW) {
         if let Operand::Relative(addr) = operand {
-            let old_pc = cpu.pc;
-            cpu.pc = addr;
-            cycles += if (old_pc & 0xFF00) != (cpu.pc & 0xFF00) { 2 } else { 1 };
+            cycles += branch(cpu, bus, addr);
         }
     }
     Instruction {
@@ -678,15 +1236,29 @@ fn handle_bvs<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruct
     }
 }
 
+fn handle_bra<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    let operand = inst.address_mode.fetch(cpu, bus);
+    let mut cycles = inst.cycles;
+    if let Operand::Relative(addr) = operand {
+        cycles += branch(cpu, bus, addr);
+    }
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles,
+    }
+}
+
 fn handle_brk<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    // Skip the signature byte, which was read and discarded in the second cycle
     cpu.pc += 1;
     cpu.push_word(bus, cpu.pc);
-    let mut status = cpu.status;
-    status.insert(Flags::BREAK);
-    status.insert(Flags::UNUSED);
-    cpu.push_byte(bus, status.bits());
+    cpu.push_byte(bus, cpu.status.pushed(true));
     cpu.status.insert(Flags::INTERRUPT);
-    cpu.pc = bus.mem_read_word(VECTOR_IRQ);
+    if cpu.variant().is_cmos() {
+        cpu.status.remove(Flags::DECIMAL);
+    }
+    cpu.pc = cpu.read_vector(bus, VECTOR_IRQ);
 
     Instruction {
         opcode: inst.opcode,
@@ -733,7 +1305,11 @@ fn handle_clv<B: Bus>(cpu: &mut CPU, _bus: &mut B, inst: &Decoded<B>) -> Instruc
 
 fn handle_cmp<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
     let operand = inst.address_mode.fetch(cpu, bus);
-    let value = operand.read(cpu, bus);
+    let (value, page_crossed) = operand.read_indexed(cpu, bus);
+    let mut cycles = inst.cycles;
+    if page_crossed {
+        cycles += 1;
+    }
     let result = cpu.a.wrapping_sub(value);
 
     Flags::set(&mut cpu.status, Flags::CARRY, cpu.a >= value);
@@ -743,7 +1319,7 @@ fn handle_cmp<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruct
     Instruction {
         opcode: inst.opcode,
         operand,
-        cycles: inst.cycles,
+        cycles,
     }
 }
 
@@ -781,13 +1357,11 @@ fn handle_cpy<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruct
 
 fn handle_dec<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
     let operand = inst.address_mode.fetch(cpu, bus);
-    let value = operand.read(cpu, bus);
-    let result = value.wrapping_sub(1);
+    let result = operand.modify(cpu, bus, |_, value| value.wrapping_sub(1));
 
     Flags::set(&mut cpu.status, Flags::NEGATIVE, result & 0x80 != 0);
     Flags::set(&mut cpu.status, Flags::ZERO, result == 0);
 
-    operand.write(cpu, bus, result);
     Instruction {
         opcode: inst.opcode,
         operand,
@@ -823,7 +1397,11 @@ fn handle_dey<B: Bus>(cpu: &mut CPU, _bus: &mut B, inst: &Decoded<B>) -> Instruc
 
 fn handle_eor<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
     let operand = inst.address_mode.fetch(cpu, bus);
-    let value = operand.read(cpu, bus);
+    let (value, page_crossed) = operand.read_indexed(cpu, bus);
+    let mut cycles = inst.cycles;
+    if page_crossed {
+        cycles += 1;
+    }
     cpu.a ^= value;
 
     Flags::set(&mut cpu.status, Flags::NEGATIVE, cpu.a & 0x80 != 0);
@@ -832,19 +1410,17 @@ fn handle_eor<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruct
     Instruction {
         opcode: inst.opcode,
         operand,
-        cycles: inst.cycles,
+        cycles,
     }
 }
 
 fn handle_inc<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
     let operand = inst.address_mode.fetch(cpu, bus);
-    let value = operand.read(cpu, bus);
-    let result = value.wrapping_add(1);
+    let result = operand.modify(cpu, bus, |_, value| value.wrapping_add(1));
 
     Flags::set(&mut cpu.status, Flags::NEGATIVE, result & 0x80 != 0);
     Flags::set(&mut cpu.status, Flags::ZERO, result == 0);
 
-    operand.write(cpu, bus, result);
     Instruction {
         opcode: inst.opcode,
         operand,
@@ -882,7 +1458,14 @@ fn handle_jmp<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruct
     let operand = inst.address_mode.fetch(cpu, bus);
     match operand {
         Operand::Absolute(addr) => cpu.pc = addr,
-        Operand::Indirect(addr) => cpu.pc = bus.mem_read_word_page_wrap(addr),
+        // The NMOS 6502 does not carry into the high byte when the pointer lies at a page boundary
+        Operand::Indirect(addr) if !cpu.variant().is_cmos() => cpu.pc = cpu.read_word_page_wrap(bus, addr),
+        // The 65C02 spends an extra cycle to carry into the high byte of the pointer
+        Operand::Indirect(addr) => {
+            cpu.dummy_read(bus, cpu.pc.wrapping_sub(1));
+            cpu.pc = cpu.read_word(bus, addr);
+        },
+        Operand::AbsoluteIndirectX(_) => cpu.pc = operand.address(cpu, bus, false),
         _ => panic!("Invalid operand for JMP"),
     }
     Instruction {
@@ -893,21 +1476,28 @@ fn handle_jmp<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruct
 }
 
 fn handle_jsr<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
-    let operand = inst.address_mode.fetch(cpu, bus);
-    if let Operand::Absolute(addr) = operand {
-        cpu.push_word(bus, cpu.pc - 1);
-        cpu.pc = addr;
-    }
+    // The return address is pushed before the high byte of the target is fetched, so it points
+    // to the last byte of the instruction
+    let lo = cpu.fetch_byte(bus);
+    cpu.stack_dummy_read(bus);
+    cpu.push_word(bus, cpu.pc);
+    let hi = cpu.fetch_byte(bus);
+    let addr = (hi as u16) << 8 | lo as u16;
+    cpu.pc = addr;
     Instruction {
         opcode: inst.opcode,
-        operand,
+        operand: Operand::Absolute(addr),
         cycles: inst.cycles,
     }
 }
 
 fn handle_lda<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
     let operand = inst.address_mode.fetch(cpu, bus);
-    let value = operand.read(cpu, bus);
+    let (value, page_crossed) = operand.read_indexed(cpu, bus);
+    let mut cycles = inst.cycles;
+    if page_crossed {
+        cycles += 1;
+    }
     cpu.a = value;
 
     Flags::set(&mut cpu.status, Flags::NEGATIVE, cpu.a & 0x80 != 0);
@@ -916,13 +1506,17 @@ fn handle_lda<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruct
     Instruction {
         opcode: inst.opcode,
         operand,
-        cycles: inst.cycles,
+        cycles,
     }
 }
 
 fn handle_ldx<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
     let operand = inst.address_mode.fetch(cpu, bus);
-    let value = operand.read(cpu, bus);
+    let (value, page_crossed) = operand.read_indexed(cpu, bus);
+    let mut cycles = inst.cycles;
+    if page_crossed {
+        cycles += 1;
+    }
     cpu.x = value;
 
     Flags::set(&mut cpu.status, Flags::NEGATIVE, cpu.x & 0x80 != 0);
@@ -931,13 +1525,17 @@ fn handle_ldx<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruct
     Instruction {
         opcode: inst.opcode,
         operand,
-        cycles: inst.cycles,
+        cycles,
     }
 }
 
 fn handle_ldy<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
     let operand = inst.address_mode.fetch(cpu, bus);
-    let value = operand.read(cpu, bus);
+    let (value, page_crossed) = operand.read_indexed(cpu, bus);
+    let mut cycles = inst.cycles;
+    if page_crossed {
+        cycles += 1;
+    }
     cpu.y = value;
 
     Flags::set(&mut cpu.status, Flags::NEGATIVE, cpu.y & 0x80 != 0);
@@ -946,32 +1544,79 @@ fn handle_ldy<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruct
     Instruction {
         opcode: inst.opcode,
         operand,
-        cycles: inst.cycles,
+        cycles,
     }
 }
 
 fn handle_lsr<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
     let operand = inst.address_mode.fetch(cpu, bus);
-    let value = operand.read(cpu, bus);
-    let result = value >> 1;
-
-    Flags::set(&mut cpu.status, Flags::CARRY, value & 0x01 != 0);
-    Flags::set(&mut cpu.status, Flags::NEGATIVE, false);
-    Flags::set(&mut cpu.status, Flags::ZERO, result == 0);
+    let (_, page_crossed) = operand.modify_shift(cpu, bus, |cpu, value| {
+        let result = value >> 1;
+
+        Flags::set(&mut cpu.status, Flags::CARRY, value & 0x01 != 0);
+        Flags::set(&mut cpu.status, Flags::NEGATIVE, false);
+        Flags::set(&mut cpu.status, Flags::ZERO, result == 0);
+        result
+    });
+    let mut cycles = inst.cycles;
+    if cpu.variant().is_cmos() && page_crossed {
+        cycles += 1;
+    }
 
-    match inst.address_mode {
-        Mode::Accumulator => cpu.a = result,
-        _ => operand.write(cpu, bus, result),
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles,
     }
+}
 
+fn handle_nop<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    let operand = inst.address_mode.fetch(cpu, bus);
+    let mut cycles = inst.cycles;
+    // Multi-byte no-ops still read their operand
+    match operand {
+        Operand::Implied | Operand::Immediate(_) => {},
+        _ => {
+            let (_, page_crossed) = operand.read_indexed(cpu, bus);
+            if page_crossed {
+                cycles += 1;
+            }
+        },
+    }
     Instruction {
         opcode: inst.opcode,
         operand,
+        cycles,
+    }
+}
+
+/// The reserved 0x5C opcode of the 65C02 fetches an absolute address, then spends the remaining
+/// cycles reading from the last page of memory.
+fn handle_nop_5c<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    let addr = cpu.fetch_word(bus);
+    for _ in 3..inst.cycles {
+        cpu.dummy_read(bus, 0xFF00 | (addr & 0x00FF));
+    }
+    Instruction {
+        opcode: inst.opcode,
+        operand: Operand::Absolute(addr),
         cycles: inst.cycles,
     }
 }
 
-fn handle_nop<B: Bus>(_cpu: &mut CPU, _bus: &mut B, inst: &Decoded<B>) -> Instruction {
+fn handle_stp<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    cpu.dummy_read(bus, cpu.pc);
+    cpu.run_state = RunState::Stopped;
+    Instruction {
+        opcode: inst.opcode,
+        operand: Operand::Implied,
+        cycles: inst.cycles,
+    }
+}
+
+fn handle_wai<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    cpu.dummy_read(bus, cpu.pc);
+    cpu.run_state = RunState::Waiting;
     Instruction {
         opcode: inst.opcode,
         operand: Operand::Implied,
@@ -981,7 +1626,11 @@ fn handle_nop<B: Bus>(_cpu: &mut CPU, _bus: &mut B, inst: &Decoded<B>) -> Instru
 
 fn handle_ora<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
     let operand = inst.address_mode.fetch(cpu, bus);
-    let value = operand.read(cpu, bus);
+    let (value, page_crossed) = operand.read_indexed(cpu, bus);
+    let mut cycles = inst.cycles;
+    if page_crossed {
+        cycles += 1;
+    }
     cpu.a |= value;
 
     Flags::set(&mut cpu.status, Flags::NEGATIVE, cpu.a & 0x80 != 0);
@@ -990,7 +1639,7 @@ fn handle_ora<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruct
     Instruction {
         opcode: inst.opcode,
         operand,
-        cycles: inst.cycles,
+        cycles,
     }
 }
 
@@ -1004,10 +1653,25 @@ fn handle_pha<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruct
 }
 
 fn handle_php<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
-    let mut status = cpu.status;
-    status.insert(Flags::BREAK);
-    status.insert(Flags::UNUSED);
-    cpu.push_byte(bus, status.bits());
+    cpu.push_byte(bus, cpu.status.pushed(true));
+    Instruction {
+        opcode: inst.opcode,
+        operand: Operand::Implied,
+        cycles: inst.cycles,
+    }
+}
+
+fn handle_phx<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    cpu.push_byte(bus, cpu.x);
+    Instruction {
+        opcode: inst.opcode,
+        operand: Operand::Implied,
+        cycles: inst.cycles,
+    }
+}
+
+fn handle_phy<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    cpu.push_byte(bus, cpu.y);
     Instruction {
         opcode: inst.opcode,
         operand: Operand::Implied,
@@ -1016,6 +1680,7 @@ fn handle_php<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruct
 }
 
 fn handle_pla<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    cpu.stack_dummy_read(bus);
     cpu.a = cpu.pop_byte(bus);
     Flags::set(&mut cpu.status, Flags::NEGATIVE, cpu.a & 0x80 != 0);
     Flags::set(&mut cpu.status, Flags::ZERO, cpu.a == 0);
@@ -1027,10 +1692,9 @@ fn handle_pla<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruct
 }
 
 fn handle_plp<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    cpu.stack_dummy_read(bus);
     let status = cpu.pop_byte(bus);
-    cpu.status = Flags::from_bits_truncate(status);
-    cpu.status.remove(Flags::BREAK);
-    cpu.status.remove(Flags::UNUSED);
+    cpu.status = Flags::pulled(status);
     Instruction {
         opcode: inst.opcode,
         operand: Operand::Implied,
@@ -1038,56 +1702,81 @@ fn handle_plp<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruct
     }
 }
 
-fn handle_rol<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
-    let operand = inst.address_mode.fetch(cpu, bus);
-    let value = operand.read(cpu, bus);
-    let carry = if cpu.status.contains(Flags::CARRY) { 1 } else { 0 };
-    let result = (value << 1) | carry;
-
-    Flags::set(&mut cpu.status, Flags::CARRY, value & 0x80 != 0);
-    Flags::set(&mut cpu.status, Flags::NEGATIVE, result & 0x80 != 0);
-    Flags::set(&mut cpu.status, Flags::ZERO, result == 0);
-
-    match inst.address_mode {
-        Mode::Accumulator => cpu.a = result,
-        _ => operand.write(cpu, bus, result),
+fn handle_plx<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    cpu.stack_dummy_read(bus);
+    cpu.x = cpu.pop_byte(bus);
+    Flags::set(&mut cpu.status, Flags::NEGATIVE, cpu.x & 0x80 != 0);
+    Flags::set(&mut cpu.status, Flags::ZERO, cpu.x == 0);
+    Instruction {
+        opcode: inst.opcode,
+        operand: Operand::Implied,
+        cycles: inst.cycles,
     }
+}
 
+fn handle_ply<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    cpu.stack_dummy_read(bus);
+    cpu.y = cpu.pop_byte(bus);
+    Flags::set(&mut cpu.status, Flags::NEGATIVE, cpu.y & 0x80 != 0);
+    Flags::set(&mut cpu.status, Flags::ZERO, cpu.y == 0);
     Instruction {
         opcode: inst.opcode,
-        operand,
+        operand: Operand::Implied,
         cycles: inst.cycles,
     }
 }
 
-fn handle_ror<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+fn handle_rol<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
     let operand = inst.address_mode.fetch(cpu, bus);
-    let value = operand.read(cpu, bus);
-    let carry = if cpu.status.contains(Flags::CARRY) { 0x80 } else { 0 };
-    let result = (value >> 1) | carry;
-
-    Flags::set(&mut cpu.status, Flags::CARRY, value & 0x01 != 0);
-    Flags::set(&mut cpu.status, Flags::NEGATIVE, result & 0x80 != 0);
-    Flags::set(&mut cpu.status, Flags::ZERO, result == 0);
-
-    match inst.address_mode {
-        Mode::Accumulator => cpu.a = result,
-        _ => operand.write(cpu, bus, result),
+    let (_, page_crossed) = operand.modify_shift(cpu, bus, |cpu, value| {
+        let carry = if cpu.status.contains(Flags::CARRY) { 1 } else { 0 };
+        let result = (value << 1) | carry;
+
+        Flags::set(&mut cpu.status, Flags::CARRY, value & 0x80 != 0);
+        Flags::set(&mut cpu.status, Flags::NEGATIVE, result & 0x80 != 0);
+        Flags::set(&mut cpu.status, Flags::ZERO, result == 0);
+        result
+    });
+    let mut cycles = inst.cycles;
+    if cpu.variant().is_cmos() && page_crossed {
+        cycles += 1;
     }
 
     Instruction {
         opcode: inst.opcode,
         operand,
-        cycles: inst.cycles,
+        cycles,
+    }
+}
+
+fn handle_ror<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    let operand = inst.address_mode.fetch(cpu, bus);
+    let (_, page_crossed) = operand.modify_shift(cpu, bus, |cpu, value| {
+        let carry = if cpu.status.contains(Flags::CARRY) { 0x80 } else { 0 };
+        let result = (value >> 1) | carry;
+
+        Flags::set(&mut cpu.status, Flags::CARRY, value & 0x01 != 0);
+        Flags::set(&mut cpu.status, Flags::NEGATIVE, result & 0x80 != 0);
+        Flags::set(&mut cpu.status, Flags::ZERO, result == 0);
+        result
+    });
+    let mut cycles = inst.cycles;
+    if cpu.variant().is_cmos() && page_crossed {
+        cycles += 1;
+    }
+
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles,
     }
 }
 
 fn handle_rti<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    cpu.stack_dummy_read(bus);
     let status = cpu.pop_byte(bus);
-    cpu.status = Flags::from_bits_truncate(status);
-    cpu.status.remove(Flags::BREAK);
-    cpu.status.remove(Flags::UNUSED);
-    cpu.pc = cpu.pop_word(bus).wrapping_add(1);
+    cpu.status = Flags::pulled(status);
+    cpu.pc = cpu.pop_word(bus);
     Instruction {
         opcode: inst.opcode,
         operand: Operand::Implied,
@@ -1096,7 +1785,11 @@ fn handle_rti<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruct
 }
 
 fn handle_rts<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
-    cpu.pc = cpu.pop_word(bus).wrapping_add(1);
+    cpu.stack_dummy_read(bus);
+    cpu.pc = cpu.pop_word(bus);
+    // The return address points to the last byte of the JSR, which is read again and skipped
+    cpu.dummy_read(bus, cpu.pc);
+    cpu.pc = cpu.pc.wrapping_add(1);
     Instruction {
         opcode: inst.opcode,
         operand: Operand::Implied,
@@ -1106,43 +1799,21 @@ fn handle_rts<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruct
 
 fn handle_sbc<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
     let operand = inst.address_mode.fetch(cpu, bus);
-    let value = operand.read(cpu, bus);
-    let carry = if cpu.status.contains(Flags::CARRY) { 1 } else { 0 };
-    
-    // First do binary subtraction
-    let binary_result = cpu.a.wrapping_sub(value).wrapping_sub(1 - carry);
-    
-    // Calculate flags based on binary result
-    Flags::set(&mut cpu.status, Flags::CARRY, cpu.a >= value && (cpu.a > value || carry == 1));
-    Flags::set(&mut cpu.status, Flags::OVERFLOW, (cpu.a ^ value) & (cpu.a ^ binary_result) & 0x80 != 0);
-    Flags::set(&mut cpu.status, Flags::NEGATIVE, binary_result & 0x80 != 0);
-    Flags::set(&mut cpu.status, Flags::ZERO, binary_result == 0);
-
-    // If in BCD mode, adjust the result
-    let result = if cpu.status.contains(Flags::DECIMAL) {
-        let mut al = binary_result & 0x0F;
-        let mut ah = binary_result >> 4;
-        
-        if al > 9 {
-            al -= 6;
-            ah -= 1;
-        }
-        
-        if ah > 9 {
-            ah -= 6;
-            cpu.status.remove(Flags::CARRY);
-        }
-        
-        ((ah & 0x0F) << 4) | (al & 0x0F)
-    } else {
-        binary_result
-    };
-
-    cpu.a = result;
+    let (value, page_crossed) = operand.read_indexed(cpu, bus);
+    let mut cycles = inst.cycles;
+    if page_crossed {
+        cycles += 1;
+    }
+    // The 65C02 takes an extra cycle in decimal mode to compute valid flags
+    if cpu.status.contains(Flags::DECIMAL) && cpu.variant().is_cmos() {
+        cpu.dummy_read(bus, cpu.pc);
+        cycles += 1;
+    }
+    subtract_with_borrow(cpu, value);
     Instruction {
         opcode: inst.opcode,
         operand,
-        cycles: inst.cycles,
+        cycles,
     }
 }
 
@@ -1203,6 +1874,16 @@ fn handle_sty<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruct
     }
 }
 
+fn handle_stz<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    let operand = inst.address_mode.fetch(cpu, bus);
+    operand.write(cpu, bus, 0);
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles: inst.cycles,
+    }
+}
+
 fn handle_tax<B: Bus>(cpu: &mut CPU, _bus: &mut B, inst: &Decoded<B>) -> Instruction {
     cpu.x = cpu.a;
     Flags::set(&mut cpu.status, Flags::NEGATIVE, cpu.x & 0x80 != 0);
@@ -1225,6 +1906,34 @@ fn handle_tay<B: Bus>(cpu: &mut CPU, _bus: &mut B, inst: &Decoded<B>) -> Instruc
     }
 }
 
+fn handle_trb<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    let operand = inst.address_mode.fetch(cpu, bus);
+    operand.modify(cpu, bus, |cpu, value| {
+        Flags::set(&mut cpu.status, Flags::ZERO, cpu.a & value == 0);
+        value & !cpu.a
+    });
+
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles: inst.cycles,
+    }
+}
+
+fn handle_tsb<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    let operand = inst.address_mode.fetch(cpu, bus);
+    operand.modify(cpu, bus, |cpu, value| {
+        Flags::set(&mut cpu.status, Flags::ZERO, cpu.a & value == 0);
+        value | cpu.a
+    });
+
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles: inst.cycles,
+    }
+}
+
 fn handle_tsx<B: Bus>(cpu: &mut CPU, _bus: &mut B, inst: &Decoded<B>) -> Instruction {
     cpu.x = cpu.sp;
     Flags::set(&mut cpu.status, Flags::NEGATIVE, cpu.x & 0x80 != 0);
@@ -1269,11 +1978,196 @@ fn handle_tya<B: Bus>(cpu: &mut CPU, _bus: &mut B, inst: &Decoded<B>) -> Instruc
 
 fn handle_bit<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
     let operand = inst.address_mode.fetch(cpu, bus);
-    let value = operand.read(cpu, bus);
+    let (value, page_crossed) = operand.read_indexed(cpu, bus);
+    let mut cycles = inst.cycles;
+    if page_crossed {
+        cycles += 1;
+    }
     let result = cpu.a & value;
 
+    // BIT #imm only affects the zero flag
+    if inst.address_mode != Mode::Immediate {
+        Flags::set(&mut cpu.status, Flags::NEGATIVE, value & 0x80 != 0);
+        Flags::set(&mut cpu.status, Flags::OVERFLOW, value & 0x40 != 0);
+    }
+    Flags::set(&mut cpu.status, Flags::ZERO, result == 0);
+
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles,
+    }
+}
+
+fn handle_bbr<B: Bus, const BIT: u8>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    // The bit is tested before the branch offset is fetched
+    let addr = cpu.fetch_byte(bus);
+    let value = cpu.read(bus, addr as u16);
+    cpu.dummy_read(bus, addr as u16);
+    let offset = cpu.fetch_byte(bus) as i8;
+    let target = cpu.pc.wrapping_add(offset as u16);
+    let operand = Operand::ZeroPageRelative(addr, target);
+    let mut cycles = inst.cycles;
+    if value & (1 << BIT) == 0 {
+        cycles += branch(cpu, bus, target);
+    }
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles,
+    }
+}
+
+fn handle_bbs<B: Bus, const BIT: u8>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    // The bit is tested before the branch offset is fetched
+    let addr = cpu.fetch_byte(bus);
+    let value = cpu.read(bus, addr as u16);
+    cpu.dummy_read(bus, addr as u16);
+    let offset = cpu.fetch_byte(bus) as i8;
+    let target = cpu.pc.wrapping_add(offset as u16);
+    let operand = Operand::ZeroPageRelative(addr, target);
+    let mut cycles = inst.cycles;
+    if value & (1 << BIT) != 0 {
+        cycles += branch(cpu, bus, target);
+    }
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles,
+    }
+}
+
+fn handle_rmb<B: Bus, const BIT: u8>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    let operand = inst.address_mode.fetch(cpu, bus);
+    operand.modify(cpu, bus, |_, value| value & !(1 << BIT));
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles: inst.cycles,
+    }
+}
+
+fn handle_smb<B: Bus, const BIT: u8>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    let operand = inst.address_mode.fetch(cpu, bus);
+    operand.modify(cpu, bus, |_, value| value | (1 << BIT));
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles: inst.cycles,
+    }
+}
+
+// Undocumented NMOS instructions
+
+fn handle_slo<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    let operand = inst.address_mode.fetch(cpu, bus);
+    let result = operand.modify(cpu, bus, |cpu, value| {
+        Flags::set(&mut cpu.status, Flags::CARRY, value & 0x80 != 0);
+        value << 1
+    });
+    cpu.a |= result;
+
+    Flags::set(&mut cpu.status, Flags::NEGATIVE, cpu.a & 0x80 != 0);
+    Flags::set(&mut cpu.status, Flags::ZERO, cpu.a == 0);
+
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles: inst.cycles,
+    }
+}
+
+fn handle_rla<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    let operand = inst.address_mode.fetch(cpu, bus);
+    let result = operand.modify(cpu, bus, |cpu, value| {
+        let carry = if cpu.status.contains(Flags::CARRY) { 1 } else { 0 };
+        Flags::set(&mut cpu.status, Flags::CARRY, value & 0x80 != 0);
+        (value << 1) | carry
+    });
+    cpu.a &= result;
+
+    Flags::set(&mut cpu.status, Flags::NEGATIVE, cpu.a & 0x80 != 0);
+    Flags::set(&mut cpu.status, Flags::ZERO, cpu.a == 0);
+
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles: inst.cycles,
+    }
+}
+
+fn handle_sre<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    let operand = inst.address_mode.fetch(cpu, bus);
+    let result = operand.modify(cpu, bus, |cpu, value| {
+        Flags::set(&mut cpu.status, Flags::CARRY, value & 0x01 != 0);
+        value >> 1
+    });
+    cpu.a ^= result;
+
+    Flags::set(&mut cpu.status, Flags::NEGATIVE, cpu.a & 0x80 != 0);
+    Flags::set(&mut cpu.status, Flags::ZERO, cpu.a == 0);
+
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles: inst.cycles,
+    }
+}
+
+fn handle_rra<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    let operand = inst.address_mode.fetch(cpu, bus);
+    // The carry out of the rotation feeds the addition
+    let result = operand.modify(cpu, bus, |cpu, value| {
+        let carry = if cpu.status.contains(Flags::CARRY) { 0x80 } else { 0 };
+        Flags::set(&mut cpu.status, Flags::CARRY, value & 0x01 != 0);
+        (value >> 1) | carry
+    });
+    add_with_carry(cpu, result);
+
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles: inst.cycles,
+    }
+}
+
+fn handle_sax<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    let operand = inst.address_mode.fetch(cpu, bus);
+    operand.write(cpu, bus, cpu.a & cpu.x);
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles: inst.cycles,
+    }
+}
+
+fn handle_lax<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    let operand = inst.address_mode.fetch(cpu, bus);
+    let (value, page_crossed) = operand.read_indexed(cpu, bus);
+    let mut cycles = inst.cycles;
+    if page_crossed {
+        cycles += 1;
+    }
+    cpu.a = value;
+    cpu.x = value;
+
     Flags::set(&mut cpu.status, Flags::NEGATIVE, value & 0x80 != 0);
-    Flags::set(&mut cpu.status, Flags::OVERFLOW, value & 0x40 != 0);
+    Flags::set(&mut cpu.status, Flags::ZERO, value == 0);
+
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles,
+    }
+}
+
+fn handle_dcp<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    let operand = inst.address_mode.fetch(cpu, bus);
+    let value = operand.modify(cpu, bus, |_, value| value.wrapping_sub(1));
+    let result = cpu.a.wrapping_sub(value);
+
+    Flags::set(&mut cpu.status, Flags::CARRY, cpu.a >= value);
+    Flags::set(&mut cpu.status, Flags::NEGATIVE, result & 0x80 != 0);
     Flags::set(&mut cpu.status, Flags::ZERO, result == 0);
 
     Instruction {
@@ -1281,4 +2175,229 @@ fn handle_bit<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruct
         operand,
         cycles: inst.cycles,
     }
-}
\ No newline at end of file
+}
+
+fn handle_isc<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    let operand = inst.address_mode.fetch(cpu, bus);
+    let value = operand.modify(cpu, bus, |_, value| value.wrapping_add(1));
+    subtract_with_borrow(cpu, value);
+
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles: inst.cycles,
+    }
+}
+
+fn handle_anc<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    let operand = inst.address_mode.fetch(cpu, bus);
+    let value = operand.read(cpu, bus);
+    cpu.a &= value;
+
+    // Bit 7 of the result is copied into the carry, as if shifted out by ASL
+    Flags::set(&mut cpu.status, Flags::CARRY, cpu.a & 0x80 != 0);
+    Flags::set(&mut cpu.status, Flags::NEGATIVE, cpu.a & 0x80 != 0);
+    Flags::set(&mut cpu.status, Flags::ZERO, cpu.a == 0);
+
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles: inst.cycles,
+    }
+}
+
+fn handle_alr<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    let operand = inst.address_mode.fetch(cpu, bus);
+    let value = operand.read(cpu, bus) & cpu.a;
+    cpu.a = value >> 1;
+
+    Flags::set(&mut cpu.status, Flags::CARRY, value & 0x01 != 0);
+    Flags::set(&mut cpu.status, Flags::NEGATIVE, false);
+    Flags::set(&mut cpu.status, Flags::ZERO, cpu.a == 0);
+
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles: inst.cycles,
+    }
+}
+
+fn handle_arr<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    let operand = inst.address_mode.fetch(cpu, bus);
+    let value = operand.read(cpu, bus) & cpu.a;
+    let carry = if cpu.status.contains(Flags::CARRY) { 0x80 } else { 0 };
+    let mut result = (value >> 1) | carry;
+
+    Flags::set(&mut cpu.status, Flags::NEGATIVE, result & 0x80 != 0);
+    Flags::set(&mut cpu.status, Flags::ZERO, result == 0);
+
+    if cpu.status.contains(Flags::DECIMAL) {
+        // V comes from the AND result, then each digit of the rotated value is fixed up as ADC
+        // would do in decimal mode
+        Flags::set(&mut cpu.status, Flags::OVERFLOW, (value ^ result) & 0x40 != 0);
+        if (value & 0x0F) + (value & 0x01) > 0x05 {
+            result = (result & 0xF0) | (result.wrapping_add(0x06) & 0x0F);
+        }
+        let high_carry = (value & 0xF0) as u16 + (value & 0x10) as u16 > 0x50;
+        if high_carry {
+            result = result.wrapping_add(0x60);
+        }
+        Flags::set(&mut cpu.status, Flags::CARRY, high_carry);
+    } else {
+        // C comes from bit 6 and V from bit 6 xor bit 5 of the result
+        Flags::set(&mut cpu.status, Flags::CARRY, result & 0x40 != 0);
+        Flags::set(&mut cpu.status, Flags::OVERFLOW, ((result >> 6) ^ (result >> 5)) & 0x01 != 0);
+    }
+    cpu.a = result;
+
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles: inst.cycles,
+    }
+}
+
+fn handle_axs<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    let operand = inst.address_mode.fetch(cpu, bus);
+    let value = operand.read(cpu, bus);
+    let ax = cpu.a & cpu.x;
+    cpu.x = ax.wrapping_sub(value);
+
+    // Compares like CMP, ignoring the carry and decimal flags
+    Flags::set(&mut cpu.status, Flags::CARRY, ax >= value);
+    Flags::set(&mut cpu.status, Flags::NEGATIVE, cpu.x & 0x80 != 0);
+    Flags::set(&mut cpu.status, Flags::ZERO, cpu.x == 0);
+
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles: inst.cycles,
+    }
+}
+
+fn handle_las<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    let operand = inst.address_mode.fetch(cpu, bus);
+    let (value, page_crossed) = operand.read_indexed(cpu, bus);
+    let value = value & cpu.sp;
+    let mut cycles = inst.cycles;
+    if page_crossed {
+        cycles += 1;
+    }
+    cpu.a = value;
+    cpu.x = value;
+    cpu.sp = value;
+
+    Flags::set(&mut cpu.status, Flags::NEGATIVE, value & 0x80 != 0);
+    Flags::set(&mut cpu.status, Flags::ZERO, value == 0);
+
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles,
+    }
+}
+
+fn handle_jam<B: Bus>(cpu: &mut CPU, _bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    // The NMOS 6502 locks up until the next reset
+    cpu.run_state = RunState::Stopped;
+    Instruction {
+        opcode: inst.opcode,
+        operand: Operand::Implied,
+        cycles: inst.cycles,
+    }
+}
+
+// Unstable undocumented NMOS instructions. Their results depend on the individual chip, so these
+// follow the most commonly observed behaviour.
+
+/// Magic constant ORed into the accumulator by XAA and LXA.
+const UNSTABLE_MAGIC: u8 = 0xEE;
+
+fn handle_xaa<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    let operand = inst.address_mode.fetch(cpu, bus);
+    let value = operand.read(cpu, bus);
+    cpu.a = (cpu.a | UNSTABLE_MAGIC) & cpu.x & value;
+
+    Flags::set(&mut cpu.status, Flags::NEGATIVE, cpu.a & 0x80 != 0);
+    Flags::set(&mut cpu.status, Flags::ZERO, cpu.a == 0);
+
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles: inst.cycles,
+    }
+}
+
+fn handle_lxa<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    let operand = inst.address_mode.fetch(cpu, bus);
+    let value = operand.read(cpu, bus);
+    cpu.a = (cpu.a | UNSTABLE_MAGIC) & value;
+    cpu.x = cpu.a;
+
+    Flags::set(&mut cpu.status, Flags::NEGATIVE, cpu.a & 0x80 != 0);
+    Flags::set(&mut cpu.status, Flags::ZERO, cpu.a == 0);
+
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles: inst.cycles,
+    }
+}
+
+/// Store the value ANDed with the high byte of the base address plus one. When indexing crosses
+/// a page, the stored value also replaces the high byte of the target address.
+fn store_and_high(cpu: &mut CPU, bus: &mut impl Bus, operand: &Operand, value: u8) {
+    let (base, index) = match operand {
+        Operand::AbsoluteX(addr) => (*addr, cpu.x),
+        Operand::AbsoluteY(addr) => (*addr, cpu.y),
+        Operand::IndirectY(addr) => (cpu.read_word_page_wrap(bus, *addr as u16), cpu.y),
+        _ => panic!("Invalid operand for unstable store"),
+    };
+    let value = value & ((base >> 8) as u8).wrapping_add(1);
+    let mut addr = index_address(cpu, bus, base, index, true);
+    if (base & 0xFF00) != (addr & 0xFF00) {
+        addr = ((value as u16) << 8) | (addr & 0x00FF);
+    }
+    cpu.write(bus, addr, value);
+}
+
+fn handle_ahx<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    let operand = inst.address_mode.fetch(cpu, bus);
+    store_and_high(cpu, bus, &operand, cpu.a & cpu.x);
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles: inst.cycles,
+    }
+}
+
+fn handle_shx<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    let operand = inst.address_mode.fetch(cpu, bus);
+    store_and_high(cpu, bus, &operand, cpu.x);
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles: inst.cycles,
+    }
+}
+
+fn handle_shy<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    let operand = inst.address_mode.fetch(cpu, bus);
+    store_and_high(cpu, bus, &operand, cpu.y);
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles: inst.cycles,
+    }
+}
+
+fn handle_tas<B: Bus>(cpu: &mut CPU, bus: &mut B, inst: &Decoded<B>) -> Instruction {
+    let operand = inst.address_mode.fetch(cpu, bus);
+    cpu.sp = cpu.a & cpu.x;
+    store_and_high(cpu, bus, &operand, cpu.sp);
+    Instruction {
+        opcode: inst.opcode,
+        operand,
+        cycles: inst.cycles,
+    }
+}
@@ -1,6 +1,9 @@
+mod asm;
 mod bus; 
 mod cpu;
+mod disasm;
 mod inst;
+mod trace;
 
 #[cfg(test)] mod test_load_store;
 #[cfg(test)] mod test_alu;
@@ -11,6 +14,20 @@ mod inst;
 #[cfg(test)] mod test_stack;
 #[cfg(test)] mod test_misc;
 #[cfg(test)] mod test_bits;
+#[cfg(test)] mod test_interrupts;
+#[cfg(test)] mod test_timing;
+#[cfg(test)] mod test_decimal;
+#[cfg(test)] mod test_variants;
+#[cfg(test)] mod test_undocumented;
+#[cfg(test)] mod test_cycles;
+#[cfg(test)] mod test_disasm;
+#[cfg(test)] mod test_asm;
+#[cfg(test)] mod test_trace;
+#[cfg(test)] mod test_run;
 
+pub use asm::{AsmError, Assembler, Program, Segment};
 pub use bus::*;
-pub use cpu::CPU;
\ No newline at end of file
+pub use cpu::{CPU, Flags, RunState, StopReason, Variant};
+pub use disasm::{disassemble, disassemble_range, Disassembly};
+pub use inst::{Instruction, Opcode, Operand};
+pub use trace::{Observer, Step, Tracer};
\ No newline at end of file
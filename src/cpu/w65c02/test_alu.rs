@@ -11,7 +11,7 @@ fn test_adc() {
     cpu.status.set(Flags::CARRY, true);
     bus.mem_write(0x2000, 0x69); // ADC immediate
     bus.mem_write(0x2001, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.a, 0x43);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -28,7 +28,7 @@ fn test_adc() {
     bus.mem_write(0x2000, 0x65); // ADC zero page
     bus.mem_write(0x2001, 0x42);
     bus.mem_write(0x42, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.a, 0x43);
     assert_eq!(inst.opcode, Opcode::ADC);
@@ -42,7 +42,7 @@ fn test_adc() {
     bus.mem_write(0x2000, 0x75); // ADC zero page,X
     bus.mem_write(0x2001, 0x40);
     bus.mem_write(0x42, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.a, 0x43);
     assert_eq!(inst.opcode, Opcode::ADC);
@@ -56,7 +56,7 @@ fn test_adc() {
     bus.mem_write(0x2001, 0x42);
     bus.mem_write(0x2002, 0x20);
     bus.mem_write(0x2042, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(cpu.a, 0x43);
     assert_eq!(inst.opcode, Opcode::ADC);
@@ -71,7 +71,7 @@ fn test_adc() {
     bus.mem_write(0x2001, 0x40);
     bus.mem_write(0x2002, 0x20);
     bus.mem_write(0x2042, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(cpu.a, 0x43);
     assert_eq!(inst.opcode, Opcode::ADC);
@@ -86,7 +86,7 @@ fn test_adc() {
     bus.mem_write(0x2001, 0x40);
     bus.mem_write(0x2002, 0x20);
     bus.mem_write(0x2042, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(cpu.a, 0x43);
     assert_eq!(inst.opcode, Opcode::ADC);
@@ -102,7 +102,7 @@ fn test_adc() {
     bus.mem_write(0x42, 0x42);
     bus.mem_write(0x43, 0x20);
     bus.mem_write(0x2042, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.a, 0x43);
     assert_eq!(inst.opcode, Opcode::ADC);
@@ -118,7 +118,22 @@ fn test_adc() {
     bus.mem_write(0x40, 0x40);
     bus.mem_write(0x41, 0x20);
     bus.mem_write(0x2042, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x2002);
+    assert_eq!(cpu.a, 0x43);
+    assert_eq!(inst.opcode, Opcode::ADC);
+    assert_eq!(inst.cycles, 5);
+
+    // (Zero page)
+    cpu.pc = 0x2000;
+    cpu.a = 0x21;
+    cpu.status.set(Flags::CARRY, true);
+    bus.mem_write(0x2000, 0x72); // ADC (zero page)
+    bus.mem_write(0x2001, 0x40);
+    bus.mem_write(0x40, 0x42);
+    bus.mem_write(0x41, 0x20);
+    bus.mem_write(0x2042, 0x21);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.a, 0x43);
     assert_eq!(inst.opcode, Opcode::ADC);
@@ -135,7 +150,7 @@ fn test_and() {
     cpu.a = 0x21;
     bus.mem_write(0x2000, 0x29); // AND immediate
     bus.mem_write(0x2001, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.a, 0x21);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -148,7 +163,7 @@ fn test_and() {
     cpu.a = 0x21;
     bus.mem_write(0x2000, 0x29); // AND immediate
     bus.mem_write(0x2001, 0x00);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.a, 0x00);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -161,13 +176,27 @@ fn test_and() {
     cpu.a = 0x80;
     bus.mem_write(0x2000, 0x29); // AND immediate
     bus.mem_write(0x2001, 0x80);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.a, 0x80);
     assert!(cpu.status.contains(Flags::NEGATIVE));
     assert!(!cpu.status.contains(Flags::ZERO));
     assert!(matches!(inst.opcode, Opcode::AND));
     assert_eq!(inst.cycles, 2);
+
+    // (Zero page)
+    cpu.pc = 0x2000;
+    cpu.a = 0x21;
+    bus.mem_write(0x2000, 0x32); // AND (zero page)
+    bus.mem_write(0x2001, 0x40);
+    bus.mem_write(0x40, 0x42);
+    bus.mem_write(0x41, 0x20);
+    bus.mem_write(0x2042, 0x21);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x2002);
+    assert_eq!(cpu.a, 0x21);
+    assert_eq!(inst.opcode, Opcode::AND);
+    assert_eq!(inst.cycles, 5);
 }
 
 #[test]
@@ -180,7 +209,7 @@ fn test_eor() {
     cpu.a = 0x21;
     bus.mem_write(0x2000, 0x49); // EOR immediate
     bus.mem_write(0x2001, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.a, 0x00);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -194,7 +223,7 @@ fn test_eor() {
     bus.mem_write(0x2000, 0x45); // EOR zero page
     bus.mem_write(0x2001, 0x42);
     bus.mem_write(0x42, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.a, 0x00);
     assert_eq!(inst.opcode, Opcode::EOR);
@@ -207,7 +236,7 @@ fn test_eor() {
     bus.mem_write(0x2000, 0x55); // EOR zero page,X
     bus.mem_write(0x2001, 0x40);
     bus.mem_write(0x42, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.a, 0x00);
     assert_eq!(inst.opcode, Opcode::EOR);
@@ -220,7 +249,7 @@ fn test_eor() {
     bus.mem_write(0x2001, 0x42);
     bus.mem_write(0x2002, 0x20);
     bus.mem_write(0x2042, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(cpu.a, 0x00);
     assert_eq!(inst.opcode, Opcode::EOR);
@@ -234,7 +263,7 @@ fn test_eor() {
     bus.mem_write(0x2001, 0x40);
     bus.mem_write(0x2002, 0x20);
     bus.mem_write(0x2042, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(cpu.a, 0x00);
     assert_eq!(inst.opcode, Opcode::EOR);
@@ -248,7 +277,7 @@ fn test_eor() {
     bus.mem_write(0x2001, 0x40);
     bus.mem_write(0x2002, 0x20);
     bus.mem_write(0x2042, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(cpu.a, 0x00);
     assert_eq!(inst.opcode, Opcode::EOR);
@@ -263,7 +292,7 @@ fn test_eor() {
     bus.mem_write(0x42, 0x42);
     bus.mem_write(0x43, 0x20);
     bus.mem_write(0x2042, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.a, 0x00);
     assert_eq!(inst.opcode, Opcode::EOR);
@@ -278,9 +307,24 @@ fn test_eor() {
     bus.mem_write(0x40, 0x40);
     bus.mem_write(0x41, 0x20);
     bus.mem_write(0x2042, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x2002);
+    assert_eq!(cpu.a, 0x00);
+    assert_eq!(inst.opcode, Opcode::EOR);
+    assert_eq!(inst.cycles, 5);
+
+    // (Zero page)
+    cpu.pc = 0x2000;
+    cpu.a = 0x21;
+    bus.mem_write(0x2000, 0x52); // EOR (zero page)
+    bus.mem_write(0x2001, 0x40);
+    bus.mem_write(0x40, 0x42);
+    bus.mem_write(0x41, 0x20);
+    bus.mem_write(0x2042, 0x21);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.a, 0x00);
+    assert!(cpu.status.contains(Flags::ZERO));
     assert_eq!(inst.opcode, Opcode::EOR);
     assert_eq!(inst.cycles, 5);
 }
@@ -295,7 +339,7 @@ fn test_ora() {
     cpu.a = 0x21;
     bus.mem_write(0x2000, 0x09); // ORA immediate
     bus.mem_write(0x2001, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.a, 0x21);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -308,7 +352,7 @@ fn test_ora() {
     cpu.a = 0x00;
     bus.mem_write(0x2000, 0x09); // ORA immediate
     bus.mem_write(0x2001, 0x00);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.a, 0x00);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -321,13 +365,27 @@ fn test_ora() {
     cpu.a = 0x80;
     bus.mem_write(0x2000, 0x09); // ORA immediate
     bus.mem_write(0x2001, 0x80);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.a, 0x80);
     assert!(cpu.status.contains(Flags::NEGATIVE));
     assert!(!cpu.status.contains(Flags::ZERO));
     assert!(matches!(inst.opcode, Opcode::ORA));
     assert_eq!(inst.cycles, 2);
+
+    // (Zero page)
+    cpu.pc = 0x2000;
+    cpu.a = 0x21;
+    bus.mem_write(0x2000, 0x12); // ORA (zero page)
+    bus.mem_write(0x2001, 0x40);
+    bus.mem_write(0x40, 0x42);
+    bus.mem_write(0x41, 0x20);
+    bus.mem_write(0x2042, 0x42);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x2002);
+    assert_eq!(cpu.a, 0x63);
+    assert_eq!(inst.opcode, Opcode::ORA);
+    assert_eq!(inst.cycles, 5);
 }
 
 #[test]
@@ -340,7 +398,7 @@ fn test_cmp() {
     cpu.a = 0x21;
     bus.mem_write(0x2000, 0xC9); // CMP immediate
     bus.mem_write(0x2001, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert!(cpu.status.contains(Flags::CARRY));
     assert!(cpu.status.contains(Flags::ZERO));
@@ -353,7 +411,7 @@ fn test_cmp() {
     cpu.a = 0x21;
     bus.mem_write(0x2000, 0xC9); // CMP immediate
     bus.mem_write(0x2001, 0x20);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert!(cpu.status.contains(Flags::CARRY));
     assert!(!cpu.status.contains(Flags::ZERO));
@@ -366,13 +424,28 @@ fn test_cmp() {
     cpu.a = 0x20;
     bus.mem_write(0x2000, 0xC9); // CMP immediate
     bus.mem_write(0x2001, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert!(!cpu.status.contains(Flags::CARRY));
     assert!(!cpu.status.contains(Flags::ZERO));
     assert!(cpu.status.contains(Flags::NEGATIVE));
     assert!(matches!(inst.opcode, Opcode::CMP));
     assert_eq!(inst.cycles, 2);
+
+    // (Zero page)
+    cpu.pc = 0x2000;
+    cpu.a = 0x42;
+    bus.mem_write(0x2000, 0xD2); // CMP (zero page)
+    bus.mem_write(0x2001, 0x40);
+    bus.mem_write(0x40, 0x42);
+    bus.mem_write(0x41, 0x20);
+    bus.mem_write(0x2042, 0x42);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x2002);
+    assert!(cpu.status.contains(Flags::ZERO));
+    assert!(cpu.status.contains(Flags::CARRY));
+    assert_eq!(inst.opcode, Opcode::CMP);
+    assert_eq!(inst.cycles, 5);
 }
 
 #[test]
@@ -385,7 +458,7 @@ fn test_cpx() {
     cpu.x = 0x21;
     bus.mem_write(0x2000, 0xE0); // CPX immediate
     bus.mem_write(0x2001, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
     assert!(cpu.status.contains(Flags::ZERO));
@@ -399,7 +472,7 @@ fn test_cpx() {
     bus.mem_write(0x2000, 0xE4); // CPX zero page
     bus.mem_write(0x2001, 0x42);
     bus.mem_write(0x42, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
     assert!(cpu.status.contains(Flags::ZERO));
@@ -414,7 +487,7 @@ fn test_cpx() {
     bus.mem_write(0x2001, 0x42);
     bus.mem_write(0x2002, 0x20);
     bus.mem_write(0x2042, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
     assert!(cpu.status.contains(Flags::ZERO));
@@ -433,7 +506,7 @@ fn test_cpy() {
     cpu.y = 0x21;
     bus.mem_write(0x2000, 0xC0); // CPY immediate
     bus.mem_write(0x2001, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
     assert!(cpu.status.contains(Flags::ZERO));
@@ -447,7 +520,7 @@ fn test_cpy() {
     bus.mem_write(0x2000, 0xC4); // CPY zero page
     bus.mem_write(0x2001, 0x42);
     bus.mem_write(0x42, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
     assert!(cpu.status.contains(Flags::ZERO));
@@ -462,7 +535,7 @@ fn test_cpy() {
     bus.mem_write(0x2001, 0x42);
     bus.mem_write(0x2002, 0x20);
     bus.mem_write(0x2042, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
     assert!(cpu.status.contains(Flags::ZERO));
@@ -482,7 +555,7 @@ fn test_sbc() {
     cpu.status.set(Flags::CARRY, true);
     bus.mem_write(0x2000, 0xE9); // SBC immediate
     bus.mem_write(0x2001, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.a, 0x00);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -499,7 +572,7 @@ fn test_sbc() {
     bus.mem_write(0x2000, 0xE5); // SBC zero page
     bus.mem_write(0x2001, 0x42);
     bus.mem_write(0x42, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.a, 0x00);
     assert_eq!(inst.opcode, Opcode::SBC);
@@ -513,7 +586,7 @@ fn test_sbc() {
     bus.mem_write(0x2000, 0xF5); // SBC zero page,X
     bus.mem_write(0x2001, 0x40);
     bus.mem_write(0x42, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.a, 0x00);
     assert_eq!(inst.opcode, Opcode::SBC);
@@ -527,7 +600,7 @@ fn test_sbc() {
     bus.mem_write(0x2001, 0x42);
     bus.mem_write(0x2002, 0x20);
     bus.mem_write(0x2042, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(cpu.a, 0x00);
     assert_eq!(inst.opcode, Opcode::SBC);
@@ -542,7 +615,7 @@ fn test_sbc() {
     bus.mem_write(0x2001, 0x40);
     bus.mem_write(0x2002, 0x20);
     bus.mem_write(0x2042, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(cpu.a, 0x00);
     assert_eq!(inst.opcode, Opcode::SBC);
@@ -557,7 +630,7 @@ fn test_sbc() {
     bus.mem_write(0x2001, 0x40);
     bus.mem_write(0x2002, 0x20);
     bus.mem_write(0x2042, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(cpu.a, 0x00);
     assert_eq!(inst.opcode, Opcode::SBC);
@@ -573,7 +646,7 @@ fn test_sbc() {
     bus.mem_write(0x42, 0x42);
     bus.mem_write(0x43, 0x20);
     bus.mem_write(0x2042, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.a, 0x00);
     assert_eq!(inst.opcode, Opcode::SBC);
@@ -589,7 +662,22 @@ fn test_sbc() {
     bus.mem_write(0x40, 0x40);
     bus.mem_write(0x41, 0x20);
     bus.mem_write(0x2042, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x2002);
+    assert_eq!(cpu.a, 0x00);
+    assert_eq!(inst.opcode, Opcode::SBC);
+    assert_eq!(inst.cycles, 5);
+
+    // (Zero page)
+    cpu.pc = 0x2000;
+    cpu.a = 0x21;
+    cpu.status.set(Flags::CARRY, true);
+    bus.mem_write(0x2000, 0xF2); // SBC (zero page)
+    bus.mem_write(0x2001, 0x40);
+    bus.mem_write(0x40, 0x42);
+    bus.mem_write(0x41, 0x20);
+    bus.mem_write(0x2042, 0x21);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.a, 0x00);
     assert_eq!(inst.opcode, Opcode::SBC);
@@ -601,12 +689,24 @@ fn test_inc() {
     let mut cpu = CPU::new();
     let mut bus = FakeBus::new();
 
+    // Accumulator
+    cpu.pc = 0x0000;
+    cpu.a = 0x7F;
+    bus.mem_write(0x0000, 0x1A); // INC A
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x0001);
+    assert_eq!(cpu.a, 0x80);
+    assert!(!cpu.status.contains(Flags::ZERO));
+    assert!(cpu.status.contains(Flags::NEGATIVE));
+    assert!(matches!(inst.opcode, Opcode::INC));
+    assert_eq!(inst.cycles, 2);
+
     // Zero page
     cpu.pc = 0x0000;
     bus.mem_write(0x0000, 0xE6); // INC zero page
     bus.mem_write(0x0001, 0x42);
     bus.mem_write(0x0042, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x0002);
     assert_eq!(bus.mem_read(0x0042), 0x22);
     assert!(!cpu.status.contains(Flags::ZERO));
@@ -620,7 +720,7 @@ fn test_inc() {
     bus.mem_write(0x0000, 0xF6); // INC zero page,X
     bus.mem_write(0x0001, 0x40);
     bus.mem_write(0x0042, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x0002);
     assert_eq!(bus.mem_read(0x0042), 0x22);
     assert!(!cpu.status.contains(Flags::ZERO));
@@ -634,7 +734,7 @@ fn test_inc() {
     bus.mem_write(0x0001, 0x42);
     bus.mem_write(0x0002, 0x20);
     bus.mem_write(0x2042, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x0003);
     assert_eq!(bus.mem_read(0x0042), 0x22);
     assert!(!cpu.status.contains(Flags::ZERO));
@@ -649,7 +749,7 @@ fn test_inc() {
     bus.mem_write(0x0001, 0x40);
     bus.mem_write(0x0002, 0x20);
     bus.mem_write(0x2042, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x0003);
     assert_eq!(bus.mem_read(0x0042), 0x22);
     assert!(!cpu.status.contains(Flags::ZERO));
@@ -663,12 +763,24 @@ fn test_dec() {
     let mut cpu = CPU::new();
     let mut bus = FakeBus::new();
 
+    // Accumulator
+    cpu.pc = 0x0000;
+    cpu.a = 0x01;
+    bus.mem_write(0x0000, 0x3A); // DEC A
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x0001);
+    assert_eq!(cpu.a, 0x00);
+    assert!(cpu.status.contains(Flags::ZERO));
+    assert!(!cpu.status.contains(Flags::NEGATIVE));
+    assert!(matches!(inst.opcode, Opcode::DEC));
+    assert_eq!(inst.cycles, 2);
+
     // Zero page
     cpu.pc = 0x0000;
     bus.mem_write(0x0000, 0xC6); // DEC zero page
     bus.mem_write(0x0001, 0x42);
     bus.mem_write(0x0042, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x0002);
     assert_eq!(bus.mem_read(0x0042), 0x20);
     assert!(!cpu.status.contains(Flags::ZERO));
@@ -682,7 +794,7 @@ fn test_dec() {
     bus.mem_write(0x0000, 0xD6); // DEC zero page,X
     bus.mem_write(0x0001, 0x40);
     bus.mem_write(0x0042, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x0002);
     assert_eq!(bus.mem_read(0x0042), 0x20);
     assert!(!cpu.status.contains(Flags::ZERO));
@@ -696,7 +808,7 @@ fn test_dec() {
     bus.mem_write(0x0001, 0x42);
     bus.mem_write(0x0002, 0x20);
     bus.mem_write(0x2042, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x0003);
     assert_eq!(bus.mem_read(0x0042), 0x20);
     assert!(!cpu.status.contains(Flags::ZERO));
@@ -711,7 +823,7 @@ fn test_dec() {
     bus.mem_write(0x0001, 0x40);
     bus.mem_write(0x0002, 0x20);
     bus.mem_write(0x2042, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x0003);
     assert_eq!(bus.mem_read(0x0042), 0x20);
     assert!(!cpu.status.contains(Flags::ZERO));
@@ -728,7 +840,7 @@ fn test_inx() {
     cpu.pc = 0x2000;
     cpu.x = 0x21;
     bus.mem_write(0x2000, 0xE8); // INX
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.x, 0x22);
     assert!(!cpu.status.contains(Flags::ZERO));
@@ -745,7 +857,7 @@ fn test_iny() {
     cpu.pc = 0x2000;
     cpu.y = 0x21;
     bus.mem_write(0x2000, 0xC8); // INY
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.y, 0x22);
     assert!(!cpu.status.contains(Flags::ZERO));
@@ -762,7 +874,7 @@ fn test_dex() {
     cpu.pc = 0x2000;
     cpu.x = 0x21;
     bus.mem_write(0x2000, 0xCA); // DEX
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.x, 0x20);
     assert!(!cpu.status.contains(Flags::ZERO));
@@ -779,7 +891,7 @@ fn test_dey() {
     cpu.pc = 0x2000;
     cpu.y = 0x21;
     bus.mem_write(0x2000, 0x88); // DEY
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.y, 0x20);
     assert!(!cpu.status.contains(Flags::ZERO));
@@ -0,0 +1,225 @@
+use std::fs;
+
+use crate::cpu::w65c02::{Assembler, Bus, CPU, RunState, Variant, bus::FakeBus, disassemble};
+
+fn assemble(source: &str) -> Vec<u8> {
+    Assembler::new(Variant::W65C02).assemble(source).unwrap().bytes()
+}
+
+fn error(source: &str) -> String {
+    Assembler::new(Variant::W65C02).assemble(source).unwrap_err().to_string()
+}
+
+#[test]
+fn test_assemble_modes() {
+    let bytes = assemble("
+        .org $2000
+        lda #$42
+        lda $12
+        lda $12,x
+        ldx $12,y
+        lda $1234
+        lda $1234,x
+        lda $1234,y
+        lda ($12,x)
+        lda ($12),y
+        lda ($12)
+        jmp ($1234)
+        jmp ($1234,x)
+        asl a
+        asl
+        nop
+        bbr0 $12,*
+    ");
+    assert_eq!(bytes, [
+        0xA9, 0x42,
+        0xA5, 0x12,
+        0xB5, 0x12,
+        0xB6, 0x12,
+        0xAD, 0x34, 0x12,
+        0xBD, 0x34, 0x12,
+        0xB9, 0x34, 0x12,
+        0xA1, 0x12,
+        0xB1, 0x12,
+        0xB2, 0x12,
+        0x6C, 0x34, 0x12,
+        0x7C, 0x34, 0x12,
+        0x0A,
+        0x0A,
+        0xEA,
+        0x0F, 0x12, 0xFD,
+    ]);
+}
+
+#[test]
+fn test_assemble_zero_page_without_absolute_form() {
+    // LDA has no (abs),Y form, but STZ has no abs,Y form either so $1234,Y must fail
+    assert_eq!(assemble("lda ($12),y"), [0xB1, 0x12]);
+    assert!(error("stz $1234,y").contains("invalid addressing mode"));
+}
+
+#[test]
+fn test_forward_labels() {
+    let bytes = assemble("
+        .org $1000
+        jmp start
+        .byte 0
+    start:
+        lda data
+        bne start
+        rts
+    data = $0080
+    ");
+    // The forward reference to `data` takes the absolute form even if it is in the zero page
+    assert_eq!(bytes, [0x4C, 0x04, 0x10, 0x00, 0xAD, 0x80, 0x00, 0xD0, 0xFB, 0x60]);
+}
+
+#[test]
+fn test_branch_out_of_range() {
+    let source = "
+        .org $1000
+        beq far
+        .org $1100
+    far:
+        rts
+    ";
+    assert_eq!(error(source), "<input>:3: branch target $1100 out of range");
+}
+
+#[test]
+fn test_expressions() {
+    let bytes = assemble("
+        base = $1200
+        size = 2 * (3 + 4)
+        .org base
+        lda #<(base + size)
+        ldx #>(base + size)
+        ldy #size % 5 | %1000
+        .word * - 1, -1
+        .byte 'A' + 1, ~$0F & $FF, 1 << 4, $80 >> 3
+    ");
+    assert_eq!(bytes, [
+        0xA9, 0x0E,
+        0xA2, 0x12,
+        0xA0, 0x0C,
+        0x05, 0x12, 0xFF, 0xFF,
+        0x42, 0xF0, 0x10, 0x10,
+    ]);
+}
+
+#[test]
+fn test_data_directives() {
+    let program = Assembler::new(Variant::W65C02).assemble("
+        .org $3000
+        .byte 1, \"ab;c\", 2 ; comment
+        .text \"hi\\n\"
+        .word $1234
+        .org $3010
+        .byte $FF
+    ").unwrap();
+    assert_eq!(program.segments.len(), 2);
+    assert_eq!(program.segments[0].addr, 0x3000);
+    assert_eq!(program.segments[0].bytes, [1, b'a', b'b', b';', b'c', 2, b'h', b'i', b'\n', 0x34, 0x12]);
+    assert_eq!(program.segments[1].addr, 0x3010);
+
+    let bytes = program.bytes();
+    assert_eq!(bytes.len(), 0x11);
+    assert_eq!(bytes[0x0B..0x10], [0; 5]);
+    assert_eq!(bytes[0x10], 0xFF);
+}
+
+#[test]
+fn test_include() {
+    let dir = std::env::temp_dir().join(format!("vm8-asm-include-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("defs.s"), "value = $42\n").unwrap();
+    fs::write(dir.join("main.s"), ".include \"defs.s\"\n.org $0200\nlda #value\n").unwrap();
+    fs::write(dir.join("loop.s"), ".include \"loop.s\"\n").unwrap();
+
+    let asm = Assembler::new(Variant::W65C02);
+    let program = asm.assemble_file(&dir.join("main.s")).unwrap();
+    assert_eq!(program.bytes(), [0xA9, 0x42]);
+    let err = asm.assemble_file(&dir.join("loop.s")).unwrap_err();
+    assert_eq!(err.message, "includes nested too deep");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_listing() {
+    let program = Assembler::new(Variant::W65C02).assemble("
+        .org $0400
+    start:
+        lda #1
+        .byte 1, 2, 3, 4
+        jmp start
+    ").unwrap();
+    assert_eq!(program.symbols["start"], 0x0400);
+    let listing = program.listing();
+    assert!(listing.contains("0400  A9 01             lda #1\n"));
+    assert!(listing.contains("0402  01 02 03          .byte 1, 2, 3, 4\n0405  04\n"));
+    assert!(listing.contains("0406  4C 00 04          jmp start\n"));
+    assert!(listing.contains("Symbols:\n  start                    $0400\n"));
+}
+
+#[test]
+fn test_errors() {
+    assert_eq!(error("lda #$100"), "<input>:1: value $100 does not fit in a byte");
+    assert_eq!(error("\nfoo"), "<input>:2: unknown instruction 'foo'");
+    assert_eq!(error("jmp nowhere"), "<input>:1: undefined symbol in 'nowhere'");
+    assert_eq!(error("x: nop\nx: nop"), "<input>:2: symbol 'x' already defined");
+    assert_eq!(error("sta #1"), "<input>:1: invalid addressing mode for STA");
+    assert_eq!(error(".bogus"), "<input>:1: unknown directive '.bogus'");
+    // Bit operations are not available on every CMOS variant
+    assert!(Assembler::new(Variant::G65SC02).assemble("rmb0 $12").is_err());
+}
+
+#[test]
+fn test_assemble_and_run() {
+    let program = Assembler::new(Variant::W65C02).assemble("
+        .org $2000
+        ldx #0
+    loop:
+        lda message,x
+        beq done
+        sta $0300,x
+        inx
+        bra loop
+    done:
+        stp
+    message:
+        .text \"HELLO\"
+        .byte 0
+    ").unwrap();
+    let mut bus = FakeBus::new();
+    program.load(&mut bus);
+    let mut cpu = CPU::new();
+    cpu.pc = 0x2000;
+    while cpu.run_state() == RunState::Running {
+        cpu.exec(&mut bus).unwrap();
+    }
+    let output: Vec<u8> = (0..5).map(|i| bus.mem_read(0x0300 + i)).collect();
+    assert_eq!(output, b"HELLO");
+}
+
+#[test]
+fn test_disassembly_round_trip() {
+    for variant in [Variant::NMOS6502, Variant::W65C02, Variant::R65C02, Variant::G65SC02] {
+        let asm = Assembler::new(variant);
+        for code in 0..=255 {
+            let mut bus = FakeBus::new();
+            bus.mem_write(0x2000, code);
+            bus.mem_write(0x2001, 0x34);
+            bus.mem_write(0x2002, 0x12);
+            let inst = match disassemble(&bus, variant, 0x2000) {
+                Ok(inst) => inst,
+                Err(_) => continue,
+            };
+            let source = format!(".org $2000\n{}", inst);
+            let mut out = FakeBus::new();
+            asm.assemble(&source).unwrap().load(&mut out);
+            let again = disassemble(&out, variant, 0x2000).unwrap();
+            assert_eq!(again.to_string(), inst.to_string(), "{:?} opcode {:02X}", variant, code);
+        }
+    }
+}
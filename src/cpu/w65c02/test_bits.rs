@@ -1,4 +1,4 @@
-use crate::cpu::w65c02::{CPU, FakeBus, cpu::Flags, bus::Bus};
+use crate::cpu::w65c02::{CPU, FakeBus, cpu::Flags, bus::Bus, inst::Opcode};
 
 #[test]
 fn test_bit() {
@@ -11,7 +11,7 @@ fn test_bit() {
     bus.mem_write(0x0001, 0x42);
     bus.mem_write(0x0042, 0xAA);
     cpu.a = 0x55;
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert!(cpu.status.contains(Flags::ZERO));
     assert!(cpu.status.contains(Flags::NEGATIVE));
     assert!(!cpu.status.contains(Flags::OVERFLOW)); 
@@ -24,9 +24,171 @@ fn test_bit() {
     bus.mem_write(0x0002, 0x00);
     bus.mem_write(0x0042, 0x00);
     cpu.a = 0xFF;
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert!(cpu.status.contains(Flags::ZERO));
     assert!(!cpu.status.contains(Flags::NEGATIVE));
     assert!(!cpu.status.contains(Flags::OVERFLOW));
     assert_eq!(inst.cycles, 4);
+
+    // Immediate: only Z is affected
+    cpu.pc = 0x0000;
+    cpu.status.insert(Flags::NEGATIVE | Flags::OVERFLOW);
+    bus.mem_write(0x0000, 0x89);
+    bus.mem_write(0x0001, 0x0F);
+    cpu.a = 0xF0;
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x0002);
+    assert!(cpu.status.contains(Flags::ZERO));
+    assert!(cpu.status.contains(Flags::NEGATIVE));
+    assert!(cpu.status.contains(Flags::OVERFLOW));
+    assert_eq!(inst.cycles, 2);
+
+    // Zero page,X
+    cpu.pc = 0x0000;
+    cpu.x = 0x02;
+    bus.mem_write(0x0000, 0x34);
+    bus.mem_write(0x0001, 0x40);
+    bus.mem_write(0x0042, 0x41);
+    cpu.a = 0x01;
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x0002);
+    assert!(!cpu.status.contains(Flags::ZERO));
+    assert!(!cpu.status.contains(Flags::NEGATIVE));
+    assert!(cpu.status.contains(Flags::OVERFLOW));
+    assert_eq!(inst.cycles, 4);
+
+    // Absolute,X
+    cpu.pc = 0x0000;
+    cpu.x = 0x02;
+    bus.mem_write(0x0000, 0x3C);
+    bus.mem_write(0x0001, 0x40);
+    bus.mem_write(0x0002, 0x20);
+    bus.mem_write(0x2042, 0x80);
+    cpu.a = 0x7F;
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x0003);
+    assert!(cpu.status.contains(Flags::ZERO));
+    assert!(cpu.status.contains(Flags::NEGATIVE));
+    assert!(!cpu.status.contains(Flags::OVERFLOW));
+    assert_eq!(inst.cycles, 4);
+}
+
+#[test]
+fn test_trb() {
+    let mut cpu = CPU::new();
+    let mut bus = FakeBus::new();
+
+    // Zero page
+    cpu.pc = 0x2000;
+    cpu.a = 0x0F;
+    bus.mem_write(0x2000, 0x14); // TRB zero page
+    bus.mem_write(0x2001, 0x42);
+    bus.mem_write(0x0042, 0x3C);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x2002);
+    assert_eq!(bus.mem_read(0x0042), 0x30);
+    assert_eq!(cpu.a, 0x0F);
+    assert!(!cpu.status.contains(Flags::ZERO));
+    assert_eq!(inst.opcode, Opcode::TRB);
+    assert_eq!(inst.cycles, 5);
+
+    // Absolute
+    cpu.pc = 0x2000;
+    cpu.a = 0x0F;
+    bus.mem_write(0x2000, 0x1C); // TRB absolute
+    bus.mem_write(0x2001, 0x42);
+    bus.mem_write(0x2002, 0x20);
+    bus.mem_write(0x2042, 0xF0);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x2003);
+    assert_eq!(bus.mem_read(0x2042), 0xF0);
+    assert!(cpu.status.contains(Flags::ZERO));
+    assert_eq!(inst.opcode, Opcode::TRB);
+    assert_eq!(inst.cycles, 6);
+}
+
+#[test]
+fn test_tsb() {
+    let mut cpu = CPU::new();
+    let mut bus = FakeBus::new();
+
+    // Zero page
+    cpu.pc = 0x2000;
+    cpu.a = 0x0F;
+    bus.mem_write(0x2000, 0x04); // TSB zero page
+    bus.mem_write(0x2001, 0x42);
+    bus.mem_write(0x0042, 0x3C);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x2002);
+    assert_eq!(bus.mem_read(0x0042), 0x3F);
+    assert_eq!(cpu.a, 0x0F);
+    assert!(!cpu.status.contains(Flags::ZERO));
+    assert_eq!(inst.opcode, Opcode::TSB);
+    assert_eq!(inst.cycles, 5);
+
+    // Absolute
+    cpu.pc = 0x2000;
+    cpu.a = 0x0F;
+    bus.mem_write(0x2000, 0x0C); // TSB absolute
+    bus.mem_write(0x2001, 0x42);
+    bus.mem_write(0x2002, 0x20);
+    bus.mem_write(0x2042, 0xF0);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x2003);
+    assert_eq!(bus.mem_read(0x2042), 0xFF);
+    assert!(cpu.status.contains(Flags::ZERO));
+    assert_eq!(inst.opcode, Opcode::TSB);
+    assert_eq!(inst.cycles, 6);
+}
+
+#[test]
+fn test_rmb() {
+    let mut cpu = CPU::new();
+    let mut bus = FakeBus::new();
+
+    // Reset each bit in turn
+    for bit in 0..8 {
+        cpu.pc = 0x2000;
+        bus.mem_write(0x2000, 0x07 + (bit << 4)); // RMBn
+        bus.mem_write(0x2001, 0x42);
+        bus.mem_write(0x0042, 0xFF);
+        let inst = cpu.exec(&mut bus).unwrap();
+        assert_eq!(cpu.pc, 0x2002);
+        assert_eq!(bus.mem_read(0x0042), !(1 << bit));
+        assert_eq!(inst.cycles, 5);
+    }
+
+    // Disassembly
+    cpu.pc = 0x2000;
+    bus.mem_write(0x2000, 0x37); // RMB3
+    bus.mem_write(0x2001, 0x42);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::RMB3);
+    assert_eq!(inst.to_string(), "RMB3 $42");
+}
+
+#[test]
+fn test_smb() {
+    let mut cpu = CPU::new();
+    let mut bus = FakeBus::new();
+
+    // Set each bit in turn
+    for bit in 0..8 {
+        cpu.pc = 0x2000;
+        bus.mem_write(0x2000, 0x87 + (bit << 4)); // SMBn
+        bus.mem_write(0x2001, 0x42);
+        bus.mem_write(0x0042, 0x00);
+        let inst = cpu.exec(&mut bus).unwrap();
+        assert_eq!(cpu.pc, 0x2002);
+        assert_eq!(bus.mem_read(0x0042), 1 << bit);
+        assert_eq!(inst.cycles, 5);
+    }
+
+    // Disassembly
+    cpu.pc = 0x2000;
+    bus.mem_write(0x2000, 0xF7); // SMB7
+    bus.mem_write(0x2001, 0x42);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::SMB7);
+    assert_eq!(inst.to_string(), "SMB7 $42");
 }
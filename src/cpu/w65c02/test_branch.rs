@@ -1,4 +1,4 @@
-use crate::cpu::w65c02::{Bus, CPU, bus::FakeBus, cpu::Flags};
+use crate::cpu::w65c02::{Bus, CPU, bus::FakeBus, cpu::Flags, inst::Opcode};
 
 #[test]
 fn test_bcc() {
@@ -10,7 +10,7 @@ fn test_bcc() {
     cpu.status.remove(Flags::CARRY);
     bus.mem_write(0x2000, 0x90); // BCC
     bus.mem_write(0x2001, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2044);
     assert_eq!(inst.cycles, 3);
 
@@ -19,7 +19,7 @@ fn test_bcc() {
     cpu.status.insert(Flags::CARRY);
     bus.mem_write(0x2000, 0x90); // BCC
     bus.mem_write(0x2001, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(inst.cycles, 2);
 
@@ -28,7 +28,7 @@ fn test_bcc() {
     cpu.status.remove(Flags::CARRY);
     bus.mem_write(0x20F0, 0x90); // BCC
     bus.mem_write(0x20F1, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2134);
     assert_eq!(inst.cycles, 4);
 }
@@ -43,7 +43,7 @@ fn test_bcs() {
     cpu.status.insert(Flags::CARRY);
     bus.mem_write(0x2000, 0xB0); // BCS
     bus.mem_write(0x2001, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2044);
     assert_eq!(inst.cycles, 3);
 
@@ -52,7 +52,7 @@ fn test_bcs() {
     cpu.status.remove(Flags::CARRY);
     bus.mem_write(0x2000, 0xB0); // BCS
     bus.mem_write(0x2001, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(inst.cycles, 2);
 
@@ -61,7 +61,7 @@ fn test_bcs() {
     cpu.status.insert(Flags::CARRY);
     bus.mem_write(0x20F0, 0xB0); // BCS
     bus.mem_write(0x20F1, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2134);
     assert_eq!(inst.cycles, 4);
 }
@@ -76,7 +76,7 @@ fn test_beq() {
     cpu.status.insert(Flags::ZERO);
     bus.mem_write(0x2000, 0xF0); // BEQ
     bus.mem_write(0x2001, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2044);
     assert_eq!(inst.cycles, 3);
 
@@ -85,7 +85,7 @@ fn test_beq() {
     cpu.status.remove(Flags::ZERO);
     bus.mem_write(0x2000, 0xF0); // BEQ
     bus.mem_write(0x2001, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(inst.cycles, 2);
 
@@ -94,7 +94,7 @@ fn test_beq() {
     cpu.status.insert(Flags::ZERO);
     bus.mem_write(0x20F0, 0xF0); // BEQ
     bus.mem_write(0x20F1, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2134);
     assert_eq!(inst.cycles, 4);
 }
@@ -109,7 +109,7 @@ fn test_bmi() {
     cpu.status.insert(Flags::NEGATIVE);
     bus.mem_write(0x2000, 0x30); // BMI
     bus.mem_write(0x2001, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2044);
     assert_eq!(inst.cycles, 3);
 
@@ -118,7 +118,7 @@ fn test_bmi() {
     cpu.status.remove(Flags::NEGATIVE);
     bus.mem_write(0x2000, 0x30); // BMI
     bus.mem_write(0x2001, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(inst.cycles, 2);
 
@@ -127,7 +127,7 @@ fn test_bmi() {
     cpu.status.insert(Flags::NEGATIVE);
     bus.mem_write(0x20F0, 0x30); // BMI
     bus.mem_write(0x20F1, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2134);
     assert_eq!(inst.cycles, 4);
 }
@@ -142,7 +142,7 @@ fn test_bne() {
     cpu.status.remove(Flags::ZERO);
     bus.mem_write(0x2000, 0xD0); // BNE
     bus.mem_write(0x2001, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2044);
     assert_eq!(inst.cycles, 3);
 
@@ -151,7 +151,7 @@ fn test_bne() {
     cpu.status.insert(Flags::ZERO);
     bus.mem_write(0x2000, 0xD0); // BNE
     bus.mem_write(0x2001, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(inst.cycles, 2);
 
@@ -160,7 +160,7 @@ fn test_bne() {
     cpu.status.remove(Flags::ZERO);
     bus.mem_write(0x20F0, 0xD0); // BNE
     bus.mem_write(0x20F1, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2134);
     assert_eq!(inst.cycles, 4);
 }
@@ -175,7 +175,7 @@ fn test_bpl() {
     cpu.status.remove(Flags::NEGATIVE);
     bus.mem_write(0x2000, 0x10); // BPL
     bus.mem_write(0x2001, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2044);
     assert_eq!(inst.cycles, 3);
 
@@ -184,7 +184,7 @@ fn test_bpl() {
     cpu.status.insert(Flags::NEGATIVE);
     bus.mem_write(0x2000, 0x10); // BPL
     bus.mem_write(0x2001, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(inst.cycles, 2);
 
@@ -193,7 +193,7 @@ fn test_bpl() {
     cpu.status.remove(Flags::NEGATIVE);
     bus.mem_write(0x20F0, 0x10); // BPL
     bus.mem_write(0x20F1, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2134);
     assert_eq!(inst.cycles, 4);
 }
@@ -208,7 +208,7 @@ fn test_bvc() {
     cpu.status.remove(Flags::OVERFLOW);
     bus.mem_write(0x2000, 0x50); // BVC
     bus.mem_write(0x2001, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2044);
     assert_eq!(inst.cycles, 3);
 
@@ -217,7 +217,7 @@ fn test_bvc() {
     cpu.status.insert(Flags::OVERFLOW);
     bus.mem_write(0x2000, 0x50); // BVC
     bus.mem_write(0x2001, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(inst.cycles, 2);
 
@@ -226,7 +226,7 @@ fn test_bvc() {
     cpu.status.remove(Flags::OVERFLOW);
     bus.mem_write(0x20F0, 0x50); // BVC
     bus.mem_write(0x20F1, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2134);
     assert_eq!(inst.cycles, 4);
 }
@@ -241,7 +241,7 @@ fn test_bvs() {
     cpu.status.insert(Flags::OVERFLOW);
     bus.mem_write(0x2000, 0x70); // BVS
     bus.mem_write(0x2001, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2044);
     assert_eq!(inst.cycles, 3);
 
@@ -250,7 +250,7 @@ fn test_bvs() {
     cpu.status.remove(Flags::OVERFLOW);
     bus.mem_write(0x2000, 0x70); // BVS
     bus.mem_write(0x2001, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(inst.cycles, 2);
 
@@ -259,7 +259,113 @@ fn test_bvs() {
     cpu.status.insert(Flags::OVERFLOW);
     bus.mem_write(0x20F0, 0x70); // BVS
     bus.mem_write(0x20F1, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2134);
     assert_eq!(inst.cycles, 4);
-} 
\ No newline at end of file
+} 
+
+#[test]
+fn test_bra() {
+    let mut cpu = CPU::new();
+    let mut bus = FakeBus::new();
+
+    // Always taken
+    cpu.pc = 0x2000;
+    bus.mem_write(0x2000, 0x80); // BRA
+    bus.mem_write(0x2001, 0x42);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x2044);
+    assert_eq!(inst.cycles, 3);
+
+    // Backwards
+    cpu.pc = 0x2010;
+    bus.mem_write(0x2010, 0x80); // BRA
+    bus.mem_write(0x2011, 0xFE);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x2010);
+    assert_eq!(inst.cycles, 3);
+
+    // Page cross
+    cpu.pc = 0x20F0;
+    bus.mem_write(0x20F0, 0x80); // BRA
+    bus.mem_write(0x20F1, 0x42);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x2134);
+    assert_eq!(inst.cycles, 4);
+}
+
+#[test]
+fn test_bbr() {
+    let mut cpu = CPU::new();
+    let mut bus = FakeBus::new();
+
+    for bit in 0..8 {
+        // Branch taken
+        cpu.pc = 0x2000;
+        bus.mem_write(0x2000, 0x0F + (bit << 4)); // BBRn
+        bus.mem_write(0x2001, 0x10);
+        bus.mem_write(0x2002, 0x42);
+        bus.mem_write(0x0010, !(1 << bit));
+        let inst = cpu.exec(&mut bus).unwrap();
+        assert_eq!(cpu.pc, 0x2045);
+        assert_eq!(inst.cycles, 6);
+
+        // Branch not taken
+        cpu.pc = 0x2000;
+        bus.mem_write(0x0010, 1 << bit);
+        let inst = cpu.exec(&mut bus).unwrap();
+        assert_eq!(cpu.pc, 0x2003);
+        assert_eq!(inst.cycles, 5);
+    }
+
+    // Branch taken, page cross
+    cpu.pc = 0x20F0;
+    bus.mem_write(0x20F0, 0x5F); // BBR5
+    bus.mem_write(0x20F1, 0x10);
+    bus.mem_write(0x20F2, 0x42);
+    bus.mem_write(0x0010, 0x00);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x2135);
+    assert_eq!(inst.cycles, 7);
+
+    // Disassembly shows the zero page address and the branch target
+    assert_eq!(inst.opcode, Opcode::BBR5);
+    assert_eq!(inst.to_string(), "BBR5 $10,$2135");
+}
+
+#[test]
+fn test_bbs() {
+    let mut cpu = CPU::new();
+    let mut bus = FakeBus::new();
+
+    for bit in 0..8 {
+        // Branch taken
+        cpu.pc = 0x2000;
+        bus.mem_write(0x2000, 0x8F + (bit << 4)); // BBSn
+        bus.mem_write(0x2001, 0x10);
+        bus.mem_write(0x2002, 0x42);
+        bus.mem_write(0x0010, 1 << bit);
+        let inst = cpu.exec(&mut bus).unwrap();
+        assert_eq!(cpu.pc, 0x2045);
+        assert_eq!(inst.cycles, 6);
+
+        // Branch not taken
+        cpu.pc = 0x2000;
+        bus.mem_write(0x0010, !(1 << bit));
+        let inst = cpu.exec(&mut bus).unwrap();
+        assert_eq!(cpu.pc, 0x2003);
+        assert_eq!(inst.cycles, 5);
+    }
+
+    // Backwards
+    cpu.pc = 0x2010;
+    bus.mem_write(0x2010, 0x8F); // BBS0
+    bus.mem_write(0x2011, 0x10);
+    bus.mem_write(0x2012, 0xFD);
+    bus.mem_write(0x0010, 0x01);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x2010);
+    assert_eq!(inst.cycles, 6);
+    assert_eq!(inst.opcode, Opcode::BBS0);
+    assert_eq!(inst.to_string(), "BBS0 $10,$2010");
+}
@@ -10,7 +10,7 @@ fn test_jmp() {
     bus.mem_write(0x2000, 0x4C); // JMP absolute
     bus.mem_write(0x2001, 0x34);
     bus.mem_write(0x2002, 0x12);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x1234);
     assert_eq!(inst.cycles, 3);
 
@@ -21,9 +21,21 @@ fn test_jmp() {
     bus.mem_write(0x2002, 0x12);
     bus.mem_write(0x1234, 0x78);
     bus.mem_write(0x1235, 0x56);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x5678);
-    assert_eq!(inst.cycles, 5);
+    assert_eq!(inst.cycles, 6);
+
+    // Absolute indexed indirect
+    cpu.pc = 0x2000;
+    cpu.x = 0x04;
+    bus.mem_write(0x2000, 0x7C); // JMP (absolute,X)
+    bus.mem_write(0x2001, 0x30);
+    bus.mem_write(0x2002, 0x12);
+    bus.mem_write(0x1234, 0xBC);
+    bus.mem_write(0x1235, 0x9A);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x9ABC);
+    assert_eq!(inst.cycles, 6);
 }
 
 #[test]
@@ -36,7 +48,7 @@ fn test_jsr() {
     bus.mem_write(0x2000, 0x20); // JSR absolute
     bus.mem_write(0x2001, 0x34);
     bus.mem_write(0x2002, 0x12);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x1234);
     assert_eq!(cpu.sp, 0xFD);
     assert_eq!(bus.mem_read(0x01FF), 0x20);
@@ -54,7 +66,7 @@ fn test_rts() {
     bus.mem_write(0x01FF, 0x20);
     bus.mem_write(0x01FE, 0x02);
     bus.mem_write(0x2000, 0x60); // RTS
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(cpu.sp, 0xFF);
     assert_eq!(inst.cycles, 6);
@@ -71,10 +83,10 @@ fn test_rti() {
     bus.mem_write(0x01FE, 0x02);
     bus.mem_write(0x01FD, 0x00);
     bus.mem_write(0x2000, 0x40); // RTI
-    let inst = cpu.exec(&mut bus);
-    assert_eq!(cpu.pc, 0x2003);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.sp, 0xFF);
-    assert_eq!(cpu.status.bits(), 0x00);
+    assert_eq!(cpu.status.bits(), 0x20);
     assert_eq!(inst.cycles, 6);
 }
 
@@ -88,7 +100,7 @@ fn test_brk() {
     bus.mem_write(0x2000, 0x00); // BRK
     bus.mem_write(0xFFFE, 0x34);
     bus.mem_write(0xFFFF, 0x12);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x1234);
     assert_eq!(cpu.sp, 0xFC);
     assert!(cpu.status.contains(Flags::INTERRUPT));
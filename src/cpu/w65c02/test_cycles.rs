@@ -0,0 +1,233 @@
+use std::cell::Cell;
+
+use crate::cpu::w65c02::{Access, Bus, BusCycle, CPU, Variant, bus::FakeBus, cpu::Flags};
+
+use Access::{Read as R, Write as W};
+
+/// A bus that records every cycle reported by the CPU, and counts the reads it serves.
+struct RecordingBus {
+    mem: FakeBus,
+    cycles: Vec<BusCycle>,
+    reads: Cell<usize>,
+}
+
+impl RecordingBus {
+    fn new() -> Self {
+        Self { mem: FakeBus::new(), cycles: Vec::new(), reads: Cell::new(0) }
+    }
+
+    /// The recorded cycles as (address, data, access) tuples.
+    fn trace(&self) -> Vec<(u16, u8, Access)> {
+        self.cycles.iter().map(|c| (c.addr, c.data, c.access)).collect()
+    }
+}
+
+impl Bus for RecordingBus {
+    fn mem_read(&self, addr: u16) -> u8 {
+        self.reads.set(self.reads.get() + 1);
+        self.mem.mem_read(addr)
+    }
+    fn mem_write(&mut self, addr: u16, val: u8) { self.mem.mem_write(addr, val) }
+    fn cycle(&mut self, cycle: BusCycle) { self.cycles.push(cycle) }
+}
+
+fn setup(variant: Variant, program: &[u8]) -> (CPU, RecordingBus) {
+    let mut cpu = CPU::with_variant(variant);
+    let mut bus = RecordingBus::new();
+    for (i, byte) in program.iter().enumerate() {
+        bus.mem_write(0x2000 + i as u16, *byte);
+    }
+    cpu.pc = 0x2000;
+    cpu.sp = 0xFF;
+    cpu.set_cycle_accurate(true);
+    (cpu, bus)
+}
+
+#[test]
+fn test_disabled_by_default() {
+    let (mut cpu, mut bus) = setup(Variant::W65C02, &[0xEE, 0x00, 0x40]); // INC $4000
+    cpu.set_cycle_accurate(false);
+    cpu.exec(&mut bus).unwrap();
+    assert!(bus.cycles.is_empty());
+}
+
+#[test]
+fn test_opcode_fetch_sync() {
+    let (mut cpu, mut bus) = setup(Variant::W65C02, &[0xA9, 0x42]); // LDA #$42
+    cpu.exec(&mut bus).unwrap();
+    assert_eq!(bus.trace(), [(0x2000, 0xA9, R), (0x2001, 0x42, R)]);
+    assert!(bus.cycles[0].sync);
+    assert!(!bus.cycles[1].sync);
+}
+
+#[test]
+fn test_implied_dummy_read() {
+    let (mut cpu, mut bus) = setup(Variant::W65C02, &[0xE8, 0xEA]); // INX
+    cpu.exec(&mut bus).unwrap();
+    assert_eq!(bus.trace(), [(0x2000, 0xE8, R), (0x2001, 0xEA, R)]);
+}
+
+#[test]
+fn test_rmw_cmos_reads_twice() {
+    let (mut cpu, mut bus) = setup(Variant::W65C02, &[0xEE, 0x00, 0x40]); // INC $4000
+    bus.mem_write(0x4000, 0x41);
+    cpu.exec(&mut bus).unwrap();
+    assert_eq!(bus.trace(), [
+        (0x2000, 0xEE, R),
+        (0x2001, 0x00, R),
+        (0x2002, 0x40, R),
+        (0x4000, 0x41, R),
+        (0x4000, 0x41, R),
+        (0x4000, 0x42, W),
+    ]);
+}
+
+#[test]
+fn test_rmw_nmos_writes_twice() {
+    let (mut cpu, mut bus) = setup(Variant::NMOS6502, &[0xEE, 0x00, 0x40]); // INC $4000
+    bus.mem_write(0x4000, 0x41);
+    cpu.exec(&mut bus).unwrap();
+    assert_eq!(bus.trace(), [
+        (0x2000, 0xEE, R),
+        (0x2001, 0x00, R),
+        (0x2002, 0x40, R),
+        (0x4000, 0x41, R),
+        (0x4000, 0x41, W),
+        (0x4000, 0x42, W),
+    ]);
+}
+
+#[test]
+fn test_page_cross_dummy_read() {
+    // LDA $40F0,X crossing into page 0x41
+    let program = [0xBD, 0xF0, 0x40];
+
+    let (mut cpu, mut bus) = setup(Variant::W65C02, &program);
+    cpu.x = 0x20;
+    cpu.exec(&mut bus).unwrap();
+    assert_eq!(bus.cycles[3].addr, 0x2002);
+    assert_eq!(bus.cycles[4].addr, 0x4110);
+
+    let (mut cpu, mut bus) = setup(Variant::NMOS6502, &program);
+    cpu.x = 0x20;
+    cpu.exec(&mut bus).unwrap();
+    assert_eq!(bus.cycles[3].addr, 0x4010);
+    assert_eq!(bus.cycles[4].addr, 0x4110);
+}
+
+#[test]
+fn test_page_cross_reads_pointer_once() {
+    // LDA ($C0),Y crossing into page 0x41
+    let (mut cpu, mut bus) = setup(Variant::W65C02, &[0xB1, 0xC0]);
+    bus.mem_write(0x00C0, 0xF0);
+    bus.mem_write(0x00C1, 0x40);
+    cpu.y = 0x20;
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.cycles, 6);
+    assert_eq!(bus.reads.get(), bus.cycles.len());
+}
+
+#[test]
+fn test_store_always_takes_index_cycle() {
+    // STA $4000,X without crossing a page
+    let (mut cpu, mut bus) = setup(Variant::NMOS6502, &[0x9D, 0x00, 0x40]);
+    cpu.x = 0x10;
+    cpu.a = 0x55;
+    cpu.exec(&mut bus).unwrap();
+    assert_eq!(bus.trace()[3..], [(0x4010, 0x00, R), (0x4010, 0x55, W)]);
+}
+
+#[test]
+fn test_stack_pull() {
+    let (mut cpu, mut bus) = setup(Variant::W65C02, &[0x68]); // PLA
+    cpu.sp = 0xFC;
+    bus.mem_write(0x01FD, 0x99);
+    cpu.exec(&mut bus).unwrap();
+    assert_eq!(bus.trace(), [
+        (0x2000, 0x68, R),
+        (0x2001, 0x00, R),
+        (0x01FC, 0x00, R),
+        (0x01FD, 0x99, R),
+    ]);
+}
+
+#[test]
+fn test_jsr() {
+    let (mut cpu, mut bus) = setup(Variant::W65C02, &[0x20, 0x34, 0x12]); // JSR $1234
+    cpu.exec(&mut bus).unwrap();
+    assert_eq!(bus.trace(), [
+        (0x2000, 0x20, R),
+        (0x2001, 0x34, R),
+        (0x01FF, 0x00, R),
+        (0x01FF, 0x20, W),
+        (0x01FE, 0x02, W),
+        (0x2002, 0x12, R),
+    ]);
+    assert_eq!(cpu.pc, 0x1234);
+}
+
+#[test]
+fn test_irq_vector_pull() {
+    let (mut cpu, mut bus) = setup(Variant::W65C02, &[0xEA]);
+    bus.mem_write(0xFFFE, 0x00);
+    bus.mem_write(0xFFFF, 0x30);
+    cpu.set_irq(true);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(bus.cycles.len(), inst.cycles);
+    assert_eq!(bus.trace(), [
+        (0x2000, 0xEA, R),
+        (0x2000, 0xEA, R),
+        (0x01FF, 0x20, W),
+        (0x01FE, 0x00, W),
+        (0x01FD, 0x20, W),
+        (0xFFFE, 0x00, R),
+        (0xFFFF, 0x30, R),
+    ]);
+    let vpb: Vec<bool> = bus.cycles.iter().map(|c| c.vpb).collect();
+    assert_eq!(vpb, [false, false, false, false, false, true, true]);
+}
+
+#[test]
+fn test_cycle_count_matches_instruction() {
+    let variants = [Variant::NMOS6502, Variant::W65C02, Variant::R65C02, Variant::G65SC02];
+    let states = [
+        (0x00, Flags::empty()),
+        (0x80, Flags::all() - Flags::INTERRUPT),
+        (0xFF, Flags::DECIMAL),
+    ];
+    for variant in variants {
+        for code in 0..=255 {
+            for (index, status) in states {
+                // Operands and pointers close to a page boundary, so indexing may cross it
+                let (mut cpu, mut bus) = setup(variant, &[code, 0xC0, 0x40]);
+                bus.mem_write(0x00C0, 0xF0);
+                bus.mem_write(0x00C1, 0x30);
+                cpu.x = index;
+                cpu.y = index;
+                cpu.status = status;
+                let inst = cpu.exec(&mut bus).unwrap();
+                assert_eq!(bus.cycles.len(), inst.cycles,
+                    "{:?} opcode {:02X} with index {:02X} and status {:?}", variant, code, index, status);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_reset() {
+    let (mut cpu, mut bus) = setup(Variant::W65C02, &[0xEA]);
+    bus.mem_write(0xFFFC, 0x00);
+    bus.mem_write(0xFFFD, 0x30);
+    let inst = cpu.reset(&mut bus);
+    assert_eq!(bus.cycles.len(), inst.cycles);
+    assert_eq!(bus.trace(), [
+        (0x2000, 0xEA, R),
+        (0x2000, 0xEA, R),
+        (0x01FF, 0x00, R),
+        (0x01FE, 0x00, R),
+        (0x01FD, 0x00, R),
+        (0xFFFC, 0x00, R),
+        (0xFFFD, 0x30, R),
+    ]);
+    assert!(bus.cycles[5].vpb && bus.cycles[6].vpb);
+}
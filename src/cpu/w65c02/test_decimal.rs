@@ -0,0 +1,286 @@
+use crate::cpu::w65c02::{Assembler, Bus, CPU, StopReason, Variant, bus::FakeBus, cpu::Flags};
+
+const ARITH_FLAGS: Flags = Flags::NEGATIVE.union(Flags::OVERFLOW).union(Flags::ZERO).union(Flags::CARRY);
+
+/// Bruce Clark's decimal mode test for the 65C02, from appendix B of his "Decimal Mode"
+/// tutorial (http://www.6502.org/tutorials/decimal_mode.html). It runs ADC and SBC in decimal
+/// mode on every accumulator, operand and carry input, and compares the results with the ones
+/// it predicts using binary arithmetic, so the expected values do not come from our decimal
+/// mode code. ERROR is left at 0 if every result matched.
+const DECIMAL_TEST: &str = "
+ERROR = $00
+N1    = $01
+N2    = $02
+N1L   = $03
+N1H   = $04
+N2L   = $05
+N2H   = $06         ; two bytes
+DA    = $08
+DNVZC = $09
+HA    = $0A
+HNVZC = $0B
+AR    = $0C
+NF    = $0D
+VF    = $0E
+ZF    = $0F
+CF    = $10
+
+        .org $0200
+        jsr test
+        stp
+
+test:   ldy #1          ; loop through both values of the carry flag
+        sty ERROR       ; 1 until the test passes
+        lda #0
+        sta N1
+        sta N2
+loop1:  lda N2
+        and #$0F
+        sta N2L
+        lda N2
+        and #$F0
+        sta N2H
+        ora #$0F        ; N2H+1 = (N2 & $F0) + $0F
+        sta N2H+1
+loop2:  lda N1
+        and #$0F
+        sta N1L
+        lda N1
+        and #$F0
+        sta N1H
+        jsr add
+        jsr a65c02
+        jsr compare
+        bne done
+        jsr sub
+        jsr s65c02
+        jsr compare
+        bne done
+        inc N1
+        bne loop2       ; all 256 values of N1
+        inc N2
+        bne loop1       ; all 256 values of N2
+        dey
+        bpl loop1
+        lda #0
+        sta ERROR
+done:   rts
+
+; Actual decimal results, binary results and predicted accumulator, carry and overflow of N1+N2
+add:    sed
+        cpy #1          ; carry set if Y = 1
+        lda N1
+        adc N2
+        sta DA
+        php
+        pla
+        sta DNVZC
+        cld
+        cpy #1
+        lda N1
+        adc N2
+        sta HA
+        php
+        pla
+        sta HNVZC
+        cpy #1
+        lda N1L
+        adc N2L
+        cmp #$0A
+        ldx #0
+        bcc a1
+        inx
+        adc #5          ; add 6, carry is set
+        and #$0F
+        sec
+a1:     ora N1H
+        adc N2H,x       ; add N2 & $F0, or (N2 & $F0) + $10 if the low digit carried
+        php
+        bcs a2
+        cmp #$A0
+        bcc a3
+a2:     adc #$5F        ; add $60, carry is set
+        sec
+a3:     sta AR
+        php
+        pla
+        sta CF
+        pla             ; all the flags of the binary sum, of which V is the prediction
+        sta VF
+        rts
+
+; Actual decimal results and binary results of N1-N2
+sub:    sed
+        cpy #1
+        lda N1
+        sbc N2
+        sta DA
+        php
+        pla
+        sta DNVZC
+        cld
+        cpy #1
+        lda N1
+        sbc N2
+        sta HA
+        php
+        pla
+        sta HNVZC
+        rts
+
+; Predicted accumulator of N1-N2
+sub2:   cpy #1
+        lda N1L
+        sbc N2L
+        ldx #0
+        bcs s21
+        inx
+        and #$0F
+        clc
+s21:    ora N1H
+        sbc N2H,x       ; subtract N2 & $F0, or (N2 & $F0) + $10 if the low digit borrowed
+        bcs s22
+        sbc #$5F        ; subtract $60, carry is clear
+s22:    cpx #0          ; sets the carry
+        beq s23
+        sbc #6
+s23:    sta AR
+        rts
+
+; Z is clear if the actual and predicted results differ
+compare:
+        lda DA
+        cmp AR
+        bne c1
+        lda DNVZC
+        eor NF
+        and #$80
+        bne c1
+        lda DNVZC
+        eor VF
+        and #$40
+        bne c1
+        lda DNVZC
+        eor ZF
+        and #2
+        bne c1
+        lda DNVZC
+        eor CF
+        and #1
+c1:     rts
+
+; On the 65C02, N and Z come from the decimal result
+a65c02: lda AR
+        php
+        pla
+        sta NF
+        sta ZF
+        rts
+
+s65c02: jsr sub2
+        lda AR
+        php
+        pla
+        sta NF
+        sta ZF
+        lda HNVZC       ; V and C are the same as in binary mode
+        sta VF
+        sta CF
+        rts
+";
+
+fn exec_decimal(cpu: &mut CPU, bus: &mut FakeBus, opcode: u8, a: u8, b: u8, c: bool) -> (u8, Flags) {
+    cpu.pc = 0x2000;
+    cpu.a = a;
+    cpu.status = Flags::DECIMAL;
+    cpu.status.set(Flags::CARRY, c);
+    bus.mem_write(0x2000, opcode);
+    bus.mem_write(0x2001, b);
+    cpu.exec(bus).unwrap();
+    (cpu.a, cpu.status & ARITH_FLAGS)
+}
+
+fn to_bcd(n: u32) -> u8 {
+    (((n / 10) % 10) << 4 | (n % 10)) as u8
+}
+
+fn from_bcd(n: u8) -> u32 {
+    (n >> 4) as u32 * 10 + (n & 0x0F) as u32
+}
+
+#[test]
+fn test_decimal_exhaustive() {
+    let program = Assembler::new(Variant::W65C02).assemble(DECIMAL_TEST).unwrap();
+    let mut cpu = CPU::new();
+    let mut bus = FakeBus::new();
+    program.load(&mut bus);
+    cpu.pc = 0x0200;
+    cpu.sp = 0xFF;
+
+    let stop = cpu.run_for(&mut bus, 500_000_000).unwrap();
+    assert_eq!(stop, StopReason::Stopped);
+    let mem = |name: &str| bus.mem_read(program.symbols[name]);
+    // The binary result tells which operation failed, as N1+N2+C and N1-N2-1+C never match
+    let adc = mem("HA") == mem("N1").wrapping_add(mem("N2")).wrapping_add(cpu.y);
+    assert_eq!(mem("ERROR"), 0,
+        "{} ${:02X} with A=${:02X} C={}: got A=${:02X} P=${:02X}, expected A=${:02X} N={} V={} Z={} C={}",
+        if adc { "ADC" } else { "SBC" },
+        mem("N2"), mem("N1"), cpu.y, mem("DA"), mem("DNVZC"), mem("AR"),
+        mem("NF") >> 7, (mem("VF") >> 6) & 1, (mem("ZF") >> 1) & 1, mem("CF") & 1);
+}
+
+#[test]
+fn test_decimal_valid_bcd() {
+    let mut cpu = CPU::new();
+    let mut bus = FakeBus::new();
+
+    // With valid BCD inputs the results match plain decimal arithmetic
+    for a in 0..100 {
+        for b in 0..100 {
+            for c in [false, true] {
+                let sum = a + b + c as u32;
+                let (result, flags) = exec_decimal(&mut cpu, &mut bus, 0x69, to_bcd(a), to_bcd(b), c);
+                assert_eq!(from_bcd(result), sum % 100);
+                assert_eq!(flags.contains(Flags::CARRY), sum >= 100);
+                assert_eq!(flags.contains(Flags::ZERO), result == 0);
+
+                let diff = a as i32 - b as i32 - !c as i32;
+                let (result, flags) = exec_decimal(&mut cpu, &mut bus, 0xE9, to_bcd(a), to_bcd(b), c);
+                assert_eq!(from_bcd(result) as i32, diff.rem_euclid(100));
+                assert_eq!(flags.contains(Flags::CARRY), diff >= 0);
+                assert_eq!(flags.contains(Flags::ZERO), result == 0);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_decimal_flags() {
+    let mut cpu = CPU::new();
+    let mut bus = FakeBus::new();
+
+    // 99 + 1: N and Z come from the decimal result, unlike the NMOS 6502
+    let (result, flags) = exec_decimal(&mut cpu, &mut bus, 0x69, 0x99, 0x01, false);
+    assert_eq!(result, 0x00);
+    assert_eq!(flags, Flags::ZERO | Flags::CARRY);
+
+    // 79 + 0 + 1: decimal result is negative, binary overflow
+    let (result, flags) = exec_decimal(&mut cpu, &mut bus, 0x69, 0x79, 0x00, true);
+    assert_eq!(result, 0x80);
+    assert_eq!(flags, Flags::NEGATIVE | Flags::OVERFLOW);
+
+    // Invalid BCD operands
+    let (result, flags) = exec_decimal(&mut cpu, &mut bus, 0x69, 0x0F, 0x01, false);
+    assert_eq!(result, 0x16);
+    assert_eq!(flags, Flags::empty());
+
+    // 0 - 1: borrows into 99
+    let (result, flags) = exec_decimal(&mut cpu, &mut bus, 0xE9, 0x00, 0x01, true);
+    assert_eq!(result, 0x99);
+    assert_eq!(flags, Flags::NEGATIVE);
+
+    // 80 - 1: binary overflow, decimal result is positive
+    let (result, flags) = exec_decimal(&mut cpu, &mut bus, 0xE9, 0x80, 0x01, true);
+    assert_eq!(result, 0x79);
+    assert_eq!(flags, Flags::OVERFLOW | Flags::CARRY);
+}
@@ -0,0 +1,84 @@
+use crate::cpu::w65c02::{Bus, CPU, Variant, bus::FakeBus, disassemble, disassemble_range};
+use crate::cpu::w65c02::inst::{Opcode, Operand};
+
+fn bus_with(addr: u16, code: &[u8]) -> FakeBus {
+    let mut bus = FakeBus::new();
+    for (i, byte) in code.iter().enumerate() {
+        bus.mem_write(addr + i as u16, *byte);
+    }
+    bus
+}
+
+#[test]
+fn test_disassemble_immediate() {
+    let bus = bus_with(0x2000, &[0xA9, 0x42]);
+    let inst = disassemble(&bus, Variant::W65C02, 0x2000).unwrap();
+    assert_eq!(inst.opcode, Opcode::LDA);
+    assert_eq!(inst.operand, Operand::Immediate(0x42));
+    assert_eq!(inst.len, 2);
+    assert_eq!(inst.cycles, 2);
+    assert_eq!(inst.to_string(), "LDA #$42");
+}
+
+#[test]
+fn test_disassemble_relative() {
+    let bus = bus_with(0x2000, &[0xD0, 0xFE]); // BNE to itself
+    let inst = disassemble(&bus, Variant::W65C02, 0x2000).unwrap();
+    assert_eq!(inst.operand, Operand::Relative(0x2000));
+    assert_eq!(inst.len, 2);
+}
+
+#[test]
+fn test_disassemble_zero_page_relative() {
+    let bus = bus_with(0x2000, &[0x0F, 0x12, 0x05]); // BBR0 $12,+5
+    let inst = disassemble(&bus, Variant::W65C02, 0x2000).unwrap();
+    assert_eq!(inst.to_string(), "BBR0 $12,$2008");
+    assert_eq!(inst.len, 3);
+}
+
+#[test]
+fn test_disassemble_variant() {
+    let bus = bus_with(0x2000, &[0xA7, 0x42]);
+    let inst = disassemble(&bus, Variant::W65C02, 0x2000).unwrap();
+    assert_eq!(inst.opcode, Opcode::SMB2);
+    let inst = disassemble(&bus, Variant::NMOS6502, 0x2000).unwrap();
+    assert_eq!(inst.opcode, Opcode::LAX);
+}
+
+#[test]
+fn test_disassemble_range() {
+    let bus = bus_with(0x2000, &[
+        0xA2, 0x00,         // LDX #$00
+        0xBD, 0x00, 0x30,   // LDA $3000,X
+        0xE8,               // INX
+    ]);
+    let insts = disassemble_range(&bus, Variant::W65C02, 0x2000, 0x2005).unwrap();
+    let text: Vec<String> = insts.iter().map(|inst| inst.to_string()).collect();
+    assert_eq!(text, ["LDX #$00", "LDA $3000,X", "INX "]);
+    assert_eq!(insts[1].addr, 0x2002);
+    assert_eq!(insts[1].next_addr(), 0x2005);
+
+    // The last instruction may extend past the end of the range
+    let insts = disassemble_range(&bus, Variant::W65C02, 0x2000, 0x2003).unwrap();
+    assert_eq!(insts.len(), 2);
+}
+
+#[test]
+fn test_disassemble_matches_exec() {
+    for variant in [Variant::NMOS6502, Variant::W65C02, Variant::R65C02, Variant::G65SC02] {
+        for code in 0..=255 {
+            let mut bus = bus_with(0x2000, &[code, 0x34, 0x12]);
+            let disassembled = disassemble(&bus, variant, 0x2000).unwrap();
+
+            let mut cpu = CPU::with_variant(variant);
+            cpu.pc = 0x2000;
+            cpu.sp = 0xFF;
+            let executed = cpu.exec(&mut bus).unwrap();
+            let context = format!("{:?} opcode {:02X}", variant, code);
+            assert_eq!(disassembled.opcode, executed.opcode, "{}", context);
+            assert_eq!(disassembled.operand, executed.operand, "{}", context);
+            assert_eq!(disassembled.len, executed.len(), "{}", context);
+            assert!(disassembled.cycles <= executed.cycles, "{}", context);
+        }
+    }
+}
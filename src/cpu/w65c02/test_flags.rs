@@ -8,7 +8,7 @@ fn test_clc() {
     cpu.pc = 0x2000;
     cpu.status.set(Flags::CARRY, true);
     bus.mem_write(0x2000, 0x18); // CLC
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert!(!cpu.status.contains(Flags::CARRY));
     assert!(matches!(inst.opcode, Opcode::CLC));
@@ -23,7 +23,7 @@ fn test_cld() {
     cpu.pc = 0x2000;
     cpu.status.set(Flags::DECIMAL, true);
     bus.mem_write(0x2000, 0xD8); // CLD
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert!(!cpu.status.contains(Flags::DECIMAL));
     assert!(matches!(inst.opcode, Opcode::CLD));
@@ -38,7 +38,7 @@ fn test_cli() {
     cpu.pc = 0x2000;
     cpu.status.set(Flags::INTERRUPT, true);
     bus.mem_write(0x2000, 0x58); // CLI
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert!(!cpu.status.contains(Flags::INTERRUPT));
     assert!(matches!(inst.opcode, Opcode::CLI));
@@ -53,7 +53,7 @@ fn test_clv() {
     cpu.pc = 0x2000;
     cpu.status.set(Flags::OVERFLOW, true);
     bus.mem_write(0x2000, 0xB8); // CLV
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert!(!cpu.status.contains(Flags::OVERFLOW));
     assert!(matches!(inst.opcode, Opcode::CLV));
@@ -68,7 +68,7 @@ fn test_sec() {
     cpu.pc = 0x2000;
     cpu.status.set(Flags::CARRY, false);
     bus.mem_write(0x2000, 0x38); // SEC
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert!(cpu.status.contains(Flags::CARRY));
     assert!(matches!(inst.opcode, Opcode::SEC));
@@ -83,7 +83,7 @@ fn test_sed() {
     cpu.pc = 0x2000;
     cpu.status.set(Flags::DECIMAL, false);
     bus.mem_write(0x2000, 0xF8); // SED
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert!(cpu.status.contains(Flags::DECIMAL));
     assert!(matches!(inst.opcode, Opcode::SED));
@@ -98,7 +98,7 @@ fn test_sei() {
     cpu.pc = 0x2000;
     cpu.status.set(Flags::INTERRUPT, false);
     bus.mem_write(0x2000, 0x78); // SEI
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert!(cpu.status.contains(Flags::INTERRUPT));
     assert!(matches!(inst.opcode, Opcode::SEI));
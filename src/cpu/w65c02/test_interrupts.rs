@@ -0,0 +1,335 @@
+use crate::cpu::{ExecError, ExecErrorKind};
+use rand::{SeedableRng, rngs::StdRng};
+
+use crate::cpu::w65c02::{Bus, CPU, RunState, Variant, bus::FakeBus, cpu::Flags, inst::Opcode};
+
+struct IrqBus {
+    mem: FakeBus,
+    irq: bool,
+    nmi: bool,
+}
+
+impl Bus for IrqBus {
+    fn mem_read(&self, addr: u16) -> u8 { self.mem.mem_read(addr) }
+    fn mem_write(&mut self, addr: u16, val: u8) { self.mem.mem_write(addr, val) }
+    fn irq(&self) -> bool { self.irq }
+    fn nmi(&self) -> bool { self.nmi }
+}
+
+#[test]
+fn test_irq() {
+    let mut cpu = CPU::new();
+    let mut bus = FakeBus::new();
+    bus.mem_write(0xFFFE, 0x34);
+    bus.mem_write(0xFFFF, 0x12);
+    bus.mem_write(0x2000, 0xEA); // NOP
+
+    // Masked
+    cpu.pc = 0x2000;
+    cpu.sp = 0xFF;
+    cpu.status = Flags::INTERRUPT;
+    cpu.set_irq(true);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::NOP);
+    assert_eq!(cpu.pc, 0x2001);
+
+    // Taken
+    cpu.pc = 0x2000;
+    cpu.status = Flags::DECIMAL | Flags::CARRY;
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::IRQ);
+    assert_eq!(inst.cycles, 7);
+    assert_eq!(cpu.pc, 0x1234);
+    assert_eq!(cpu.sp, 0xFC);
+    assert_eq!(bus.mem_read(0x01FF), 0x20);
+    assert_eq!(bus.mem_read(0x01FE), 0x00);
+    assert_eq!(bus.mem_read(0x01FD), (Flags::UNUSED | Flags::DECIMAL | Flags::CARRY).bits());
+    assert!(cpu.status.contains(Flags::INTERRUPT));
+    assert!(!cpu.status.contains(Flags::DECIMAL));
+
+    // Level-triggered: taken again once the handler clears the mask
+    cpu.pc = 0x2000;
+    cpu.status.remove(Flags::INTERRUPT);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::IRQ);
+
+    // Released
+    cpu.pc = 0x2000;
+    cpu.status.remove(Flags::INTERRUPT);
+    cpu.set_irq(false);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::NOP);
+}
+
+#[test]
+fn test_nmi() {
+    let mut cpu = CPU::new();
+    let mut bus = FakeBus::new();
+    bus.mem_write(0xFFFA, 0x78);
+    bus.mem_write(0xFFFB, 0x56);
+    bus.mem_write(0x2000, 0xEA); // NOP
+
+    // Taken even if interrupts are masked
+    cpu.pc = 0x2000;
+    cpu.sp = 0xFF;
+    cpu.status = Flags::INTERRUPT;
+    cpu.set_nmi(true);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::NMI);
+    assert_eq!(inst.cycles, 7);
+    assert_eq!(cpu.pc, 0x5678);
+    assert_eq!(cpu.sp, 0xFC);
+    assert_eq!(bus.mem_read(0x01FF), 0x20);
+    assert_eq!(bus.mem_read(0x01FE), 0x00);
+    assert!(!Flags::from_bits_truncate(bus.mem_read(0x01FD)).contains(Flags::BREAK));
+
+    // Edge-triggered: holding the line does not interrupt again
+    cpu.pc = 0x2000;
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::NOP);
+
+    // A new edge does
+    cpu.set_nmi(false);
+    cpu.set_nmi(true);
+    cpu.pc = 0x2000;
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::NMI);
+}
+
+#[test]
+fn test_nmi_before_irq() {
+    let mut cpu = CPU::new();
+    let mut bus = FakeBus::new();
+    bus.mem_write(0xFFFA, 0x78);
+    bus.mem_write(0xFFFB, 0x56);
+    bus.mem_write(0xFFFE, 0x34);
+    bus.mem_write(0xFFFF, 0x12);
+
+    cpu.pc = 0x2000;
+    cpu.sp = 0xFF;
+    cpu.set_irq(true);
+    cpu.set_nmi(true);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::NMI);
+    assert_eq!(cpu.pc, 0x5678);
+}
+
+#[test]
+fn test_rti_from_irq() {
+    let mut cpu = CPU::new();
+    let mut bus = FakeBus::new();
+    bus.mem_write(0xFFFE, 0x00);
+    bus.mem_write(0xFFFF, 0x30);
+    bus.mem_write(0x3000, 0x40); // RTI
+
+    cpu.pc = 0x2000;
+    cpu.sp = 0xFF;
+    cpu.status = Flags::CARRY;
+    cpu.set_irq(true);
+    cpu.exec(&mut bus).unwrap();
+    cpu.set_irq(false);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::RTI);
+    assert_eq!(cpu.pc, 0x2000);
+    assert_eq!(cpu.sp, 0xFF);
+    assert_eq!(cpu.status, Flags::CARRY | Flags::UNUSED);
+}
+
+#[test]
+fn test_bus_lines() {
+    let mut cpu = CPU::new();
+    let mut bus = IrqBus { mem: FakeBus::new(), irq: false, nmi: false };
+    bus.mem_write(0xFFFA, 0x78);
+    bus.mem_write(0xFFFB, 0x56);
+    bus.mem_write(0xFFFE, 0x34);
+    bus.mem_write(0xFFFF, 0x12);
+    bus.mem_write(0x2000, 0xEA); // NOP
+
+    // IRQ driven by a device
+    cpu.pc = 0x2000;
+    cpu.sp = 0xFF;
+    bus.irq = true;
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::IRQ);
+    assert_eq!(cpu.pc, 0x1234);
+    bus.irq = false;
+
+    // NMI driven by a device, only on the edge
+    cpu.pc = 0x2000;
+    bus.nmi = true;
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::NMI);
+    assert_eq!(cpu.pc, 0x5678);
+    cpu.pc = 0x2000;
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::NOP);
+}
+
+#[test]
+fn test_wai() {
+    let mut cpu = CPU::new();
+    let mut bus = FakeBus::new();
+    bus.mem_write(0xFFFE, 0x34);
+    bus.mem_write(0xFFFF, 0x12);
+    bus.mem_write(0x2000, 0xCB); // WAI
+    bus.mem_write(0x2001, 0xEA); // NOP
+
+    // Wait
+    cpu.pc = 0x2000;
+    cpu.sp = 0xFF;
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::WAI);
+    assert_eq!(inst.cycles, 3);
+    assert_eq!(cpu.pc, 0x2001);
+    assert_eq!(cpu.run_state(), RunState::Waiting);
+
+    // Idle until an interrupt arrives
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::WAI);
+    assert_eq!(inst.cycles, 1);
+    assert_eq!(cpu.pc, 0x2001);
+
+    // Woken up by an IRQ
+    cpu.set_irq(true);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::IRQ);
+    assert_eq!(cpu.pc, 0x1234);
+    assert_eq!(cpu.run_state(), RunState::Running);
+    assert_eq!(bus.mem_read(0x01FF), 0x20);
+    assert_eq!(bus.mem_read(0x01FE), 0x01);
+
+    // Woken up by a masked IRQ, which resumes after WAI without taking the interrupt
+    cpu.pc = 0x2000;
+    cpu.status = Flags::INTERRUPT;
+    cpu.set_irq(false);
+    cpu.exec(&mut bus).unwrap();
+    cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.run_state(), RunState::Waiting);
+    cpu.set_irq(true);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::NOP);
+    assert_eq!(cpu.pc, 0x2002);
+    assert_eq!(cpu.run_state(), RunState::Running);
+}
+
+#[test]
+fn test_stp() {
+    let mut cpu = CPU::new();
+    let mut bus = FakeBus::new();
+    bus.mem_write(0xFFFC, 0x00);
+    bus.mem_write(0xFFFD, 0x30);
+    bus.mem_write(0x2000, 0xDB); // STP
+
+    // Stop
+    cpu.pc = 0x2000;
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::STP);
+    assert_eq!(inst.cycles, 3);
+    assert_eq!(cpu.run_state(), RunState::Stopped);
+
+    // Interrupts do not restart the CPU
+    cpu.set_irq(true);
+    cpu.set_nmi(true);
+    let err = cpu.exec(&mut bus).unwrap_err();
+    assert_eq!(err, ExecError::new(ExecErrorKind::Stopped, 0x2000, &[0xDB]));
+    assert_eq!(cpu.pc, 0x2001);
+
+    // Reset does
+    cpu.reset(&mut bus);
+    assert_eq!(cpu.run_state(), RunState::Running);
+    assert_eq!(cpu.pc, 0x3000);
+}
+
+#[test]
+fn test_reset_keeps_registers() {
+    let mut cpu = CPU::new();
+    let mut bus = FakeBus::new();
+    bus.mem_write(0xFFFC, 0x00);
+    bus.mem_write(0xFFFD, 0x30);
+    cpu.a = 0x12;
+    cpu.x = 0x34;
+    cpu.y = 0x56;
+    cpu.sp = 0x80;
+    cpu.status = Flags::DECIMAL | Flags::CARRY;
+    cpu.pc = 0x2000;
+    bus.mem_write(0x0180, 0xAA);
+
+    let inst = cpu.reset(&mut bus);
+    assert_eq!(inst.opcode, Opcode::RESET);
+    assert_eq!(inst.cycles, 7);
+    assert_eq!((cpu.a, cpu.x, cpu.y), (0x12, 0x34, 0x56));
+    assert_eq!(cpu.sp, 0x7D);
+    assert_eq!(cpu.status, Flags::INTERRUPT | Flags::CARRY);
+    assert_eq!(cpu.pc, 0x3000);
+    // Nothing is pushed
+    assert_eq!(bus.mem_read(0x0180), 0xAA);
+}
+
+#[test]
+fn test_reset_nmos_keeps_decimal() {
+    let mut cpu = CPU::with_variant(Variant::NMOS6502);
+    let mut bus = FakeBus::new();
+    cpu.status = Flags::DECIMAL;
+    cpu.reset(&mut bus);
+    assert_eq!(cpu.status, Flags::DECIMAL | Flags::INTERRUPT);
+}
+
+#[test]
+fn test_power_on() {
+    let mut cpu = CPU::new();
+    let mut bus = FakeBus::new();
+    bus.mem_write(0xFFFC, 0x00);
+    bus.mem_write(0xFFFD, 0x30);
+    cpu.a = 0x12;
+    let inst = cpu.power_on(&mut bus);
+    assert_eq!(inst.cycles, 7);
+    assert_eq!((cpu.a, cpu.x, cpu.y), (0, 0, 0));
+    assert_eq!(cpu.sp, 0xFD);
+    assert_eq!(cpu.status, Flags::INTERRUPT | Flags::UNUSED);
+    assert_eq!(cpu.pc, 0x3000);
+}
+
+#[test]
+fn test_power_on_random() {
+    let mut bus = FakeBus::new();
+    bus.mem_write(0xFFFC, 0x00);
+    bus.mem_write(0xFFFD, 0x30);
+    let mut rng = StdRng::seed_from_u64(1);
+    let mut registers = Vec::new();
+    for _ in 0..8 {
+        let mut cpu = CPU::new();
+        cpu.power_on_random(&mut bus, &mut rng);
+        assert_eq!(cpu.pc, 0x3000);
+        assert!(cpu.status.contains(Flags::INTERRUPT));
+        assert!(!cpu.status.contains(Flags::DECIMAL));
+        registers.push((cpu.a, cpu.x, cpu.y, cpu.sp));
+    }
+    registers.dedup();
+    assert!(registers.len() > 1);
+}
+
+#[test]
+fn test_pushed_break_flag() {
+    let mut cpu = CPU::new();
+    let mut bus = FakeBus::new();
+    bus.mem_write(0xFFFE, 0x00);
+    bus.mem_write(0xFFFF, 0x30);
+    bus.mem_write(0x2000, 0x00); // BRK
+
+    // BRK pushes B set
+    cpu.pc = 0x2000;
+    cpu.sp = 0xFF;
+    cpu.status = Flags::UNUSED;
+    cpu.exec(&mut bus).unwrap();
+    assert_eq!(bus.mem_read(0x01FD), 0x30);
+    assert!(!cpu.status.contains(Flags::BREAK));
+
+    // IRQ pushes B clear
+    cpu.pc = 0x2000;
+    cpu.sp = 0xFF;
+    cpu.status = Flags::UNUSED;
+    cpu.set_irq(true);
+    cpu.exec(&mut bus).unwrap();
+    assert_eq!(bus.mem_read(0x01FD), 0x20);
+    assert!(!cpu.status.contains(Flags::BREAK));
+}
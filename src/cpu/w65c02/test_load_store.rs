@@ -9,7 +9,7 @@ fn test_lda() {
     cpu.pc = 0x2000;
     bus.mem_write(0x2000, 0xA9); // LDA immediate
     bus.mem_write(0x2001, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.a, 0x42);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -22,7 +22,7 @@ fn test_lda() {
     bus.mem_write(0x2000, 0xA5); // LDA zero page
     bus.mem_write(0x2001, 0x42);
     bus.mem_write(0x42, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.a, 0x42);
     assert_eq!(inst.opcode, Opcode::LDA);
@@ -34,7 +34,7 @@ fn test_lda() {
     bus.mem_write(0x2000, 0xB5); // LDA zero page,X
     bus.mem_write(0x2001, 0x40);
     bus.mem_write(0x42, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.a, 0x42);
     assert_eq!(inst.opcode, Opcode::LDA);
@@ -46,7 +46,7 @@ fn test_lda() {
     bus.mem_write(0x2001, 0x42);
     bus.mem_write(0x2002, 0x20);
     bus.mem_write(0x2042, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(cpu.a, 0x42);
     assert_eq!(inst.opcode, Opcode::LDA);
@@ -59,7 +59,7 @@ fn test_lda() {
     bus.mem_write(0x2001, 0x40);
     bus.mem_write(0x2002, 0x20);
     bus.mem_write(0x2042, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(cpu.a, 0x42);
     assert_eq!(inst.opcode, Opcode::LDA);
@@ -72,7 +72,7 @@ fn test_lda() {
     bus.mem_write(0x2001, 0x40);
     bus.mem_write(0x2002, 0x20);
     bus.mem_write(0x2042, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(cpu.a, 0x42);
     assert_eq!(inst.opcode, Opcode::LDA);
@@ -86,7 +86,7 @@ fn test_lda() {
     bus.mem_write(0x42, 0x42);
     bus.mem_write(0x43, 0x20);
     bus.mem_write(0x2042, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.a, 0x42);
     assert_eq!(inst.opcode, Opcode::LDA);
@@ -100,11 +100,43 @@ fn test_lda() {
     bus.mem_write(0x40, 0x40);
     bus.mem_write(0x41, 0x20);
     bus.mem_write(0x2042, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.a, 0x42);
     assert_eq!(inst.opcode, Opcode::LDA);
     assert_eq!(inst.cycles, 5);
+
+    // (Zero page)
+    cpu.pc = 0x2000;
+    bus.mem_write(0x2000, 0xB2); // LDA (zero page)
+    bus.mem_write(0x2001, 0xFF);
+    bus.mem_write(0xFF, 0x42);
+    bus.mem_write(0x00, 0x20);
+    bus.mem_write(0x2042, 0x42);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x2002);
+    assert_eq!(cpu.a, 0x42);
+    assert_eq!(inst.opcode, Opcode::LDA);
+    assert_eq!(inst.cycles, 5);
+
+    // (Indirect,X) and (Indirect),Y take the pointer high byte from $00, not $0100
+    bus.mem_write(0x0100, 0x30);
+    bus.mem_write(0x3042, 0x99);
+    bus.mem_write(0x2043, 0x43);
+
+    cpu.pc = 0x2000;
+    cpu.x = 0x01;
+    bus.mem_write(0x2000, 0xA1); // LDA ($FE,X)
+    bus.mem_write(0x2001, 0xFE);
+    cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.a, 0x42);
+
+    cpu.pc = 0x2000;
+    cpu.y = 0x01;
+    bus.mem_write(0x2000, 0xB1); // LDA ($FF),Y
+    bus.mem_write(0x2001, 0xFF);
+    cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.a, 0x43);
 }
 
 #[test]
@@ -116,7 +148,7 @@ fn test_ldx() {
     cpu.pc = 0x2000;
     bus.mem_write(0x2000, 0xA2); // LDX immediate
     bus.mem_write(0x2001, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.x, 0x42);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -129,7 +161,7 @@ fn test_ldx() {
     bus.mem_write(0x2000, 0xA6); // LDX zero page
     bus.mem_write(0x2001, 0x42);
     bus.mem_write(0x42, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.x, 0x42);
     assert_eq!(inst.opcode, Opcode::LDX);
@@ -141,7 +173,7 @@ fn test_ldx() {
     bus.mem_write(0x2000, 0xB6); // LDX zero page,Y
     bus.mem_write(0x2001, 0x40);
     bus.mem_write(0x42, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.x, 0x42);
     assert_eq!(inst.opcode, Opcode::LDX);
@@ -153,7 +185,7 @@ fn test_ldx() {
     bus.mem_write(0x2001, 0x42);
     bus.mem_write(0x2002, 0x20);
     bus.mem_write(0x2042, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(cpu.x, 0x42);
     assert_eq!(inst.opcode, Opcode::LDX);
@@ -166,7 +198,7 @@ fn test_ldx() {
     bus.mem_write(0x2001, 0x40);
     bus.mem_write(0x2002, 0x20);
     bus.mem_write(0x2042, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(cpu.x, 0x42);
     assert_eq!(inst.opcode, Opcode::LDX);
@@ -182,7 +214,7 @@ fn test_ldy() {
     cpu.pc = 0x2000;
     bus.mem_write(0x2000, 0xA0); // LDY immediate
     bus.mem_write(0x2001, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.y, 0x42);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -195,7 +227,7 @@ fn test_ldy() {
     bus.mem_write(0x2000, 0xA4); // LDY zero page
     bus.mem_write(0x2001, 0x42);
     bus.mem_write(0x42, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.y, 0x42);
     assert_eq!(inst.opcode, Opcode::LDY);
@@ -207,7 +239,7 @@ fn test_ldy() {
     bus.mem_write(0x2000, 0xB4); // LDY zero page,X
     bus.mem_write(0x2001, 0x40);
     bus.mem_write(0x42, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(cpu.y, 0x42);
     assert_eq!(inst.opcode, Opcode::LDY);
@@ -219,7 +251,7 @@ fn test_ldy() {
     bus.mem_write(0x2001, 0x42);
     bus.mem_write(0x2002, 0x20);
     bus.mem_write(0x2042, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(cpu.y, 0x42);
     assert_eq!(inst.opcode, Opcode::LDY);
@@ -232,7 +264,7 @@ fn test_ldy() {
     bus.mem_write(0x2001, 0x40);
     bus.mem_write(0x2002, 0x20);
     bus.mem_write(0x2042, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(cpu.y, 0x42);
     assert_eq!(inst.opcode, Opcode::LDY);
@@ -249,7 +281,7 @@ fn test_sta() {
     cpu.a = 0x42;
     bus.mem_write(0x2000, 0x85); // STA zero page
     bus.mem_write(0x2001, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(bus.mem_read(0x42), 0x42);
     assert_eq!(inst.opcode, Opcode::STA);
@@ -261,7 +293,7 @@ fn test_sta() {
     cpu.x = 0x02;
     bus.mem_write(0x2000, 0x95); // STA zero page,X
     bus.mem_write(0x2001, 0x40);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(bus.mem_read(0x42), 0x42);
     assert_eq!(inst.opcode, Opcode::STA);
@@ -273,7 +305,7 @@ fn test_sta() {
     bus.mem_write(0x2000, 0x8D); // STA absolute
     bus.mem_write(0x2001, 0x42);
     bus.mem_write(0x2002, 0x20);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(bus.mem_read(0x2042), 0x42);
     assert_eq!(inst.opcode, Opcode::STA);
@@ -286,7 +318,7 @@ fn test_sta() {
     bus.mem_write(0x2000, 0x9D); // STA absolute,X
     bus.mem_write(0x2001, 0x40);
     bus.mem_write(0x2002, 0x20);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(bus.mem_read(0x2042), 0x42);
     assert_eq!(inst.opcode, Opcode::STA);
@@ -299,7 +331,7 @@ fn test_sta() {
     bus.mem_write(0x2000, 0x99); // STA absolute,Y
     bus.mem_write(0x2001, 0x40);
     bus.mem_write(0x2002, 0x20);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(bus.mem_read(0x2042), 0x42);
     assert_eq!(inst.opcode, Opcode::STA);
@@ -313,7 +345,7 @@ fn test_sta() {
     bus.mem_write(0x2001, 0x40);
     bus.mem_write(0x42, 0x42);
     bus.mem_write(0x43, 0x20);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(bus.mem_read(0x2042), 0x42);
     assert_eq!(inst.opcode, Opcode::STA);
@@ -327,11 +359,25 @@ fn test_sta() {
     bus.mem_write(0x2001, 0x40);
     bus.mem_write(0x40, 0x40);
     bus.mem_write(0x41, 0x20);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(bus.mem_read(0x2042), 0x42);
     assert_eq!(inst.opcode, Opcode::STA);
     assert_eq!(inst.cycles, 6);
+
+    // (Zero page)
+    cpu.pc = 0x2000;
+    cpu.a = 0x42;
+    bus.mem_write(0x2000, 0x92); // STA (zero page)
+    bus.mem_write(0x2001, 0x40);
+    bus.mem_write(0x40, 0x42);
+    bus.mem_write(0x41, 0x20);
+    bus.mem_write(0x2042, 0x00);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x2002);
+    assert_eq!(bus.mem_read(0x2042), 0x42);
+    assert_eq!(inst.opcode, Opcode::STA);
+    assert_eq!(inst.cycles, 5);
 }
 
 #[test]
@@ -344,7 +390,7 @@ fn test_stx() {
     cpu.x = 0x42;
     bus.mem_write(0x2000, 0x86); // STX zero page
     bus.mem_write(0x2001, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(bus.mem_read(0x42), 0x42);
     assert_eq!(inst.opcode, Opcode::STX);
@@ -356,7 +402,7 @@ fn test_stx() {
     cpu.y = 0x02;
     bus.mem_write(0x2000, 0x96); // STX zero page,Y
     bus.mem_write(0x2001, 0x40);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(bus.mem_read(0x42), 0x42);
     assert_eq!(inst.opcode, Opcode::STX);
@@ -368,7 +414,7 @@ fn test_stx() {
     bus.mem_write(0x2000, 0x8E); // STX absolute
     bus.mem_write(0x2001, 0x42);
     bus.mem_write(0x2002, 0x20);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(bus.mem_read(0x2042), 0x42);
     assert_eq!(inst.opcode, Opcode::STX);
@@ -385,7 +431,7 @@ fn test_sty() {
     cpu.y = 0x42;
     bus.mem_write(0x2000, 0x84); // STY zero page
     bus.mem_write(0x2001, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(bus.mem_read(0x42), 0x42);
     assert_eq!(inst.opcode, Opcode::STY);
@@ -397,7 +443,7 @@ fn test_sty() {
     cpu.x = 0x02;
     bus.mem_write(0x2000, 0x94); // STY zero page,X
     bus.mem_write(0x2001, 0x40);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(bus.mem_read(0x42), 0x42);
     assert_eq!(inst.opcode, Opcode::STY);
@@ -409,13 +455,67 @@ fn test_sty() {
     bus.mem_write(0x2000, 0x8C); // STY absolute
     bus.mem_write(0x2001, 0x42);
     bus.mem_write(0x2002, 0x20);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(bus.mem_read(0x2042), 0x42);
     assert_eq!(inst.opcode, Opcode::STY);
     assert_eq!(inst.cycles, 4);
 }
 
+#[test]
+fn test_stz() {
+    let mut cpu = CPU::new();
+    let mut bus = FakeBus::new();
+
+    // Zero page
+    cpu.pc = 0x2000;
+    bus.mem_write(0x2000, 0x64); // STZ zero page
+    bus.mem_write(0x2001, 0x42);
+    bus.mem_write(0x42, 0xFF);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x2002);
+    assert_eq!(bus.mem_read(0x42), 0x00);
+    assert_eq!(inst.opcode, Opcode::STZ);
+    assert_eq!(inst.cycles, 3);
+
+    // Zero page,X
+    cpu.pc = 0x2000;
+    cpu.x = 0x02;
+    bus.mem_write(0x2000, 0x74); // STZ zero page,X
+    bus.mem_write(0x2001, 0x40);
+    bus.mem_write(0x42, 0xFF);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x2002);
+    assert_eq!(bus.mem_read(0x42), 0x00);
+    assert_eq!(inst.opcode, Opcode::STZ);
+    assert_eq!(inst.cycles, 4);
+
+    // Absolute
+    cpu.pc = 0x2000;
+    bus.mem_write(0x2000, 0x9C); // STZ absolute
+    bus.mem_write(0x2001, 0x42);
+    bus.mem_write(0x2002, 0x20);
+    bus.mem_write(0x2042, 0xFF);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x2003);
+    assert_eq!(bus.mem_read(0x2042), 0x00);
+    assert_eq!(inst.opcode, Opcode::STZ);
+    assert_eq!(inst.cycles, 4);
+
+    // Absolute,X
+    cpu.pc = 0x2000;
+    cpu.x = 0x02;
+    bus.mem_write(0x2000, 0x9E); // STZ absolute,X
+    bus.mem_write(0x2001, 0x40);
+    bus.mem_write(0x2002, 0x20);
+    bus.mem_write(0x2042, 0xFF);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x2003);
+    assert_eq!(bus.mem_read(0x2042), 0x00);
+    assert_eq!(inst.opcode, Opcode::STZ);
+    assert_eq!(inst.cycles, 5);
+}
+
 #[test]
 fn test_tax() {
     let mut cpu = CPU::new();
@@ -425,7 +525,7 @@ fn test_tax() {
     cpu.pc = 0x2000;
     cpu.a = 0x42;
     bus.mem_write(0x2000, 0xAA); // TAX
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.x, 0x42);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -437,7 +537,7 @@ fn test_tax() {
     cpu.pc = 0x2000;
     cpu.a = 0x00;
     bus.mem_write(0x2000, 0xAA); // TAX
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.x, 0x00);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -449,7 +549,7 @@ fn test_tax() {
     cpu.pc = 0x2000;
     cpu.a = 0x80;
     bus.mem_write(0x2000, 0xAA); // TAX
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.x, 0x80);
     assert!(cpu.status.contains(Flags::NEGATIVE));
@@ -467,7 +567,7 @@ fn test_tay() {
     cpu.pc = 0x2000;
     cpu.a = 0x42;
     bus.mem_write(0x2000, 0xA8); // TAY
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.y, 0x42);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -479,7 +579,7 @@ fn test_tay() {
     cpu.pc = 0x2000;
     cpu.a = 0x00;
     bus.mem_write(0x2000, 0xA8); // TAY
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.y, 0x00);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -491,7 +591,7 @@ fn test_tay() {
     cpu.pc = 0x2000;
     cpu.a = 0x80;
     bus.mem_write(0x2000, 0xA8); // TAY
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.y, 0x80);
     assert!(cpu.status.contains(Flags::NEGATIVE));
@@ -509,7 +609,7 @@ fn test_tsx() {
     cpu.pc = 0x2000;
     cpu.sp = 0x42;
     bus.mem_write(0x2000, 0xBA); // TSX
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.x, 0x42);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -521,7 +621,7 @@ fn test_tsx() {
     cpu.pc = 0x2000;
     cpu.sp = 0x00;
     bus.mem_write(0x2000, 0xBA); // TSX
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.x, 0x00);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -533,7 +633,7 @@ fn test_tsx() {
     cpu.pc = 0x2000;
     cpu.sp = 0x80;
     bus.mem_write(0x2000, 0xBA); // TSX
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.x, 0x80);
     assert!(cpu.status.contains(Flags::NEGATIVE));
@@ -551,7 +651,7 @@ fn test_txa() {
     cpu.pc = 0x2000;
     cpu.x = 0x42;
     bus.mem_write(0x2000, 0x8A); // TXA
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.a, 0x42);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -563,7 +663,7 @@ fn test_txa() {
     cpu.pc = 0x2000;
     cpu.x = 0x00;
     bus.mem_write(0x2000, 0x8A); // TXA
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.a, 0x00);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -575,7 +675,7 @@ fn test_txa() {
     cpu.pc = 0x2000;
     cpu.x = 0x80;
     bus.mem_write(0x2000, 0x8A); // TXA
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.a, 0x80);
     assert!(cpu.status.contains(Flags::NEGATIVE));
@@ -593,7 +693,7 @@ fn test_txs() {
     cpu.pc = 0x2000;
     cpu.x = 0x42;
     bus.mem_write(0x2000, 0x9A); // TXS
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.sp, 0x42);
     assert!(matches!(inst.opcode, Opcode::TXS));
@@ -603,7 +703,7 @@ fn test_txs() {
     cpu.pc = 0x2000;
     cpu.x = 0x00;
     bus.mem_write(0x2000, 0x9A); // TXS
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.sp, 0x00);
     assert!(matches!(inst.opcode, Opcode::TXS));
@@ -613,7 +713,7 @@ fn test_txs() {
     cpu.pc = 0x2000;
     cpu.x = 0x80;
     bus.mem_write(0x2000, 0x9A); // TXS
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.sp, 0x80);
     assert!(matches!(inst.opcode, Opcode::TXS));
@@ -629,7 +729,7 @@ fn test_tya() {
     cpu.pc = 0x2000;
     cpu.y = 0x42;
     bus.mem_write(0x2000, 0x98); // TYA
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.a, 0x42);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -641,7 +741,7 @@ fn test_tya() {
     cpu.pc = 0x2000;
     cpu.y = 0x00;
     bus.mem_write(0x2000, 0x98); // TYA
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.a, 0x00);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -653,7 +753,7 @@ fn test_tya() {
     cpu.pc = 0x2000;
     cpu.y = 0x80;
     bus.mem_write(0x2000, 0x98); // TYA
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.a, 0x80);
     assert!(cpu.status.contains(Flags::NEGATIVE));
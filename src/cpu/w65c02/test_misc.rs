@@ -7,7 +7,7 @@ fn test_nop() {
 
     cpu.pc = 0x2000;
     bus.mem_write(0x2000, 0xEA); // NOP
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(inst.opcode, Opcode::NOP);
     assert_eq!(inst.cycles, 2);
@@ -0,0 +1,75 @@
+use crate::cpu::w65c02::{Bus, CPU, Flags, StopReason, Variant, bus::FakeBus};
+
+fn setup(program: &[u8]) -> (CPU, FakeBus) {
+    let mut cpu = CPU::new();
+    let mut bus = FakeBus::new();
+    for (i, byte) in program.iter().enumerate() {
+        bus.mem_write(0x2000 + i as u16, *byte);
+    }
+    cpu.pc = 0x2000;
+    cpu.sp = 0xFF;
+    (cpu, bus)
+}
+
+#[test]
+fn test_cycle_counter() {
+    let (mut cpu, mut bus) = setup(&[0xA9, 0x42, 0x8D, 0x00, 0x03]); // LDA #$42, STA $0300
+    assert_eq!(cpu.cycles(), 0);
+    cpu.exec(&mut bus).unwrap();
+    cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.cycles(), 6);
+    cpu.reset_cycles();
+    assert_eq!(cpu.cycles(), 0);
+    cpu.reset(&mut bus);
+    assert_eq!(cpu.cycles(), 7);
+}
+
+#[test]
+fn test_run_for() {
+    let (mut cpu, mut bus) = setup(&[0x4C, 0x00, 0x20]); // JMP $2000
+    assert_eq!(cpu.run_for(&mut bus, 10), Ok(StopReason::Budget));
+    // The last instruction may overrun the budget
+    assert_eq!(cpu.cycles(), 12);
+    assert_eq!(cpu.run_for(&mut bus, 3), Ok(StopReason::Budget));
+    assert_eq!(cpu.cycles(), 15);
+}
+
+#[test]
+fn test_run_until() {
+    // INX, JMP $2000
+    let (mut cpu, mut bus) = setup(&[0xE8, 0x4C, 0x00, 0x20]);
+    assert_eq!(cpu.run_until(&mut bus, 1000, |cpu| cpu.x == 3), Ok(StopReason::Break));
+    assert_eq!(cpu.x, 3);
+    assert_eq!(cpu.pc, 0x2001);
+    assert_eq!(cpu.cycles(), 2 + 3 + 2 + 3 + 2);
+}
+
+#[test]
+fn test_run_until_stopped() {
+    let (mut cpu, mut bus) = setup(&[0xEA, 0xDB]); // NOP, STP
+    assert_eq!(cpu.run_for(&mut bus, 1000), Ok(StopReason::Stopped));
+    assert_eq!(cpu.cycles(), 5);
+    assert_eq!(cpu.run_for(&mut bus, 1000), Ok(StopReason::Stopped));
+}
+
+#[test]
+fn test_run_skips_waiting() {
+    let (mut cpu, mut bus) = setup(&[0xCB, 0xEA]); // WAI, NOP
+    assert_eq!(cpu.run_for(&mut bus, 1000), Ok(StopReason::Budget));
+    assert_eq!(cpu.cycles(), 1000);
+    assert_eq!(cpu.pc, 0x2001);
+
+    // A masked IRQ wakes the CPU up and execution goes on after WAI
+    cpu.status.insert(Flags::INTERRUPT);
+    cpu.set_irq(true);
+    assert_eq!(cpu.run_until(&mut bus, 1000, |cpu| cpu.pc == 0x2002), Ok(StopReason::Break));
+}
+
+#[test]
+fn test_run_until_jam() {
+    let mut cpu = CPU::with_variant(Variant::NMOS6502);
+    let mut bus = FakeBus::new();
+    bus.mem_write(0x2000, 0x02); // JAM
+    cpu.pc = 0x2000;
+    assert_eq!(cpu.run_for(&mut bus, 100), Ok(StopReason::Stopped));
+}
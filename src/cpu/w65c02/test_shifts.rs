@@ -9,7 +9,7 @@ fn test_asl() {
     cpu.pc = 0x2000;
     cpu.a = 0x21;
     bus.mem_write(0x2000, 0x0A); // ASL A
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.a, 0x42);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -23,7 +23,7 @@ fn test_asl() {
     bus.mem_write(0x2000, 0x06); // ASL zero page
     bus.mem_write(0x2001, 0x42);
     bus.mem_write(0x42, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(bus.mem_read(0x42), 0x42);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -38,7 +38,7 @@ fn test_asl() {
     bus.mem_write(0x2000, 0x16); // ASL zero page,X
     bus.mem_write(0x2001, 0x40);
     bus.mem_write(0x42, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(bus.mem_read(0x42), 0x42);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -53,7 +53,7 @@ fn test_asl() {
     bus.mem_write(0x2001, 0x42);
     bus.mem_write(0x2002, 0x20);
     bus.mem_write(0x2042, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(bus.mem_read(0x2042), 0x42);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -69,20 +69,20 @@ fn test_asl() {
     bus.mem_write(0x2001, 0x40);
     bus.mem_write(0x2002, 0x20);
     bus.mem_write(0x2042, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(bus.mem_read(0x2042), 0x42);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
     assert!(!cpu.status.contains(Flags::ZERO));
     assert!(!cpu.status.contains(Flags::CARRY));
     assert!(matches!(inst.opcode, Opcode::ASL));
-    assert_eq!(inst.cycles, 7);
+    assert_eq!(inst.cycles, 6);
 
     // Carry
     cpu.pc = 0x2000;
     cpu.a = 0x80;
     bus.mem_write(0x2000, 0x0A); // ASL A
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.a, 0x00);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -95,7 +95,7 @@ fn test_asl() {
     cpu.pc = 0x2000;
     cpu.a = 0x40;
     bus.mem_write(0x2000, 0x0A); // ASL A
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.a, 0x80);
     assert!(cpu.status.contains(Flags::NEGATIVE));
@@ -114,7 +114,7 @@ fn test_lsr() {
     cpu.pc = 0x2000;
     cpu.a = 0x42;
     bus.mem_write(0x2000, 0x4A); // LSR A
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.a, 0x21);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -128,7 +128,7 @@ fn test_lsr() {
     bus.mem_write(0x2000, 0x46); // LSR zero page
     bus.mem_write(0x2001, 0x42);
     bus.mem_write(0x42, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(bus.mem_read(0x42), 0x21);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -143,7 +143,7 @@ fn test_lsr() {
     bus.mem_write(0x2000, 0x56); // LSR zero page,X
     bus.mem_write(0x2001, 0x40);
     bus.mem_write(0x42, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(bus.mem_read(0x42), 0x21);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -158,7 +158,7 @@ fn test_lsr() {
     bus.mem_write(0x2001, 0x42);
     bus.mem_write(0x2002, 0x20);
     bus.mem_write(0x2042, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(bus.mem_read(0x2042), 0x21);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -174,20 +174,20 @@ fn test_lsr() {
     bus.mem_write(0x2001, 0x40);
     bus.mem_write(0x2002, 0x20);
     bus.mem_write(0x2042, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(bus.mem_read(0x2042), 0x21);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
     assert!(!cpu.status.contains(Flags::ZERO));
     assert!(!cpu.status.contains(Flags::CARRY));
     assert!(matches!(inst.opcode, Opcode::LSR));
-    assert_eq!(inst.cycles, 7);
+    assert_eq!(inst.cycles, 6);
 
     // Carry
     cpu.pc = 0x2000;
     cpu.a = 0x01;
     bus.mem_write(0x2000, 0x4A); // LSR A
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.a, 0x00);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -200,7 +200,7 @@ fn test_lsr() {
     cpu.pc = 0x2000;
     cpu.a = 0x00;
     bus.mem_write(0x2000, 0x4A); // LSR A
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.a, 0x00);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -219,7 +219,7 @@ fn test_rol() {
     cpu.pc = 0x2000;
     cpu.a = 0x21;
     bus.mem_write(0x2000, 0x2A); // ROL A
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.a, 0x42);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -233,7 +233,7 @@ fn test_rol() {
     bus.mem_write(0x2000, 0x26); // ROL zero page
     bus.mem_write(0x2001, 0x42);
     bus.mem_write(0x42, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(bus.mem_read(0x42), 0x42);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -248,7 +248,7 @@ fn test_rol() {
     bus.mem_write(0x2000, 0x36); // ROL zero page,X
     bus.mem_write(0x2001, 0x40);
     bus.mem_write(0x42, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(bus.mem_read(0x42), 0x42);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -263,7 +263,7 @@ fn test_rol() {
     bus.mem_write(0x2001, 0x42);
     bus.mem_write(0x2002, 0x20);
     bus.mem_write(0x2042, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(bus.mem_read(0x2042), 0x42);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -279,20 +279,20 @@ fn test_rol() {
     bus.mem_write(0x2001, 0x40);
     bus.mem_write(0x2002, 0x20);
     bus.mem_write(0x2042, 0x21);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(bus.mem_read(0x2042), 0x42);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
     assert!(!cpu.status.contains(Flags::ZERO));
     assert!(!cpu.status.contains(Flags::CARRY));
     assert!(matches!(inst.opcode, Opcode::ROL));
-    assert_eq!(inst.cycles, 7);
+    assert_eq!(inst.cycles, 6);
 
     // Carry
     cpu.pc = 0x2000;
     cpu.a = 0x80;
     bus.mem_write(0x2000, 0x2A); // ROL A
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.a, 0x00);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -306,7 +306,7 @@ fn test_rol() {
     cpu.a = 0x40;
     cpu.status.set(Flags::CARRY, false);
     bus.mem_write(0x2000, 0x2A); // ROL A
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.a, 0x80);
     assert!(cpu.status.contains(Flags::NEGATIVE));
@@ -325,7 +325,7 @@ fn test_ror() {
     cpu.pc = 0x2000;
     cpu.a = 0x42;
     bus.mem_write(0x2000, 0x6A); // ROR A
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.a, 0x21);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -339,7 +339,7 @@ fn test_ror() {
     bus.mem_write(0x2000, 0x66); // ROR zero page
     bus.mem_write(0x2001, 0x42);
     bus.mem_write(0x42, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(bus.mem_read(0x42), 0x21);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -354,7 +354,7 @@ fn test_ror() {
     bus.mem_write(0x2000, 0x76); // ROR zero page,X
     bus.mem_write(0x2001, 0x40);
     bus.mem_write(0x42, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2002);
     assert_eq!(bus.mem_read(0x42), 0x21);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -369,7 +369,7 @@ fn test_ror() {
     bus.mem_write(0x2001, 0x42);
     bus.mem_write(0x2002, 0x20);
     bus.mem_write(0x2042, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(bus.mem_read(0x2042), 0x21);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -385,20 +385,20 @@ fn test_ror() {
     bus.mem_write(0x2001, 0x40);
     bus.mem_write(0x2002, 0x20);
     bus.mem_write(0x2042, 0x42);
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2003);
     assert_eq!(bus.mem_read(0x2042), 0x21);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
     assert!(!cpu.status.contains(Flags::ZERO));
     assert!(!cpu.status.contains(Flags::CARRY));
     assert!(matches!(inst.opcode, Opcode::ROR));
-    assert_eq!(inst.cycles, 7);
+    assert_eq!(inst.cycles, 6);
 
     // Carry
     cpu.pc = 0x2000;
     cpu.a = 0x01;
     bus.mem_write(0x2000, 0x6A); // ROR A
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.a, 0x00);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -412,7 +412,7 @@ fn test_ror() {
     cpu.a = 0x00;    
     cpu.status.set(Flags::CARRY, false);
     bus.mem_write(0x2000, 0x6A); // ROR A
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.a, 0x00);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
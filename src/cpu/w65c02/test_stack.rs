@@ -9,7 +9,7 @@ fn test_pha() {
     cpu.a = 0x42;
     cpu.sp = 0xFF;
     bus.mem_write(0x2000, 0x48); // PHA
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.sp, 0xFE);
     assert_eq!(bus.mem_read(0x01FF), 0x42);
@@ -26,7 +26,7 @@ fn test_php() {
     cpu.status = Flags::CARRY | Flags::ZERO;
     cpu.sp = 0xFF;
     bus.mem_write(0x2000, 0x08); // PHP
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.sp, 0xFE);
     let status = Flags::from_bits(bus.mem_read(0x01FF)).unwrap();
@@ -44,7 +44,7 @@ fn test_pla() {
     cpu.sp = 0xFE;
     bus.mem_write(0x01FF, 0x42);
     bus.mem_write(0x2000, 0x68); // PLA
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.sp, 0xFF);
     assert_eq!(cpu.a, 0x42);
@@ -63,7 +63,7 @@ fn test_plp() {
     cpu.sp = 0xFE;
     bus.mem_write(0x01FF, 0x03);
     bus.mem_write(0x2000, 0x28); // PLP
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.sp, 0xFF);
     assert!(cpu.status.contains(Flags::CARRY));
@@ -72,6 +72,92 @@ fn test_plp() {
     assert_eq!(inst.cycles, 4);
 }
 
+#[test]
+fn test_plp_break_and_unused() {
+    let mut cpu = CPU::new();
+    let mut bus = FakeBus::new();
+
+    // B is not a register bit, and bit 5 always reads as 1
+    cpu.pc = 0x2000;
+    cpu.sp = 0xFE;
+    bus.mem_write(0x01FF, 0x10);
+    bus.mem_write(0x2000, 0x28); // PLP
+    cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.status, Flags::UNUSED);
+}
+
+#[test]
+fn test_phx() {
+    let mut cpu = CPU::new();
+    let mut bus = FakeBus::new();
+
+    cpu.pc = 0x2000;
+    cpu.x = 0x42;
+    cpu.sp = 0xFF;
+    bus.mem_write(0x2000, 0xDA); // PHX
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x2001);
+    assert_eq!(cpu.sp, 0xFE);
+    assert_eq!(bus.mem_read(0x01FF), 0x42);
+    assert!(matches!(inst.opcode, Opcode::PHX));
+    assert_eq!(inst.cycles, 3);
+}
+
+#[test]
+fn test_phy() {
+    let mut cpu = CPU::new();
+    let mut bus = FakeBus::new();
+
+    cpu.pc = 0x2000;
+    cpu.y = 0x42;
+    cpu.sp = 0xFF;
+    bus.mem_write(0x2000, 0x5A); // PHY
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x2001);
+    assert_eq!(cpu.sp, 0xFE);
+    assert_eq!(bus.mem_read(0x01FF), 0x42);
+    assert!(matches!(inst.opcode, Opcode::PHY));
+    assert_eq!(inst.cycles, 3);
+}
+
+#[test]
+fn test_plx() {
+    let mut cpu = CPU::new();
+    let mut bus = FakeBus::new();
+
+    cpu.pc = 0x2000;
+    cpu.sp = 0xFE;
+    bus.mem_write(0x01FF, 0x80);
+    bus.mem_write(0x2000, 0xFA); // PLX
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x2001);
+    assert_eq!(cpu.sp, 0xFF);
+    assert_eq!(cpu.x, 0x80);
+    assert!(cpu.status.contains(Flags::NEGATIVE));
+    assert!(!cpu.status.contains(Flags::ZERO));
+    assert!(matches!(inst.opcode, Opcode::PLX));
+    assert_eq!(inst.cycles, 4);
+}
+
+#[test]
+fn test_ply() {
+    let mut cpu = CPU::new();
+    let mut bus = FakeBus::new();
+
+    cpu.pc = 0x2000;
+    cpu.sp = 0xFE;
+    bus.mem_write(0x01FF, 0x00);
+    bus.mem_write(0x2000, 0x7A); // PLY
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x2001);
+    assert_eq!(cpu.sp, 0xFF);
+    assert_eq!(cpu.y, 0x00);
+    assert!(!cpu.status.contains(Flags::NEGATIVE));
+    assert!(cpu.status.contains(Flags::ZERO));
+    assert!(matches!(inst.opcode, Opcode::PLY));
+    assert_eq!(inst.cycles, 4);
+}
+
 #[test]
 fn test_tsx() {
     let mut cpu = CPU::new();
@@ -80,7 +166,7 @@ fn test_tsx() {
     cpu.pc = 0x2000;
     cpu.sp = 0x42;
     bus.mem_write(0x2000, 0xBA); // TSX
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.x, 0x42);
     assert!(!cpu.status.contains(Flags::NEGATIVE));
@@ -97,7 +183,7 @@ fn test_txs() {
     cpu.pc = 0x2000;
     cpu.x = 0x42;
     bus.mem_write(0x2000, 0x9A); // TXS
-    let inst = cpu.exec(&mut bus);
+    let inst = cpu.exec(&mut bus).unwrap();
     assert_eq!(cpu.pc, 0x2001);
     assert_eq!(cpu.sp, 0x42);
     assert!(matches!(inst.opcode, Opcode::TXS));
@@ -0,0 +1,204 @@
+use crate::cpu::w65c02::{Bus, CPU, Variant, bus::FakeBus, cpu::Flags, inst::Opcode};
+
+#[test]
+fn test_absolute_x_page_cross() {
+    let cases = [
+        (0x7D, Opcode::ADC),
+        (0x3D, Opcode::AND),
+        (0x3C, Opcode::BIT),
+        (0xDD, Opcode::CMP),
+        (0x5D, Opcode::EOR),
+        (0xBD, Opcode::LDA),
+        (0xBC, Opcode::LDY),
+        (0x1D, Opcode::ORA),
+        (0xFD, Opcode::SBC),
+    ];
+    for (code, opcode) in cases {
+        let mut cpu = CPU::new();
+        let mut bus = FakeBus::new();
+        bus.mem_write(0x2000, code);
+        bus.mem_write(0x2001, 0x80);
+        bus.mem_write(0x2002, 0x40);
+
+        // Same page
+        cpu.pc = 0x2000;
+        cpu.x = 0x7F;
+        let inst = cpu.exec(&mut bus).unwrap();
+        assert_eq!(inst.opcode, opcode);
+        assert_eq!(inst.cycles, 4, "{:?} $4080,X without page cross", opcode);
+
+        // Page cross
+        cpu.pc = 0x2000;
+        cpu.x = 0x80;
+        let inst = cpu.exec(&mut bus).unwrap();
+        assert_eq!(inst.cycles, 5, "{:?} $4080,X with page cross", opcode);
+    }
+}
+
+#[test]
+fn test_absolute_y_page_cross() {
+    let cases = [
+        (0x79, Opcode::ADC),
+        (0x39, Opcode::AND),
+        (0xD9, Opcode::CMP),
+        (0x59, Opcode::EOR),
+        (0xB9, Opcode::LDA),
+        (0xBE, Opcode::LDX),
+        (0x19, Opcode::ORA),
+        (0xF9, Opcode::SBC),
+    ];
+    for (code, opcode) in cases {
+        let mut cpu = CPU::new();
+        let mut bus = FakeBus::new();
+        bus.mem_write(0x2000, code);
+        bus.mem_write(0x2001, 0x80);
+        bus.mem_write(0x2002, 0x40);
+
+        // Same page
+        cpu.pc = 0x2000;
+        cpu.y = 0x7F;
+        let inst = cpu.exec(&mut bus).unwrap();
+        assert_eq!(inst.opcode, opcode);
+        assert_eq!(inst.cycles, 4, "{:?} $4080,Y without page cross", opcode);
+
+        // Page cross
+        cpu.pc = 0x2000;
+        cpu.y = 0x80;
+        let inst = cpu.exec(&mut bus).unwrap();
+        assert_eq!(inst.cycles, 5, "{:?} $4080,Y with page cross", opcode);
+    }
+}
+
+#[test]
+fn test_indirect_y_page_cross() {
+    let cases = [
+        (0x71, Opcode::ADC),
+        (0x31, Opcode::AND),
+        (0xD1, Opcode::CMP),
+        (0x51, Opcode::EOR),
+        (0xB1, Opcode::LDA),
+        (0x11, Opcode::ORA),
+        (0xF1, Opcode::SBC),
+    ];
+    for (code, opcode) in cases {
+        let mut cpu = CPU::new();
+        let mut bus = FakeBus::new();
+        bus.mem_write(0x2000, code);
+        bus.mem_write(0x2001, 0x10);
+        bus.mem_write(0x0010, 0x80);
+        bus.mem_write(0x0011, 0x40);
+
+        // Same page
+        cpu.pc = 0x2000;
+        cpu.y = 0x7F;
+        let inst = cpu.exec(&mut bus).unwrap();
+        assert_eq!(inst.opcode, opcode);
+        assert_eq!(inst.cycles, 5, "{:?} ($10),Y without page cross", opcode);
+
+        // Page cross
+        cpu.pc = 0x2000;
+        cpu.y = 0x80;
+        let inst = cpu.exec(&mut bus).unwrap();
+        assert_eq!(inst.cycles, 6, "{:?} ($10),Y with page cross", opcode);
+    }
+}
+
+#[test]
+fn test_store_no_page_cross() {
+    let mut cpu = CPU::new();
+    let mut bus = FakeBus::new();
+
+    // STA $4080,X
+    cpu.pc = 0x2000;
+    cpu.x = 0x80;
+    bus.mem_write(0x2000, 0x9D);
+    bus.mem_write(0x2001, 0x80);
+    bus.mem_write(0x2002, 0x40);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.cycles, 5);
+
+    // STA ($10),Y
+    cpu.pc = 0x2000;
+    cpu.y = 0x80;
+    bus.mem_write(0x2000, 0x91);
+    bus.mem_write(0x2001, 0x10);
+    bus.mem_write(0x0010, 0x80);
+    bus.mem_write(0x0011, 0x40);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.cycles, 6);
+}
+
+#[test]
+fn test_shift_absolute_x() {
+    let cases = [
+        (0x1E, Opcode::ASL),
+        (0x5E, Opcode::LSR),
+        (0x3E, Opcode::ROL),
+        (0x7E, Opcode::ROR),
+    ];
+    // Cycles without and with page cross: only the NMOS 6502 always spends the indexing cycle
+    let variants = [
+        (Variant::NMOS6502, 7, 7),
+        (Variant::W65C02, 6, 7),
+        (Variant::R65C02, 6, 7),
+        (Variant::G65SC02, 6, 7),
+    ];
+    for (variant, same_page, page_cross) in variants {
+        for (code, opcode) in cases {
+            let mut cpu = CPU::with_variant(variant);
+            let mut bus = FakeBus::new();
+            bus.mem_write(0x2000, code);
+            bus.mem_write(0x2001, 0x80);
+            bus.mem_write(0x2002, 0x40);
+
+            cpu.pc = 0x2000;
+            cpu.x = 0x7F;
+            let inst = cpu.exec(&mut bus).unwrap();
+            assert_eq!(inst.opcode, opcode);
+            assert_eq!(inst.cycles, same_page, "{:?} {:?} $4080,X without page cross", variant, opcode);
+
+            cpu.pc = 0x2000;
+            cpu.x = 0x80;
+            let inst = cpu.exec(&mut bus).unwrap();
+            assert_eq!(inst.cycles, page_cross, "{:?} {:?} $4080,X with page cross", variant, opcode);
+        }
+    }
+}
+
+#[test]
+fn test_decimal_mode() {
+    let cases = [
+        (0x69, Opcode::ADC),
+        (0xE9, Opcode::SBC),
+    ];
+    for (code, opcode) in cases {
+        let mut cpu = CPU::new();
+        let mut bus = FakeBus::new();
+        bus.mem_write(0x2000, code);
+        bus.mem_write(0x2001, 0x01);
+
+        // Binary
+        cpu.pc = 0x2000;
+        let inst = cpu.exec(&mut bus).unwrap();
+        assert_eq!(inst.opcode, opcode);
+        assert_eq!(inst.cycles, 2, "{:?} in binary mode", opcode);
+
+        // Decimal
+        cpu.pc = 0x2000;
+        cpu.status.insert(Flags::DECIMAL);
+        let inst = cpu.exec(&mut bus).unwrap();
+        assert_eq!(inst.cycles, 3, "{:?} in decimal mode", opcode);
+    }
+
+    // Both penalties add up
+    let mut cpu = CPU::new();
+    let mut bus = FakeBus::new();
+    cpu.pc = 0x2000;
+    cpu.x = 0x80;
+    cpu.status.insert(Flags::DECIMAL);
+    bus.mem_write(0x2000, 0x7D); // ADC $4080,X
+    bus.mem_write(0x2001, 0x80);
+    bus.mem_write(0x2002, 0x40);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.cycles, 6);
+}
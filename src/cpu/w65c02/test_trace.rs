@@ -0,0 +1,147 @@
+use std::cell::RefCell;
+use std::io;
+use std::rc::Rc;
+
+use crate::cpu::w65c02::{Bus, CPU, Opcode, Step, Tracer, Variant, bus::FakeBus, cpu::Flags};
+
+/// Output shared between a test and the tracer owned by the CPU.
+#[derive(Clone, Default)]
+struct SharedOutput(Rc<RefCell<Vec<u8>>>);
+
+impl SharedOutput {
+    fn lines(&self) -> Vec<String> {
+        String::from_utf8(self.0.borrow().clone()).unwrap().lines().map(String::from).collect()
+    }
+}
+
+impl io::Write for SharedOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn setup(program: &[u8]) -> (CPU, FakeBus) {
+    let mut cpu = CPU::with_variant(Variant::W65C02);
+    let mut bus = FakeBus::new();
+    for (i, byte) in program.iter().enumerate() {
+        bus.mem_write(0xC000 + i as u16, *byte);
+    }
+    cpu.pc = 0xC000;
+    cpu.sp = 0xFD;
+    cpu.status = Flags::UNUSED | Flags::INTERRUPT;
+    (cpu, bus)
+}
+
+/// Output that fails every write.
+struct BrokenOutput;
+
+impl io::Write for BrokenOutput {
+    fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+        Err(io::Error::new(io::ErrorKind::Other, "broken"))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_trace_line_layout() {
+    // The reset sequence takes 7 cycles and leaves SP at $FD, as at the start of nestest
+    let (mut cpu, mut bus) = setup(&[0x4C, 0xF5, 0xC5]); // JMP $C5F5
+    bus.mem_write(0xFFFC, 0x00);
+    bus.mem_write(0xFFFD, 0xC0);
+    cpu.sp = 0x00;
+    cpu.reset(&mut bus);
+    let output = SharedOutput::default();
+    cpu.set_observer(Tracer::new(output.clone()));
+    cpu.exec(&mut bus).unwrap();
+    assert_eq!(output.lines(), [
+        "C000  4C F5 C5  JMP $C5F5                       A:00 X:00 Y:00 P:24 SP:FD CYC:7",
+    ]);
+}
+
+#[test]
+fn test_trace_cumulative_cycles() {
+    // LDA #$42, TAX, INX, STA $0200
+    let (mut cpu, mut bus) = setup(&[0xA9, 0x42, 0xAA, 0xE8, 0x8D, 0x00, 0x02]);
+    let output = SharedOutput::default();
+    cpu.set_observer(Tracer::new(output.clone()));
+    for _ in 0..4 {
+        cpu.exec(&mut bus).unwrap();
+    }
+    assert_eq!(output.lines(), [
+        "C000  A9 42     LDA #$42                        A:00 X:00 Y:00 P:24 SP:FD CYC:0",
+        "C002  AA        TAX                             A:42 X:00 Y:00 P:24 SP:FD CYC:2",
+        "C003  E8        INX                             A:42 X:42 Y:00 P:24 SP:FD CYC:4",
+        "C004  8D 00 02  STA $0200                       A:42 X:43 Y:00 P:24 SP:FD CYC:6",
+    ]);
+}
+
+#[test]
+fn test_trace_cycles_outside_instructions() {
+    // WAI, then NOP once a masked IRQ wakes the CPU up
+    let (mut cpu, mut bus) = setup(&[0xCB, 0xEA]);
+    let output = SharedOutput::default();
+    cpu.set_observer(Tracer::new(output.clone()));
+    cpu.exec(&mut bus).unwrap();
+    cpu.exec(&mut bus).unwrap();
+    cpu.exec(&mut bus).unwrap();
+    cpu.set_irq(true);
+    cpu.exec(&mut bus).unwrap();
+
+    // The cycles idled while waiting are not steps, but they count
+    let lines = output.lines();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[1].ends_with("CYC:5"), "{}", lines[1]);
+}
+
+#[test]
+fn test_trace_write_error() {
+    let (mut cpu, mut bus) = setup(&[0xEA, 0xEA]);
+    cpu.set_observer(Tracer::new(BrokenOutput));
+    cpu.exec(&mut bus).unwrap();
+    cpu.exec(&mut bus).unwrap();
+    let err = cpu.remove_observer().unwrap_err();
+    assert_eq!(err.to_string(), "broken");
+    assert!(cpu.remove_observer().is_ok());
+}
+
+#[test]
+fn test_observer_sees_interrupts() {
+    let (mut cpu, mut bus) = setup(&[0xEA]);
+    cpu.status = Flags::UNUSED;
+    bus.mem_write(0xFFFE, 0x00);
+    bus.mem_write(0xFFFF, 0xD0);
+    let steps = Rc::new(RefCell::new(Vec::<Step>::new()));
+    let recorded = steps.clone();
+    cpu.set_observer(move |step: &Step| recorded.borrow_mut().push(*step));
+
+    cpu.set_irq(true);
+    cpu.exec(&mut bus).unwrap();
+    let steps = steps.borrow();
+    assert_eq!(steps.len(), 1);
+    assert_eq!(steps[0].pc, 0xC000);
+    assert_eq!(steps[0].inst.opcode, Opcode::IRQ);
+    assert_eq!(steps[0].inst.cycles, 7);
+    assert_eq!(steps[0].disassembly, None);
+    assert_eq!(steps[0].cycles, 0);
+    assert_eq!(steps[0].trace_line(),
+        "C000            IRQ                             A:00 X:00 Y:00 P:20 SP:FD CYC:0");
+}
+
+#[test]
+fn test_remove_observer() {
+    let (mut cpu, mut bus) = setup(&[0xEA, 0xEA]);
+    let output = SharedOutput::default();
+    cpu.set_observer(Tracer::new(output.clone()));
+    cpu.exec(&mut bus).unwrap();
+    cpu.remove_observer().unwrap();
+    cpu.exec(&mut bus).unwrap();
+    assert_eq!(output.lines().len(), 1);
+}
@@ -0,0 +1,377 @@
+use crate::cpu::ExecErrorKind;
+use crate::cpu::w65c02::{Bus, CPU, RunState, Variant, bus::FakeBus, cpu::Flags, inst::Opcode};
+
+fn nmos() -> (CPU, FakeBus) {
+    let mut cpu = CPU::with_variant(Variant::NMOS6502);
+    cpu.pc = 0x2000;
+    (cpu, FakeBus::new())
+}
+
+#[test]
+fn test_slo() {
+    let (mut cpu, mut bus) = nmos();
+    cpu.a = 0x01;
+    bus.mem_write(0x2000, 0x07); // SLO $42
+    bus.mem_write(0x2001, 0x42);
+    bus.mem_write(0x0042, 0x81);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::SLO);
+    assert_eq!(bus.mem_read(0x0042), 0x02);
+    assert_eq!(cpu.a, 0x03);
+    assert!(cpu.status.contains(Flags::CARRY));
+    assert_eq!(inst.cycles, 5);
+}
+
+#[test]
+fn test_rla() {
+    let (mut cpu, mut bus) = nmos();
+    cpu.a = 0x0F;
+    cpu.status.insert(Flags::CARRY);
+    bus.mem_write(0x2000, 0x2F); // RLA $4000
+    bus.mem_write(0x2001, 0x00);
+    bus.mem_write(0x2002, 0x40);
+    bus.mem_write(0x4000, 0x84);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::RLA);
+    assert_eq!(bus.mem_read(0x4000), 0x09);
+    assert_eq!(cpu.a, 0x09);
+    assert!(cpu.status.contains(Flags::CARRY));
+    assert_eq!(inst.cycles, 6);
+}
+
+#[test]
+fn test_sre() {
+    let (mut cpu, mut bus) = nmos();
+    cpu.a = 0xFF;
+    cpu.x = 0x01;
+    bus.mem_write(0x2000, 0x57); // SRE $41,X
+    bus.mem_write(0x2001, 0x41);
+    bus.mem_write(0x0042, 0x03);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::SRE);
+    assert_eq!(bus.mem_read(0x0042), 0x01);
+    assert_eq!(cpu.a, 0xFE);
+    assert!(cpu.status.contains(Flags::CARRY));
+    assert!(cpu.status.contains(Flags::NEGATIVE));
+    assert_eq!(inst.cycles, 6);
+}
+
+#[test]
+fn test_rra() {
+    let (mut cpu, mut bus) = nmos();
+    cpu.a = 0x10;
+    bus.mem_write(0x2000, 0x67); // RRA $42
+    bus.mem_write(0x2001, 0x42);
+    bus.mem_write(0x0042, 0x05);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::RRA);
+    assert_eq!(bus.mem_read(0x0042), 0x02);
+
+    // The bit rotated out is carried into the addition
+    assert_eq!(cpu.a, 0x13);
+    assert!(!cpu.status.contains(Flags::CARRY));
+    assert_eq!(inst.cycles, 5);
+}
+
+#[test]
+fn test_sax() {
+    let (mut cpu, mut bus) = nmos();
+    cpu.a = 0xF0;
+    cpu.x = 0x3C;
+    cpu.status = Flags::empty();
+    bus.mem_write(0x2000, 0x87); // SAX $42
+    bus.mem_write(0x2001, 0x42);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::SAX);
+    assert_eq!(bus.mem_read(0x0042), 0x30);
+    assert_eq!(cpu.status, Flags::empty());
+    assert_eq!(inst.cycles, 3);
+}
+
+#[test]
+fn test_lax() {
+    let (mut cpu, mut bus) = nmos();
+
+    // Absolute,Y
+    cpu.y = 0x01;
+    bus.mem_write(0x2000, 0xBF); // LAX $40FF,Y
+    bus.mem_write(0x2001, 0xFF);
+    bus.mem_write(0x2002, 0x40);
+    bus.mem_write(0x4100, 0x80);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::LAX);
+    assert_eq!(cpu.a, 0x80);
+    assert_eq!(cpu.x, 0x80);
+    assert!(cpu.status.contains(Flags::NEGATIVE));
+    assert_eq!(inst.cycles, 5);
+
+    // (Indirect),Y
+    cpu.pc = 0x2000;
+    cpu.y = 0x00;
+    bus.mem_write(0x2000, 0xB3); // LAX ($10),Y
+    bus.mem_write(0x2001, 0x10);
+    bus.mem_write(0x0010, 0x00);
+    bus.mem_write(0x0011, 0x41);
+    bus.mem_write(0x4100, 0x00);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.a, 0x00);
+    assert_eq!(cpu.x, 0x00);
+    assert!(cpu.status.contains(Flags::ZERO));
+    assert_eq!(inst.cycles, 5);
+}
+
+#[test]
+fn test_dcp() {
+    let (mut cpu, mut bus) = nmos();
+    cpu.a = 0x41;
+    bus.mem_write(0x2000, 0xC7); // DCP $42
+    bus.mem_write(0x2001, 0x42);
+    bus.mem_write(0x0042, 0x42);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::DCP);
+    assert_eq!(bus.mem_read(0x0042), 0x41);
+    assert_eq!(cpu.a, 0x41);
+    assert!(cpu.status.contains(Flags::ZERO));
+    assert!(cpu.status.contains(Flags::CARRY));
+    assert_eq!(inst.cycles, 5);
+}
+
+#[test]
+fn test_isc() {
+    let (mut cpu, mut bus) = nmos();
+    cpu.a = 0x50;
+    cpu.status.insert(Flags::CARRY);
+    bus.mem_write(0x2000, 0xE3); // ISC ($40,X)
+    bus.mem_write(0x2001, 0x40);
+    bus.mem_write(0x0040, 0x00);
+    bus.mem_write(0x0041, 0x30);
+    bus.mem_write(0x3000, 0x0F);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::ISC);
+    assert_eq!(bus.mem_read(0x3000), 0x10);
+    assert_eq!(cpu.a, 0x40);
+    assert!(cpu.status.contains(Flags::CARRY));
+    assert_eq!(inst.cycles, 8);
+}
+
+#[test]
+fn test_immediate() {
+    let (mut cpu, mut bus) = nmos();
+
+    // ANC
+    cpu.a = 0xF0;
+    bus.mem_write(0x2000, 0x0B); // ANC #$81
+    bus.mem_write(0x2001, 0x81);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::ANC);
+    assert_eq!(cpu.a, 0x80);
+    assert!(cpu.status.contains(Flags::CARRY));
+    assert!(cpu.status.contains(Flags::NEGATIVE));
+    assert_eq!(inst.cycles, 2);
+
+    // ALR
+    cpu.pc = 0x2000;
+    cpu.a = 0xFF;
+    bus.mem_write(0x2000, 0x4B); // ALR #$03
+    bus.mem_write(0x2001, 0x03);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::ALR);
+    assert_eq!(cpu.a, 0x01);
+    assert!(cpu.status.contains(Flags::CARRY));
+    assert!(!cpu.status.contains(Flags::NEGATIVE));
+
+    // ARR
+    cpu.pc = 0x2000;
+    cpu.a = 0xFF;
+    cpu.status = Flags::CARRY;
+    bus.mem_write(0x2000, 0x6B); // ARR #$C0
+    bus.mem_write(0x2001, 0xC0);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::ARR);
+    assert_eq!(cpu.a, 0xE0);
+    assert!(cpu.status.contains(Flags::CARRY));
+    assert!(!cpu.status.contains(Flags::OVERFLOW));
+    assert!(cpu.status.contains(Flags::NEGATIVE));
+
+    // AXS
+    cpu.pc = 0x2000;
+    cpu.a = 0x0F;
+    cpu.x = 0xFC;
+    bus.mem_write(0x2000, 0xCB); // AXS #$02
+    bus.mem_write(0x2001, 0x02);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::AXS);
+    assert_eq!(cpu.x, 0x0A);
+    assert!(cpu.status.contains(Flags::CARRY));
+
+    // SBC duplicate
+    cpu.pc = 0x2000;
+    cpu.a = 0x10;
+    cpu.status = Flags::CARRY;
+    bus.mem_write(0x2000, 0xEB); // SBC #$01
+    bus.mem_write(0x2001, 0x01);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::SBC);
+    assert_eq!(cpu.a, 0x0F);
+}
+
+#[test]
+fn test_arr_decimal() {
+    let (mut cpu, mut bus) = nmos();
+    cpu.a = 0xFF;
+    cpu.status = Flags::DECIMAL;
+    bus.mem_write(0x2000, 0x6B); // ARR #$FF
+    bus.mem_write(0x2001, 0xFF);
+    cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.a, 0xD5);
+    assert!(cpu.status.contains(Flags::CARRY));
+    assert!(!cpu.status.contains(Flags::OVERFLOW));
+}
+
+#[test]
+fn test_las() {
+    let (mut cpu, mut bus) = nmos();
+    cpu.sp = 0xF3;
+    cpu.y = 0x10;
+    bus.mem_write(0x2000, 0xBB); // LAS $4000,Y
+    bus.mem_write(0x2001, 0x00);
+    bus.mem_write(0x2002, 0x40);
+    bus.mem_write(0x4010, 0x3F);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::LAS);
+    assert_eq!(cpu.a, 0x33);
+    assert_eq!(cpu.x, 0x33);
+    assert_eq!(cpu.sp, 0x33);
+    assert_eq!(inst.cycles, 4);
+}
+
+#[test]
+fn test_nop() {
+    let cases = [
+        (0x1A, 1, 2),
+        (0x80, 2, 2),
+        (0x04, 2, 3),
+        (0x14, 2, 4),
+        (0x0C, 3, 4),
+        (0x1C, 3, 4),
+    ];
+    for (code, len, cycles) in cases {
+        let (mut cpu, mut bus) = nmos();
+        bus.mem_write(0x2000, code);
+        let inst = cpu.exec(&mut bus).unwrap();
+        assert_eq!(inst.opcode, Opcode::NOP);
+        assert_eq!(cpu.pc, 0x2000 + len, "length of NOP {:02X}", code);
+        assert_eq!(inst.cycles, cycles, "cycles of NOP {:02X}", code);
+    }
+
+    // Absolute,X with page cross
+    let (mut cpu, mut bus) = nmos();
+    cpu.x = 0x01;
+    bus.mem_write(0x2000, 0xFC); // NOP $40FF,X
+    bus.mem_write(0x2001, 0xFF);
+    bus.mem_write(0x2002, 0x40);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.cycles, 5);
+}
+
+#[test]
+fn test_jam() {
+    let (mut cpu, mut bus) = nmos();
+    bus.mem_write(0x2000, 0x02); // JAM
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::JAM);
+    assert_eq!(cpu.run_state(), RunState::Stopped);
+
+    let err = cpu.exec(&mut bus).unwrap_err();
+    assert_eq!(err.kind, ExecErrorKind::Stopped);
+    assert_eq!(err.pc, 0x2000);
+    assert_eq!(err.opcode, vec![0x02]);
+    assert_eq!(err.to_string(), "CPU stopped by opcode 02 at 2000");
+}
+
+#[test]
+fn test_unstable() {
+    let (mut cpu, mut bus) = nmos();
+
+    // XAA
+    cpu.a = 0x00;
+    cpu.x = 0xFF;
+    bus.mem_write(0x2000, 0x8B); // XAA #$FF
+    bus.mem_write(0x2001, 0xFF);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::XAA);
+    assert!(inst.opcode.is_unstable());
+    assert_eq!(cpu.a, 0xEE);
+
+    // LXA
+    cpu.pc = 0x2000;
+    cpu.a = 0x00;
+    bus.mem_write(0x2000, 0xAB); // LXA #$0F
+    bus.mem_write(0x2001, 0x0F);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::LXA);
+    assert!(inst.opcode.is_unstable());
+    assert_eq!(cpu.a, 0x0E);
+    assert_eq!(cpu.x, 0x0E);
+
+    // SHX
+    cpu.pc = 0x2000;
+    cpu.x = 0xFF;
+    cpu.y = 0x01;
+    bus.mem_write(0x2000, 0x9E); // SHX $1200,Y
+    bus.mem_write(0x2001, 0x00);
+    bus.mem_write(0x2002, 0x12);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::SHX);
+    assert!(inst.opcode.is_unstable());
+    assert_eq!(bus.mem_read(0x1201), 0x13);
+    assert_eq!(inst.cycles, 5);
+
+    // TAS
+    cpu.pc = 0x2000;
+    cpu.a = 0xF0;
+    cpu.x = 0x3F;
+    bus.mem_write(0x2000, 0x9B); // TAS $1200,Y
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::TAS);
+    assert_eq!(cpu.sp, 0x30);
+    assert_eq!(bus.mem_read(0x1201), 0x10);
+
+    // AHX wraps its pointer within the zero page
+    cpu.pc = 0x2000;
+    cpu.a = 0xFF;
+    cpu.x = 0xFF;
+    cpu.y = 0x01;
+    bus.mem_write(0x2000, 0x93); // AHX ($FF),Y
+    bus.mem_write(0x2001, 0xFF);
+    bus.mem_write(0x00FF, 0x00);
+    bus.mem_write(0x0000, 0x12);
+    bus.mem_write(0x0100, 0x30);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::AHX);
+    assert_eq!(bus.mem_read(0x1201), 0x13);
+
+    // Documented opcodes are stable
+    assert!(!Opcode::LAX.is_unstable());
+    assert!(!Opcode::LDA.is_unstable());
+}
+
+#[test]
+fn test_all_opcodes_defined() {
+    for code in 0..=255 {
+        let (mut cpu, mut bus) = nmos();
+        bus.mem_write(0x2000, code);
+        assert!(cpu.exec(&mut bus).is_ok(), "NMOS opcode {:02X}", code);
+    }
+}
+
+#[test]
+fn test_cmos_unaffected() {
+    let mut cpu = CPU::new();
+    let mut bus = FakeBus::new();
+    cpu.pc = 0x2000;
+    bus.mem_write(0x2000, 0xA7); // SMB2 $42 on the 65C02, LAX $42 on the NMOS 6502
+    bus.mem_write(0x2001, 0x42);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::SMB2);
+}
@@ -0,0 +1,177 @@
+use crate::cpu::w65c02::{Bus, CPU, Variant, bus::FakeBus, cpu::Flags, inst::Opcode};
+
+#[test]
+fn test_default_variant() {
+    let cpu = CPU::new();
+    assert_eq!(cpu.variant(), Variant::W65C02);
+}
+
+#[test]
+fn test_jmp_indirect_page_boundary() {
+    let mut bus = FakeBus::new();
+    bus.mem_write(0x2000, 0x6C); // JMP ($12FF)
+    bus.mem_write(0x2001, 0xFF);
+    bus.mem_write(0x2002, 0x12);
+    bus.mem_write(0x12FF, 0x78);
+    bus.mem_write(0x1200, 0x34);
+    bus.mem_write(0x1300, 0x56);
+
+    // NMOS reads the high byte from the start of the same page
+    let mut cpu = CPU::with_variant(Variant::NMOS6502);
+    cpu.pc = 0x2000;
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.pc, 0x3478);
+    assert_eq!(inst.cycles, 5);
+
+    // CMOS variants fixed the bug at the cost of one cycle
+    for variant in [Variant::W65C02, Variant::R65C02, Variant::G65SC02] {
+        let mut cpu = CPU::with_variant(variant);
+        cpu.pc = 0x2000;
+        let inst = cpu.exec(&mut bus).unwrap();
+        assert_eq!(cpu.pc, 0x5678);
+        assert_eq!(inst.cycles, 6);
+    }
+}
+
+#[test]
+fn test_decimal_flag_on_interrupt() {
+    let mut bus = FakeBus::new();
+    bus.mem_write(0x2000, 0x00); // BRK
+
+    // NMOS leaves D untouched
+    let mut cpu = CPU::with_variant(Variant::NMOS6502);
+    cpu.pc = 0x2000;
+    cpu.sp = 0xFF;
+    cpu.status = Flags::DECIMAL;
+    cpu.exec(&mut bus).unwrap();
+    assert!(cpu.status.contains(Flags::DECIMAL));
+
+    cpu.pc = 0x2000;
+    cpu.status = Flags::DECIMAL;
+    cpu.set_irq(true);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::IRQ);
+    assert!(cpu.status.contains(Flags::DECIMAL));
+
+    // CMOS clears it
+    let mut cpu = CPU::with_variant(Variant::R65C02);
+    cpu.pc = 0x2000;
+    cpu.sp = 0xFF;
+    cpu.status = Flags::DECIMAL;
+    cpu.exec(&mut bus).unwrap();
+    assert!(!cpu.status.contains(Flags::DECIMAL));
+}
+
+#[test]
+fn test_nmos_decimal_flags() {
+    let mut cpu = CPU::with_variant(Variant::NMOS6502);
+    let mut bus = FakeBus::new();
+
+    // 99 + 1: N from the unadjusted sum, Z from the binary sum, no extra cycle
+    cpu.pc = 0x2000;
+    cpu.a = 0x99;
+    cpu.status = Flags::DECIMAL;
+    bus.mem_write(0x2000, 0x69); // ADC #$01
+    bus.mem_write(0x2001, 0x01);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.a, 0x00);
+    assert_eq!(cpu.status, Flags::DECIMAL | Flags::NEGATIVE | Flags::CARRY);
+    assert_eq!(inst.cycles, 2);
+
+    // 0 - 1: N and Z from the binary result
+    cpu.pc = 0x2000;
+    cpu.a = 0x00;
+    cpu.status = Flags::DECIMAL | Flags::CARRY;
+    bus.mem_write(0x2000, 0xE9); // SBC #$01
+    bus.mem_write(0x2001, 0x01);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.a, 0x99);
+    assert_eq!(cpu.status, Flags::DECIMAL | Flags::NEGATIVE);
+    assert_eq!(inst.cycles, 2);
+
+    // Invalid BCD digits are adjusted differently than on CMOS parts
+    cpu.pc = 0x2000;
+    cpu.a = 0x10;
+    cpu.status = Flags::DECIMAL | Flags::CARRY;
+    bus.mem_write(0x2001, 0x0F);
+    cpu.exec(&mut bus).unwrap();
+    assert_eq!(cpu.a, 0x0B);
+}
+
+#[test]
+fn test_bit_ops_availability() {
+    let mut bus = FakeBus::new();
+    bus.mem_write(0x2000, 0x87); // SMB0 $42
+    bus.mem_write(0x2001, 0x42);
+
+    for variant in [Variant::W65C02, Variant::R65C02] {
+        let mut cpu = CPU::with_variant(variant);
+        bus.mem_write(0x0042, 0x00);
+        cpu.pc = 0x2000;
+        let inst = cpu.exec(&mut bus).unwrap();
+        assert_eq!(inst.opcode, Opcode::SMB0);
+        assert_eq!(bus.mem_read(0x0042), 0x01);
+    }
+
+    // A single byte, single cycle no-op on the 65SC02
+    let mut cpu = CPU::with_variant(Variant::G65SC02);
+    bus.mem_write(0x0042, 0x00);
+    cpu.pc = 0x2000;
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::NOP);
+    assert_eq!(inst.cycles, 1);
+    assert_eq!(cpu.pc, 0x2001);
+    assert_eq!(bus.mem_read(0x0042), 0x00);
+}
+
+#[test]
+fn test_wai_stp_availability() {
+    let mut bus = FakeBus::new();
+    bus.mem_write(0x2000, 0xCB); // WAI
+    bus.mem_write(0x2001, 0xDB); // STP
+
+    for variant in [Variant::R65C02, Variant::G65SC02] {
+        let mut cpu = CPU::with_variant(variant);
+        cpu.pc = 0x2000;
+        let inst = cpu.exec(&mut bus).unwrap();
+        assert_eq!(inst.opcode, Opcode::NOP);
+        let inst = cpu.exec(&mut bus).unwrap();
+        assert_eq!(inst.opcode, Opcode::NOP);
+        assert_eq!(cpu.pc, 0x2002);
+    }
+}
+
+#[test]
+fn test_reserved_opcodes() {
+    let cases = [
+        (0x02, 2, 2),
+        (0x44, 2, 3),
+        (0x54, 2, 4),
+        (0x5C, 3, 8),
+        (0xDC, 3, 4),
+        (0x03, 1, 1),
+        (0xFB, 1, 1),
+    ];
+    for (code, len, cycles) in cases {
+        let mut cpu = CPU::new();
+        let mut bus = FakeBus::new();
+        cpu.pc = 0x2000;
+        bus.mem_write(0x2000, code);
+        let inst = cpu.exec(&mut bus).unwrap();
+        assert_eq!(inst.opcode, Opcode::NOP);
+        assert_eq!(cpu.pc, 0x2000 + len, "length of reserved opcode {:02X}", code);
+        assert_eq!(inst.cycles, cycles, "cycles of reserved opcode {:02X}", code);
+    }
+}
+
+#[test]
+fn test_nmos_ignores_cmos_opcodes() {
+    let mut cpu = CPU::with_variant(Variant::NMOS6502);
+    let mut bus = FakeBus::new();
+    cpu.pc = 0x2000;
+    bus.mem_write(0x2000, 0x80); // BRA on CMOS variants
+    bus.mem_write(0x2001, 0x10);
+    let inst = cpu.exec(&mut bus).unwrap();
+    assert_eq!(inst.opcode, Opcode::NOP);
+    assert_eq!(cpu.pc, 0x2002);
+}
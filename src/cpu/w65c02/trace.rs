@@ -0,0 +1,118 @@
+use std::fmt::Write as _;
+use std::io;
+
+use crate::cpu::w65c02::{Bus, CPU, Disassembly, disassemble, inst::{Instruction, Opcode, Operand}};
+
+/// An executed instruction, with the registers as they were before it ran.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Step {
+    /// Cycles run by the CPU before the step, as counted by `CPU::cycles`.
+    pub cycles: usize,
+    pub pc: u16,
+    pub a: u8,
+    pub x: u8,
+    pub y: u8,
+    pub sp: u8,
+    pub status: u8,
+    /// Bytes of the instruction in memory, of which only the first `len` are meaningful.
+    pub bytes: [u8; 3],
+    pub len: usize,
+    /// The instruction as decoded from memory, or `None` if the CPU serviced an interrupt.
+    pub disassembly: Option<Disassembly>,
+    /// The instruction as executed, including the extra cycles it took.
+    pub inst: Instruction,
+}
+
+impl Step {
+    /// Capture the state before the instruction at the program counter runs.
+    pub(crate) fn before(cpu: &CPU, bus: &impl Bus, interrupt: bool) -> Self {
+        let disassembly = if interrupt { None } else { disassemble(bus, cpu.variant(), cpu.pc).ok() };
+        let len = disassembly.map_or(0, |d| d.len);
+        let mut bytes = [0; 3];
+        for (i, byte) in bytes.iter_mut().enumerate().take(len) {
+            *byte = bus.mem_read(cpu.pc.wrapping_add(i as u16));
+        }
+        Self {
+            cycles: cpu.cycles(),
+            pc: cpu.pc,
+            a: cpu.a,
+            x: cpu.x,
+            y: cpu.y,
+            sp: cpu.sp,
+            status: cpu.status.bits(),
+            bytes,
+            len,
+            disassembly,
+            // Filled in once the instruction has run
+            inst: Instruction { opcode: Opcode::NOP, operand: Operand::Implied, cycles: 0 },
+        }
+    }
+
+    /// Format the step in the layout of the nestest log: address, raw bytes, disassembly and
+    /// registers at fixed columns, followed by the number of cycles elapsed before the step.
+    ///
+    /// ```text
+    /// C000  4C F5 C5  JMP $C5F5                       A:00 X:00 Y:00 P:24 SP:FD CYC:7
+    /// ```
+    pub fn trace_line(&self) -> String {
+        let mut bytes = String::new();
+        for byte in &self.bytes[..self.len] {
+            let _ = write!(bytes, "{:02X} ", byte);
+        }
+        let text = match &self.disassembly {
+            Some(disassembly) => disassembly.to_string(),
+            None => format!("{:?}", self.inst.opcode),
+        };
+        format!("{:04X}  {:<10}{:<32}A:{:02X} X:{:02X} Y:{:02X} P:{:02X} SP:{:02X} CYC:{}",
+            self.pc, bytes, text.trim_end(), self.a, self.x, self.y, self.status, self.sp, self.cycles)
+    }
+}
+
+/// Receives every instruction executed by the CPU, and every interrupt it services.
+pub trait Observer {
+    fn observe(&mut self, step: &Step);
+
+    /// Complete the output once the observer is removed from the CPU, returning the first error
+    /// found while writing it.
+    fn finish(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<F: FnMut(&Step)> Observer for F {
+    fn observe(&mut self, step: &Step) {
+        self(step)
+    }
+}
+
+/// Observer that writes one trace line per step, as formatted by `Step::trace_line`.
+///
+/// A broken output does not interrupt the emulation: the tracer stops writing at the first
+/// error, which `CPU::remove_observer` returns.
+pub struct Tracer<W: io::Write> {
+    out: W,
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write> Tracer<W> {
+    pub fn new(out: W) -> Self {
+        Self { out, error: None }
+    }
+}
+
+impl<W: io::Write> Observer for Tracer<W> {
+    fn observe(&mut self, step: &Step) {
+        if self.error.is_none() {
+            if let Err(err) = writeln!(self.out, "{}", step.trace_line()) {
+                self.error = Some(err);
+            }
+        }
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        match self.error.take() {
+            Some(err) => Err(err),
+            None => self.out.flush(),
+        }
+    }
+}
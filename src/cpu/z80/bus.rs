@@ -6,8 +6,20 @@ pub trait Bus {
     fn io_read(&self, addr: u8) -> u8;
     fn io_write(&mut self, addr: u8, val: u8);
 
+    /// Level of the INT line as driven by the devices on the bus.
+    fn int(&self) -> bool { false }
+
+    /// Level of the NMI line as driven by the devices on the bus. The CPU reacts to its rising
+    /// edge.
+    fn nmi(&self) -> bool { false }
+
+    /// Byte put on the data bus by the interrupting device when the CPU acknowledges an INT: the
+    /// opcode to execute in IM 0, or the low byte of the vector table address in IM 2. It
+    /// defaults to 0xFF, what a floating data bus reads as.
+    fn int_ack(&mut self) -> u8 { 0xFF }
+
     fn mem_read_word(&self, addr: u16) -> u16 {
-        let data = [self.mem_read(addr), self.mem_read(addr + 1)];
+        let data = [self.mem_read(addr), self.mem_read(addr.wrapping_add(1))];
         LittleEndian::read_u16(&data)        
     }
 
@@ -15,7 +27,7 @@ pub trait Bus {
         let mut data = [0; 2];
         LittleEndian::write_u16(&mut data, val);
         self.mem_write(addr, data[0]);
-        self.mem_write(addr + 1, data[1]);
+        self.mem_write(addr.wrapping_add(1), data[1]);
     }
 }
 
@@ -29,6 +41,7 @@ impl Bus for () {
 pub struct FakeBus {
     mem: [u8; 64*1024],
     io: [u8; 256],
+    int_data: u8,
 }
 
 impl FakeBus {
@@ -36,8 +49,12 @@ impl FakeBus {
         Self {
             mem: [0; 64*1024],
             io: [0; 256],
+            int_data: 0xFF,
         }
     }
+
+    /// Set the byte supplied on interrupt acknowledge.
+    pub fn set_int_data(&mut self, val: u8) { self.int_data = val }
 }
 
 impl Bus for FakeBus {
@@ -45,4 +62,5 @@ impl Bus for FakeBus {
     fn mem_write(&mut self, addr: u16, val: u8) { self.mem[addr as usize] = val }
     fn io_read(&self, addr: u8) -> u8 { self.io[addr as usize] }
     fn io_write(&mut self, addr: u8, val: u8) { self.io[addr as usize] = val }
+    fn int_ack(&mut self) -> u8 { self.int_data }
 }
@@ -1,3 +1,4 @@
+use crate::cpu::{ExecError, ExecErrorKind};
 use crate::cpu::z80::bus::Bus;
 use crate::cpu::z80::reg::Registers;
 use crate::cpu::z80::flag::{self, Predicate};
@@ -9,11 +10,23 @@ pub struct CPU {
 
     iff1: bool,
     iff2: bool,
+    im: u8,
+
+    // Interrupt inputs, in addition to the lines driven by the bus
+    int: bool,
+    nmi: bool,
+    // Last level seen on the NMI line of the bus, to detect its edges
+    nmi_bus: bool,
+    nmi_pending: bool,
+    // Set by instructions after which INT is not accepted until another instruction runs
+    int_delay: bool,
+    halted: bool,
     
     flags_inc8: flag::PrecomputedUnary,
     flags_dec8: flag::PrecomputedUnary,
     flags_rla: flag::PrecomputedUnary,
     flags_rra: flag::PrecomputedUnary,
+    flags_shift: flag::PrecomputedUnary,
     flags_add8: flag::PrecomputedBinary,
     flags_sub8: flag::PrecomputedBinary,
     flags_and8: flag::PrecomputedBinary,
@@ -28,10 +41,18 @@ impl CPU {
             cycles: 0,
             iff1: false,
             iff2: false,
+            im: 0,
+            int: false,
+            nmi: false,
+            nmi_bus: false,
+            nmi_pending: false,
+            int_delay: false,
+            halted: false,
             flags_inc8: flag::PrecomputedUnary::for_inc8(),
             flags_dec8: flag::PrecomputedUnary::for_dec8(),
             flags_rla: flag::PrecomputedUnary::for_rla(),
             flags_rra: flag::PrecomputedUnary::for_rra(),
+            flags_shift: flag::PrecomputedUnary::for_shift(),
             flags_add8: flag::PrecomputedBinary::for_add8(),
             flags_sub8: flag::PrecomputedBinary::for_sub8(),
             flags_and8: flag::PrecomputedBinary::for_and8(),
@@ -46,8 +67,14 @@ impl CPU {
         self.regs.set_pc(0x0000);
         self.regs.set_af(0xFFFF);
         self.regs.set_sp(0xFFFF);
+        self.regs.set_i(0x00);
+        self.regs.set_r(0x00);
         self.iff1 = false;
         self.iff2 = false;
+        self.im = 0;
+        self.nmi_pending = false;
+        self.int_delay = false;
+        self.halted = false;
     }
 
     pub fn cycles(&self) -> usize { self.cycles }
@@ -56,12 +83,96 @@ impl CPU {
 
     pub fn regs(&self) -> &Registers { &self.regs }
 
-    pub fn exec<B: Bus>(&mut self, b: &mut B) {
+    pub fn regs_mut(&mut self) -> &mut Registers { &mut self.regs }
+
+    /// Whether the CPU is halted, running NOPs until an interrupt arrives.
+    pub fn halted(&self) -> bool { self.halted }
+
+    /// Assert or release the INT input. It is level triggered: an interrupt is serviced before
+    /// every instruction for as long as it stays asserted and interrupts are enabled.
+    pub fn set_int(&mut self, asserted: bool) { self.int = asserted }
+
+    /// Assert or release the NMI input. It is edge triggered: asserting it makes the CPU service
+    /// one interrupt before the next instruction, regardless of IFF1.
+    pub fn set_nmi(&mut self, asserted: bool) {
+        if asserted && !self.nmi {
+            self.nmi_pending = true;
+        }
+        self.nmi = asserted;
+    }
+
+    pub fn exec<B: Bus>(&mut self, b: &mut B) -> Result<(), ExecError> {
+        let nmi = b.nmi();
+        if nmi && !self.nmi_bus {
+            self.nmi_pending = true;
+        }
+        self.nmi_bus = nmi;
+
+        let int_delay = std::mem::take(&mut self.int_delay);
+        // Instructions, interrupt acknowledges and the NOPs run while halted all start with an M1
+        // cycle, that counts in R
+        self.regs.inc_r();
+        if self.nmi_pending {
+            self.nmi_pending = false;
+            self.accept_nmi(b);
+            return Ok(());
+        }
+        if self.iff1 && !int_delay && (self.int || b.int()) {
+            return self.accept_int(b);
+        }
+        if self.halted {
+            self.exec_nop(0, 4);
+            return Ok(());
+        }
+
         let opcode = b.mem_read(self.regs.pc());
-        self.decode(b, opcode);
+        self.decode(b, opcode)
+    }
+
+    fn accept_nmi(&mut self, bus: &mut impl Bus) {
+        // IFF2 keeps the state of IFF1, so RETN can restore it
+        self.iff1 = false;
+        self.halted = false;
+        self.stack_push(bus, self.regs.pc());
+        self.regs.set_pc(0x0066);
+        self.cycles += 11;
     }
 
-    fn decode(&mut self, bus: &mut impl Bus, opcode: u8) {
+    fn accept_int(&mut self, bus: &mut impl Bus) -> Result<(), ExecError> {
+        let data = bus.int_ack();
+        let pc = self.regs.pc();
+        if self.im == 0 && !single_byte(data) {
+            return Err(ExecError::new(ExecErrorKind::Unimplemented, pc, &[data]));
+        }
+        self.iff1 = false;
+        self.iff2 = false;
+        self.halted = false;
+        match self.im {
+            2 => {
+                self.stack_push(bus, pc);
+                let vector = u16::from_le_bytes([data, self.regs.i()]);
+                self.regs.set_pc(bus.mem_read_word(vector));
+                self.cycles += 19;
+            },
+            // In IM 0 the device supplies an instruction, usually a RST. It runs as if fetched
+            // right before PC, so it returns to PC when it moves past itself, and RST pushes PC.
+            // Only single byte instructions are supported, as their operands would come from the
+            // bus too. The acknowledge adds two wait states.
+            0 => {
+                self.regs.set_pc(pc.wrapping_sub(1));
+                self.decode(bus, data)?;
+                self.cycles += 2;
+            },
+            _ => {
+                self.stack_push(bus, pc);
+                self.regs.set_pc(0x0038);
+                self.cycles += 13;
+            },
+        }
+        Ok(())
+    }
+
+    fn decode(&mut self, bus: &mut impl Bus, opcode: u8) -> Result<(), ExecError> {
         match opcode {
             0x00 => self.exec_nop(1, 4),
             0x01 => self.exec_ld(bus, Reg16::BC, Imm16::with_offset(1), 3, 10),
@@ -278,7 +389,7 @@ impl CPU {
             0xC8 => self.exec_ret(bus, flag::Z),
             0xC9 => self.exec_ret(bus, flag::Any),
             0xCA => self.exec_jp(bus, flag::Z, Imm16::with_offset(1), 3, 10),
-            0xCB => todo!(),
+            0xCB => self.decode_bits(bus, bus.mem_read(self.regs.pc().wrapping_add(1))),
             0xCC => self.exec_call(bus, flag::Z),
             0xCD => self.exec_call(bus, flag::Any),
             0xCE => self.exec_add8(bus, Reg8::A, Imm8::with_offset(1), true, 2, 4),
@@ -297,7 +408,7 @@ impl CPU {
             0xDA => self.exec_jp(bus, flag::C, Imm16::with_offset(1), 3, 10),
             0xDB => self.exec_in(bus, Some(Reg8::A), Imm8::with_offset(1), false, 2, 11),
             0xDC => self.exec_call(bus, flag::C),
-            0xDD => todo!(),
+            0xDD => return self.decode_indexed(bus, Reg16::IX),
             0xDE => self.exec_sub8(bus, Reg8::A, Imm8::with_offset(1), true, 2, 4),
             0xDF => self.exec_rst(bus, 0x18),
 
@@ -331,36 +442,796 @@ impl CPU {
             0xFA => self.exec_jp(bus, flag::N, Imm16::with_offset(1), 3, 10),
             0xFB => self.exec_ei(),
             0xFC => self.exec_call(bus, flag::N),
-            0xFD => todo!(),
+            0xFD => return self.decode_indexed(bus, Reg16::IY),
             0xFE => self.exec_cp(bus, Reg8::A, Imm8::with_offset(1), 2, 7),
             0xFF => self.exec_rst(bus, 0x38),
         }
+        Ok(())
+    }
+
+    fn decode_bits(&mut self, bus: &mut impl Bus, opcode: u8) {
+        // The opcode after the prefix takes its own M1 cycle
+        self.regs.inc_r();
+        match opcode {
+            0x00 => self.exec_rlc(bus, Reg8::B, 2, 8),
+            0x01 => self.exec_rlc(bus, Reg8::C, 2, 8),
+            0x02 => self.exec_rlc(bus, Reg8::D, 2, 8),
+            0x03 => self.exec_rlc(bus, Reg8::E, 2, 8),
+            0x04 => self.exec_rlc(bus, Reg8::H, 2, 8),
+            0x05 => self.exec_rlc(bus, Reg8::L, 2, 8),
+            0x06 => self.exec_rlc(bus, Ind8(Reg16::HL), 2, 15),
+            0x07 => self.exec_rlc(bus, Reg8::A, 2, 8),
+            0x08 => self.exec_rrc(bus, Reg8::B, 2, 8),
+            0x09 => self.exec_rrc(bus, Reg8::C, 2, 8),
+            0x0A => self.exec_rrc(bus, Reg8::D, 2, 8),
+            0x0B => self.exec_rrc(bus, Reg8::E, 2, 8),
+            0x0C => self.exec_rrc(bus, Reg8::H, 2, 8),
+            0x0D => self.exec_rrc(bus, Reg8::L, 2, 8),
+            0x0E => self.exec_rrc(bus, Ind8(Reg16::HL), 2, 15),
+            0x0F => self.exec_rrc(bus, Reg8::A, 2, 8),
+
+            0x10 => self.exec_rl(bus, Reg8::B, 2, 8),
+            0x11 => self.exec_rl(bus, Reg8::C, 2, 8),
+            0x12 => self.exec_rl(bus, Reg8::D, 2, 8),
+            0x13 => self.exec_rl(bus, Reg8::E, 2, 8),
+            0x14 => self.exec_rl(bus, Reg8::H, 2, 8),
+            0x15 => self.exec_rl(bus, Reg8::L, 2, 8),
+            0x16 => self.exec_rl(bus, Ind8(Reg16::HL), 2, 15),
+            0x17 => self.exec_rl(bus, Reg8::A, 2, 8),
+            0x18 => self.exec_rr(bus, Reg8::B, 2, 8),
+            0x19 => self.exec_rr(bus, Reg8::C, 2, 8),
+            0x1A => self.exec_rr(bus, Reg8::D, 2, 8),
+            0x1B => self.exec_rr(bus, Reg8::E, 2, 8),
+            0x1C => self.exec_rr(bus, Reg8::H, 2, 8),
+            0x1D => self.exec_rr(bus, Reg8::L, 2, 8),
+            0x1E => self.exec_rr(bus, Ind8(Reg16::HL), 2, 15),
+            0x1F => self.exec_rr(bus, Reg8::A, 2, 8),
+
+            0x20 => self.exec_sla(bus, Reg8::B, 2, 8),
+            0x21 => self.exec_sla(bus, Reg8::C, 2, 8),
+            0x22 => self.exec_sla(bus, Reg8::D, 2, 8),
+            0x23 => self.exec_sla(bus, Reg8::E, 2, 8),
+            0x24 => self.exec_sla(bus, Reg8::H, 2, 8),
+            0x25 => self.exec_sla(bus, Reg8::L, 2, 8),
+            0x26 => self.exec_sla(bus, Ind8(Reg16::HL), 2, 15),
+            0x27 => self.exec_sla(bus, Reg8::A, 2, 8),
+            0x28 => self.exec_sra(bus, Reg8::B, 2, 8),
+            0x29 => self.exec_sra(bus, Reg8::C, 2, 8),
+            0x2A => self.exec_sra(bus, Reg8::D, 2, 8),
+            0x2B => self.exec_sra(bus, Reg8::E, 2, 8),
+            0x2C => self.exec_sra(bus, Reg8::H, 2, 8),
+            0x2D => self.exec_sra(bus, Reg8::L, 2, 8),
+            0x2E => self.exec_sra(bus, Ind8(Reg16::HL), 2, 15),
+            0x2F => self.exec_sra(bus, Reg8::A, 2, 8),
+
+            0x30 => self.exec_sll(bus, Reg8::B, 2, 8),
+            0x31 => self.exec_sll(bus, Reg8::C, 2, 8),
+            0x32 => self.exec_sll(bus, Reg8::D, 2, 8),
+            0x33 => self.exec_sll(bus, Reg8::E, 2, 8),
+            0x34 => self.exec_sll(bus, Reg8::H, 2, 8),
+            0x35 => self.exec_sll(bus, Reg8::L, 2, 8),
+            0x36 => self.exec_sll(bus, Ind8(Reg16::HL), 2, 15),
+            0x37 => self.exec_sll(bus, Reg8::A, 2, 8),
+            0x38 => self.exec_srl(bus, Reg8::B, 2, 8),
+            0x39 => self.exec_srl(bus, Reg8::C, 2, 8),
+            0x3A => self.exec_srl(bus, Reg8::D, 2, 8),
+            0x3B => self.exec_srl(bus, Reg8::E, 2, 8),
+            0x3C => self.exec_srl(bus, Reg8::H, 2, 8),
+            0x3D => self.exec_srl(bus, Reg8::L, 2, 8),
+            0x3E => self.exec_srl(bus, Ind8(Reg16::HL), 2, 15),
+            0x3F => self.exec_srl(bus, Reg8::A, 2, 8),
+
+            0x40 => self.exec_bit(bus, 0, Reg8::B, 2, 8),
+            0x41 => self.exec_bit(bus, 0, Reg8::C, 2, 8),
+            0x42 => self.exec_bit(bus, 0, Reg8::D, 2, 8),
+            0x43 => self.exec_bit(bus, 0, Reg8::E, 2, 8),
+            0x44 => self.exec_bit(bus, 0, Reg8::H, 2, 8),
+            0x45 => self.exec_bit(bus, 0, Reg8::L, 2, 8),
+            0x46 => self.exec_bit(bus, 0, Ind8(Reg16::HL), 2, 12),
+            0x47 => self.exec_bit(bus, 0, Reg8::A, 2, 8),
+            0x48 => self.exec_bit(bus, 1, Reg8::B, 2, 8),
+            0x49 => self.exec_bit(bus, 1, Reg8::C, 2, 8),
+            0x4A => self.exec_bit(bus, 1, Reg8::D, 2, 8),
+            0x4B => self.exec_bit(bus, 1, Reg8::E, 2, 8),
+            0x4C => self.exec_bit(bus, 1, Reg8::H, 2, 8),
+            0x4D => self.exec_bit(bus, 1, Reg8::L, 2, 8),
+            0x4E => self.exec_bit(bus, 1, Ind8(Reg16::HL), 2, 12),
+            0x4F => self.exec_bit(bus, 1, Reg8::A, 2, 8),
+
+            0x50 => self.exec_bit(bus, 2, Reg8::B, 2, 8),
+            0x51 => self.exec_bit(bus, 2, Reg8::C, 2, 8),
+            0x52 => self.exec_bit(bus, 2, Reg8::D, 2, 8),
+            0x53 => self.exec_bit(bus, 2, Reg8::E, 2, 8),
+            0x54 => self.exec_bit(bus, 2, Reg8::H, 2, 8),
+            0x55 => self.exec_bit(bus, 2, Reg8::L, 2, 8),
+            0x56 => self.exec_bit(bus, 2, Ind8(Reg16::HL), 2, 12),
+            0x57 => self.exec_bit(bus, 2, Reg8::A, 2, 8),
+            0x58 => self.exec_bit(bus, 3, Reg8::B, 2, 8),
+            0x59 => self.exec_bit(bus, 3, Reg8::C, 2, 8),
+            0x5A => self.exec_bit(bus, 3, Reg8::D, 2, 8),
+            0x5B => self.exec_bit(bus, 3, Reg8::E, 2, 8),
+            0x5C => self.exec_bit(bus, 3, Reg8::H, 2, 8),
+            0x5D => self.exec_bit(bus, 3, Reg8::L, 2, 8),
+            0x5E => self.exec_bit(bus, 3, Ind8(Reg16::HL), 2, 12),
+            0x5F => self.exec_bit(bus, 3, Reg8::A, 2, 8),
+
+            0x60 => self.exec_bit(bus, 4, Reg8::B, 2, 8),
+            0x61 => self.exec_bit(bus, 4, Reg8::C, 2, 8),
+            0x62 => self.exec_bit(bus, 4, Reg8::D, 2, 8),
+            0x63 => self.exec_bit(bus, 4, Reg8::E, 2, 8),
+            0x64 => self.exec_bit(bus, 4, Reg8::H, 2, 8),
+            0x65 => self.exec_bit(bus, 4, Reg8::L, 2, 8),
+            0x66 => self.exec_bit(bus, 4, Ind8(Reg16::HL), 2, 12),
+            0x67 => self.exec_bit(bus, 4, Reg8::A, 2, 8),
+            0x68 => self.exec_bit(bus, 5, Reg8::B, 2, 8),
+            0x69 => self.exec_bit(bus, 5, Reg8::C, 2, 8),
+            0x6A => self.exec_bit(bus, 5, Reg8::D, 2, 8),
+            0x6B => self.exec_bit(bus, 5, Reg8::E, 2, 8),
+            0x6C => self.exec_bit(bus, 5, Reg8::H, 2, 8),
+            0x6D => self.exec_bit(bus, 5, Reg8::L, 2, 8),
+            0x6E => self.exec_bit(bus, 5, Ind8(Reg16::HL), 2, 12),
+            0x6F => self.exec_bit(bus, 5, Reg8::A, 2, 8),
+
+            0x70 => self.exec_bit(bus, 6, Reg8::B, 2, 8),
+            0x71 => self.exec_bit(bus, 6, Reg8::C, 2, 8),
+            0x72 => self.exec_bit(bus, 6, Reg8::D, 2, 8),
+            0x73 => self.exec_bit(bus, 6, Reg8::E, 2, 8),
+            0x74 => self.exec_bit(bus, 6, Reg8::H, 2, 8),
+            0x75 => self.exec_bit(bus, 6, Reg8::L, 2, 8),
+            0x76 => self.exec_bit(bus, 6, Ind8(Reg16::HL), 2, 12),
+            0x77 => self.exec_bit(bus, 6, Reg8::A, 2, 8),
+            0x78 => self.exec_bit(bus, 7, Reg8::B, 2, 8),
+            0x79 => self.exec_bit(bus, 7, Reg8::C, 2, 8),
+            0x7A => self.exec_bit(bus, 7, Reg8::D, 2, 8),
+            0x7B => self.exec_bit(bus, 7, Reg8::E, 2, 8),
+            0x7C => self.exec_bit(bus, 7, Reg8::H, 2, 8),
+            0x7D => self.exec_bit(bus, 7, Reg8::L, 2, 8),
+            0x7E => self.exec_bit(bus, 7, Ind8(Reg16::HL), 2, 12),
+            0x7F => self.exec_bit(bus, 7, Reg8::A, 2, 8),
+
+            0x80 => self.exec_res(bus, 0, Reg8::B, 2, 8),
+            0x81 => self.exec_res(bus, 0, Reg8::C, 2, 8),
+            0x82 => self.exec_res(bus, 0, Reg8::D, 2, 8),
+            0x83 => self.exec_res(bus, 0, Reg8::E, 2, 8),
+            0x84 => self.exec_res(bus, 0, Reg8::H, 2, 8),
+            0x85 => self.exec_res(bus, 0, Reg8::L, 2, 8),
+            0x86 => self.exec_res(bus, 0, Ind8(Reg16::HL), 2, 15),
+            0x87 => self.exec_res(bus, 0, Reg8::A, 2, 8),
+            0x88 => self.exec_res(bus, 1, Reg8::B, 2, 8),
+            0x89 => self.exec_res(bus, 1, Reg8::C, 2, 8),
+            0x8A => self.exec_res(bus, 1, Reg8::D, 2, 8),
+            0x8B => self.exec_res(bus, 1, Reg8::E, 2, 8),
+            0x8C => self.exec_res(bus, 1, Reg8::H, 2, 8),
+            0x8D => self.exec_res(bus, 1, Reg8::L, 2, 8),
+            0x8E => self.exec_res(bus, 1, Ind8(Reg16::HL), 2, 15),
+            0x8F => self.exec_res(bus, 1, Reg8::A, 2, 8),
+
+            0x90 => self.exec_res(bus, 2, Reg8::B, 2, 8),
+            0x91 => self.exec_res(bus, 2, Reg8::C, 2, 8),
+            0x92 => self.exec_res(bus, 2, Reg8::D, 2, 8),
+            0x93 => self.exec_res(bus, 2, Reg8::E, 2, 8),
+            0x94 => self.exec_res(bus, 2, Reg8::H, 2, 8),
+            0x95 => self.exec_res(bus, 2, Reg8::L, 2, 8),
+            0x96 => self.exec_res(bus, 2, Ind8(Reg16::HL), 2, 15),
+            0x97 => self.exec_res(bus, 2, Reg8::A, 2, 8),
+            0x98 => self.exec_res(bus, 3, Reg8::B, 2, 8),
+            0x99 => self.exec_res(bus, 3, Reg8::C, 2, 8),
+            0x9A => self.exec_res(bus, 3, Reg8::D, 2, 8),
+            0x9B => self.exec_res(bus, 3, Reg8::E, 2, 8),
+            0x9C => self.exec_res(bus, 3, Reg8::H, 2, 8),
+            0x9D => self.exec_res(bus, 3, Reg8::L, 2, 8),
+            0x9E => self.exec_res(bus, 3, Ind8(Reg16::HL), 2, 15),
+            0x9F => self.exec_res(bus, 3, Reg8::A, 2, 8),
+
+            0xA0 => self.exec_res(bus, 4, Reg8::B, 2, 8),
+            0xA1 => self.exec_res(bus, 4, Reg8::C, 2, 8),
+            0xA2 => self.exec_res(bus, 4, Reg8::D, 2, 8),
+            0xA3 => self.exec_res(bus, 4, Reg8::E, 2, 8),
+            0xA4 => self.exec_res(bus, 4, Reg8::H, 2, 8),
+            0xA5 => self.exec_res(bus, 4, Reg8::L, 2, 8),
+            0xA6 => self.exec_res(bus, 4, Ind8(Reg16::HL), 2, 15),
+            0xA7 => self.exec_res(bus, 4, Reg8::A, 2, 8),
+            0xA8 => self.exec_res(bus, 5, Reg8::B, 2, 8),
+            0xA9 => self.exec_res(bus, 5, Reg8::C, 2, 8),
+            0xAA => self.exec_res(bus, 5, Reg8::D, 2, 8),
+            0xAB => self.exec_res(bus, 5, Reg8::E, 2, 8),
+            0xAC => self.exec_res(bus, 5, Reg8::H, 2, 8),
+            0xAD => self.exec_res(bus, 5, Reg8::L, 2, 8),
+            0xAE => self.exec_res(bus, 5, Ind8(Reg16::HL), 2, 15),
+            0xAF => self.exec_res(bus, 5, Reg8::A, 2, 8),
+
+            0xB0 => self.exec_res(bus, 6, Reg8::B, 2, 8),
+            0xB1 => self.exec_res(bus, 6, Reg8::C, 2, 8),
+            0xB2 => self.exec_res(bus, 6, Reg8::D, 2, 8),
+            0xB3 => self.exec_res(bus, 6, Reg8::E, 2, 8),
+            0xB4 => self.exec_res(bus, 6, Reg8::H, 2, 8),
+            0xB5 => self.exec_res(bus, 6, Reg8::L, 2, 8),
+            0xB6 => self.exec_res(bus, 6, Ind8(Reg16::HL), 2, 15),
+            0xB7 => self.exec_res(bus, 6, Reg8::A, 2, 8),
+            0xB8 => self.exec_res(bus, 7, Reg8::B, 2, 8),
+            0xB9 => self.exec_res(bus, 7, Reg8::C, 2, 8),
+            0xBA => self.exec_res(bus, 7, Reg8::D, 2, 8),
+            0xBB => self.exec_res(bus, 7, Reg8::E, 2, 8),
+            0xBC => self.exec_res(bus, 7, Reg8::H, 2, 8),
+            0xBD => self.exec_res(bus, 7, Reg8::L, 2, 8),
+            0xBE => self.exec_res(bus, 7, Ind8(Reg16::HL), 2, 15),
+            0xBF => self.exec_res(bus, 7, Reg8::A, 2, 8),
+
+            0xC0 => self.exec_set(bus, 0, Reg8::B, 2, 8),
+            0xC1 => self.exec_set(bus, 0, Reg8::C, 2, 8),
+            0xC2 => self.exec_set(bus, 0, Reg8::D, 2, 8),
+            0xC3 => self.exec_set(bus, 0, Reg8::E, 2, 8),
+            0xC4 => self.exec_set(bus, 0, Reg8::H, 2, 8),
+            0xC5 => self.exec_set(bus, 0, Reg8::L, 2, 8),
+            0xC6 => self.exec_set(bus, 0, Ind8(Reg16::HL), 2, 15),
+            0xC7 => self.exec_set(bus, 0, Reg8::A, 2, 8),
+            0xC8 => self.exec_set(bus, 1, Reg8::B, 2, 8),
+            0xC9 => self.exec_set(bus, 1, Reg8::C, 2, 8),
+            0xCA => self.exec_set(bus, 1, Reg8::D, 2, 8),
+            0xCB => self.exec_set(bus, 1, Reg8::E, 2, 8),
+            0xCC => self.exec_set(bus, 1, Reg8::H, 2, 8),
+            0xCD => self.exec_set(bus, 1, Reg8::L, 2, 8),
+            0xCE => self.exec_set(bus, 1, Ind8(Reg16::HL), 2, 15),
+            0xCF => self.exec_set(bus, 1, Reg8::A, 2, 8),
+
+            0xD0 => self.exec_set(bus, 2, Reg8::B, 2, 8),
+            0xD1 => self.exec_set(bus, 2, Reg8::C, 2, 8),
+            0xD2 => self.exec_set(bus, 2, Reg8::D, 2, 8),
+            0xD3 => self.exec_set(bus, 2, Reg8::E, 2, 8),
+            0xD4 => self.exec_set(bus, 2, Reg8::H, 2, 8),
+            0xD5 => self.exec_set(bus, 2, Reg8::L, 2, 8),
+            0xD6 => self.exec_set(bus, 2, Ind8(Reg16::HL), 2, 15),
+            0xD7 => self.exec_set(bus, 2, Reg8::A, 2, 8),
+            0xD8 => self.exec_set(bus, 3, Reg8::B, 2, 8),
+            0xD9 => self.exec_set(bus, 3, Reg8::C, 2, 8),
+            0xDA => self.exec_set(bus, 3, Reg8::D, 2, 8),
+            0xDB => self.exec_set(bus, 3, Reg8::E, 2, 8),
+            0xDC => self.exec_set(bus, 3, Reg8::H, 2, 8),
+            0xDD => self.exec_set(bus, 3, Reg8::L, 2, 8),
+            0xDE => self.exec_set(bus, 3, Ind8(Reg16::HL), 2, 15),
+            0xDF => self.exec_set(bus, 3, Reg8::A, 2, 8),
+
+            0xE0 => self.exec_set(bus, 4, Reg8::B, 2, 8),
+            0xE1 => self.exec_set(bus, 4, Reg8::C, 2, 8),
+            0xE2 => self.exec_set(bus, 4, Reg8::D, 2, 8),
+            0xE3 => self.exec_set(bus, 4, Reg8::E, 2, 8),
+            0xE4 => self.exec_set(bus, 4, Reg8::H, 2, 8),
+            0xE5 => self.exec_set(bus, 4, Reg8::L, 2, 8),
+            0xE6 => self.exec_set(bus, 4, Ind8(Reg16::HL), 2, 15),
+            0xE7 => self.exec_set(bus, 4, Reg8::A, 2, 8),
+            0xE8 => self.exec_set(bus, 5, Reg8::B, 2, 8),
+            0xE9 => self.exec_set(bus, 5, Reg8::C, 2, 8),
+            0xEA => self.exec_set(bus, 5, Reg8::D, 2, 8),
+            0xEB => self.exec_set(bus, 5, Reg8::E, 2, 8),
+            0xEC => self.exec_set(bus, 5, Reg8::H, 2, 8),
+            0xED => self.exec_set(bus, 5, Reg8::L, 2, 8),
+            0xEE => self.exec_set(bus, 5, Ind8(Reg16::HL), 2, 15),
+            0xEF => self.exec_set(bus, 5, Reg8::A, 2, 8),
+
+            0xF0 => self.exec_set(bus, 6, Reg8::B, 2, 8),
+            0xF1 => self.exec_set(bus, 6, Reg8::C, 2, 8),
+            0xF2 => self.exec_set(bus, 6, Reg8::D, 2, 8),
+            0xF3 => self.exec_set(bus, 6, Reg8::E, 2, 8),
+            0xF4 => self.exec_set(bus, 6, Reg8::H, 2, 8),
+            0xF5 => self.exec_set(bus, 6, Reg8::L, 2, 8),
+            0xF6 => self.exec_set(bus, 6, Ind8(Reg16::HL), 2, 15),
+            0xF7 => self.exec_set(bus, 6, Reg8::A, 2, 8),
+            0xF8 => self.exec_set(bus, 7, Reg8::B, 2, 8),
+            0xF9 => self.exec_set(bus, 7, Reg8::C, 2, 8),
+            0xFA => self.exec_set(bus, 7, Reg8::D, 2, 8),
+            0xFB => self.exec_set(bus, 7, Reg8::E, 2, 8),
+            0xFC => self.exec_set(bus, 7, Reg8::H, 2, 8),
+            0xFD => self.exec_set(bus, 7, Reg8::L, 2, 8),
+            0xFE => self.exec_set(bus, 7, Ind8(Reg16::HL), 2, 15),
+            0xFF => self.exec_set(bus, 7, Reg8::A, 2, 8),
+
+        }
+    }
+
+    fn decode_indexed(&mut self, bus: &mut impl Bus, idx: Reg16) -> Result<(), ExecError> {
+        let opcode = bus.mem_read(self.regs.pc().wrapping_add(1));
+        // The opcode after the prefix takes its own M1 cycle, unless it is another prefix that
+        // is fetched again by the next instruction. DDCB and FDCB fetch their last byte with a
+        // regular read.
+        if !matches!(opcode, 0xDD | 0xFD) {
+            self.regs.inc_r();
+        }
+        let mem = Idx8::with_offset(idx, 2);
+        let (idx_h, idx_l) = match idx {
+            Reg16::IX => (Reg8::IXH, Reg8::IXL),
+            _ => (Reg8::IYH, Reg8::IYL),
+        };
+        match opcode {
+            0x09 => self.exec_add16(bus, idx, Reg16::BC, 2, 15),
+            0x19 => self.exec_add16(bus, idx, Reg16::DE, 2, 15),
+            0x21 => self.exec_ld(bus, idx, Imm16::with_offset(2), 4, 14),
+            0x22 => self.exec_ld(bus, Ind16(Imm16::with_offset(2)), idx, 4, 20),
+            0x23 => self.exec_inc16(bus, idx, 2, 10),
+            0x24 => self.exec_inc8(bus, idx_h, 2, 8),
+            0x25 => self.exec_dec8(bus, idx_h, 2, 8),
+            0x26 => self.exec_ld(bus, idx_h, Imm8::with_offset(2), 3, 11),
+            0x29 => self.exec_add16(bus, idx, idx, 2, 15),
+            0x2A => self.exec_ld(bus, idx, Ind16(Imm16::with_offset(2)), 4, 20),
+            0x2B => self.exec_dec16(bus, idx, 2, 10),
+            0x2C => self.exec_inc8(bus, idx_l, 2, 8),
+            0x2D => self.exec_dec8(bus, idx_l, 2, 8),
+            0x2E => self.exec_ld(bus, idx_l, Imm8::with_offset(2), 3, 11),
+            0x34 => self.exec_inc8(bus, mem, 3, 23),
+            0x35 => self.exec_dec8(bus, mem, 3, 23),
+            0x36 => self.exec_ld(bus, mem, Imm8::with_offset(3), 4, 19),
+            0x39 => self.exec_add16(bus, idx, Reg16::SP, 2, 15),
+            0x44 => self.exec_ld(bus, Reg8::B, idx_h, 2, 8),
+            0x45 => self.exec_ld(bus, Reg8::B, idx_l, 2, 8),
+            0x46 => self.exec_ld(bus, Reg8::B, mem, 3, 19),
+            0x4C => self.exec_ld(bus, Reg8::C, idx_h, 2, 8),
+            0x4D => self.exec_ld(bus, Reg8::C, idx_l, 2, 8),
+            0x4E => self.exec_ld(bus, Reg8::C, mem, 3, 19),
+            0x54 => self.exec_ld(bus, Reg8::D, idx_h, 2, 8),
+            0x55 => self.exec_ld(bus, Reg8::D, idx_l, 2, 8),
+            0x56 => self.exec_ld(bus, Reg8::D, mem, 3, 19),
+            0x5C => self.exec_ld(bus, Reg8::E, idx_h, 2, 8),
+            0x5D => self.exec_ld(bus, Reg8::E, idx_l, 2, 8),
+            0x5E => self.exec_ld(bus, Reg8::E, mem, 3, 19),
+            0x60 => self.exec_ld(bus, idx_h, Reg8::B, 2, 8),
+            0x61 => self.exec_ld(bus, idx_h, Reg8::C, 2, 8),
+            0x62 => self.exec_ld(bus, idx_h, Reg8::D, 2, 8),
+            0x63 => self.exec_ld(bus, idx_h, Reg8::E, 2, 8),
+            0x64 => self.exec_ld(bus, idx_h, idx_h, 2, 8),
+            0x65 => self.exec_ld(bus, idx_h, idx_l, 2, 8),
+            0x66 => self.exec_ld(bus, Reg8::H, mem, 3, 19),
+            0x67 => self.exec_ld(bus, idx_h, Reg8::A, 2, 8),
+            0x68 => self.exec_ld(bus, idx_l, Reg8::B, 2, 8),
+            0x69 => self.exec_ld(bus, idx_l, Reg8::C, 2, 8),
+            0x6A => self.exec_ld(bus, idx_l, Reg8::D, 2, 8),
+            0x6B => self.exec_ld(bus, idx_l, Reg8::E, 2, 8),
+            0x6C => self.exec_ld(bus, idx_l, idx_h, 2, 8),
+            0x6D => self.exec_ld(bus, idx_l, idx_l, 2, 8),
+            0x6E => self.exec_ld(bus, Reg8::L, mem, 3, 19),
+            0x6F => self.exec_ld(bus, idx_l, Reg8::A, 2, 8),
+            0x70 => self.exec_ld(bus, mem, Reg8::B, 3, 19),
+            0x71 => self.exec_ld(bus, mem, Reg8::C, 3, 19),
+            0x72 => self.exec_ld(bus, mem, Reg8::D, 3, 19),
+            0x73 => self.exec_ld(bus, mem, Reg8::E, 3, 19),
+            0x74 => self.exec_ld(bus, mem, Reg8::H, 3, 19),
+            0x75 => self.exec_ld(bus, mem, Reg8::L, 3, 19),
+            0x77 => self.exec_ld(bus, mem, Reg8::A, 3, 19),
+            0x7C => self.exec_ld(bus, Reg8::A, idx_h, 2, 8),
+            0x7D => self.exec_ld(bus, Reg8::A, idx_l, 2, 8),
+            0x7E => self.exec_ld(bus, Reg8::A, mem, 3, 19),
+            0x84 => self.exec_add8(bus, Reg8::A, idx_h, false, 2, 8),
+            0x85 => self.exec_add8(bus, Reg8::A, idx_l, false, 2, 8),
+            0x86 => self.exec_add8(bus, Reg8::A, mem, false, 3, 19),
+            0x8C => self.exec_add8(bus, Reg8::A, idx_h, true, 2, 8),
+            0x8D => self.exec_add8(bus, Reg8::A, idx_l, true, 2, 8),
+            0x8E => self.exec_add8(bus, Reg8::A, mem, true, 3, 19),
+            0x94 => self.exec_sub8(bus, Reg8::A, idx_h, false, 2, 8),
+            0x95 => self.exec_sub8(bus, Reg8::A, idx_l, false, 2, 8),
+            0x96 => self.exec_sub8(bus, Reg8::A, mem, false, 3, 19),
+            0x9C => self.exec_sub8(bus, Reg8::A, idx_h, true, 2, 8),
+            0x9D => self.exec_sub8(bus, Reg8::A, idx_l, true, 2, 8),
+            0x9E => self.exec_sub8(bus, Reg8::A, mem, true, 3, 19),
+            0xA4 => self.exec_and(bus, Reg8::A, idx_h, 2, 8),
+            0xA5 => self.exec_and(bus, Reg8::A, idx_l, 2, 8),
+            0xA6 => self.exec_and(bus, Reg8::A, mem, 3, 19),
+            0xAC => self.exec_xor(bus, Reg8::A, idx_h, 2, 8),
+            0xAD => self.exec_xor(bus, Reg8::A, idx_l, 2, 8),
+            0xAE => self.exec_xor(bus, Reg8::A, mem, 3, 19),
+            0xB4 => self.exec_or(bus, Reg8::A, idx_h, 2, 8),
+            0xB5 => self.exec_or(bus, Reg8::A, idx_l, 2, 8),
+            0xB6 => self.exec_or(bus, Reg8::A, mem, 3, 19),
+            0xBC => self.exec_cp(bus, Reg8::A, idx_h, 2, 8),
+            0xBD => self.exec_cp(bus, Reg8::A, idx_l, 2, 8),
+            0xBE => self.exec_cp(bus, Reg8::A, mem, 3, 19),
+            0xCB => self.decode_indexed_bits(bus, idx, bus.mem_read(self.regs.pc().wrapping_add(3))),
+            0xE1 => self.exec_pop(bus, idx, 2, 14),
+            0xE3 => self.exec_ex(bus, Ind16(Reg16::SP), idx, 2, 23),
+            0xE5 => self.exec_push(bus, idx, 2, 15),
+            0xE9 => self.exec_jp(bus, flag::Any, idx, 2, 8),
+            0xF9 => self.exec_ld(bus, Reg16::SP, idx, 2, 10),
+
+            // Only the last prefix of a chain applies, the ones before it run as NOPs. INT is not
+            // accepted in the middle of the chain.
+            0xDD | 0xFD => {
+                self.int_delay = true;
+                self.exec_nop(1, 4)
+            },
+            _ => {
+                // The prefix does not affect other instructions, that run as usual after it
+                self.regs.inc_pc(1);
+                self.cycles += 4;
+                return self.decode(bus, opcode);
+            },
+        }
+        Ok(())
+    }
+
+    fn decode_indexed_bits(&mut self, bus: &mut impl Bus, idx: Reg16, opcode: u8) {
+        let mem = Idx8::with_offset(idx, 2);
+        match opcode {
+            0x00 => self.exec_rlc(bus, WithCopy(mem, Reg8::B), 4, 23),
+            0x01 => self.exec_rlc(bus, WithCopy(mem, Reg8::C), 4, 23),
+            0x02 => self.exec_rlc(bus, WithCopy(mem, Reg8::D), 4, 23),
+            0x03 => self.exec_rlc(bus, WithCopy(mem, Reg8::E), 4, 23),
+            0x04 => self.exec_rlc(bus, WithCopy(mem, Reg8::H), 4, 23),
+            0x05 => self.exec_rlc(bus, WithCopy(mem, Reg8::L), 4, 23),
+            0x06 => self.exec_rlc(bus, mem, 4, 23),
+            0x07 => self.exec_rlc(bus, WithCopy(mem, Reg8::A), 4, 23),
+            0x08 => self.exec_rrc(bus, WithCopy(mem, Reg8::B), 4, 23),
+            0x09 => self.exec_rrc(bus, WithCopy(mem, Reg8::C), 4, 23),
+            0x0A => self.exec_rrc(bus, WithCopy(mem, Reg8::D), 4, 23),
+            0x0B => self.exec_rrc(bus, WithCopy(mem, Reg8::E), 4, 23),
+            0x0C => self.exec_rrc(bus, WithCopy(mem, Reg8::H), 4, 23),
+            0x0D => self.exec_rrc(bus, WithCopy(mem, Reg8::L), 4, 23),
+            0x0E => self.exec_rrc(bus, mem, 4, 23),
+            0x0F => self.exec_rrc(bus, WithCopy(mem, Reg8::A), 4, 23),
+
+            0x10 => self.exec_rl(bus, WithCopy(mem, Reg8::B), 4, 23),
+            0x11 => self.exec_rl(bus, WithCopy(mem, Reg8::C), 4, 23),
+            0x12 => self.exec_rl(bus, WithCopy(mem, Reg8::D), 4, 23),
+            0x13 => self.exec_rl(bus, WithCopy(mem, Reg8::E), 4, 23),
+            0x14 => self.exec_rl(bus, WithCopy(mem, Reg8::H), 4, 23),
+            0x15 => self.exec_rl(bus, WithCopy(mem, Reg8::L), 4, 23),
+            0x16 => self.exec_rl(bus, mem, 4, 23),
+            0x17 => self.exec_rl(bus, WithCopy(mem, Reg8::A), 4, 23),
+            0x18 => self.exec_rr(bus, WithCopy(mem, Reg8::B), 4, 23),
+            0x19 => self.exec_rr(bus, WithCopy(mem, Reg8::C), 4, 23),
+            0x1A => self.exec_rr(bus, WithCopy(mem, Reg8::D), 4, 23),
+            0x1B => self.exec_rr(bus, WithCopy(mem, Reg8::E), 4, 23),
+            0x1C => self.exec_rr(bus, WithCopy(mem, Reg8::H), 4, 23),
+            0x1D => self.exec_rr(bus, WithCopy(mem, Reg8::L), 4, 23),
+            0x1E => self.exec_rr(bus, mem, 4, 23),
+            0x1F => self.exec_rr(bus, WithCopy(mem, Reg8::A), 4, 23),
+
+            0x20 => self.exec_sla(bus, WithCopy(mem, Reg8::B), 4, 23),
+            0x21 => self.exec_sla(bus, WithCopy(mem, Reg8::C), 4, 23),
+            0x22 => self.exec_sla(bus, WithCopy(mem, Reg8::D), 4, 23),
+            0x23 => self.exec_sla(bus, WithCopy(mem, Reg8::E), 4, 23),
+            0x24 => self.exec_sla(bus, WithCopy(mem, Reg8::H), 4, 23),
+            0x25 => self.exec_sla(bus, WithCopy(mem, Reg8::L), 4, 23),
+            0x26 => self.exec_sla(bus, mem, 4, 23),
+            0x27 => self.exec_sla(bus, WithCopy(mem, Reg8::A), 4, 23),
+            0x28 => self.exec_sra(bus, WithCopy(mem, Reg8::B), 4, 23),
+            0x29 => self.exec_sra(bus, WithCopy(mem, Reg8::C), 4, 23),
+            0x2A => self.exec_sra(bus, WithCopy(mem, Reg8::D), 4, 23),
+            0x2B => self.exec_sra(bus, WithCopy(mem, Reg8::E), 4, 23),
+            0x2C => self.exec_sra(bus, WithCopy(mem, Reg8::H), 4, 23),
+            0x2D => self.exec_sra(bus, WithCopy(mem, Reg8::L), 4, 23),
+            0x2E => self.exec_sra(bus, mem, 4, 23),
+            0x2F => self.exec_sra(bus, WithCopy(mem, Reg8::A), 4, 23),
+
+            0x30 => self.exec_sll(bus, WithCopy(mem, Reg8::B), 4, 23),
+            0x31 => self.exec_sll(bus, WithCopy(mem, Reg8::C), 4, 23),
+            0x32 => self.exec_sll(bus, WithCopy(mem, Reg8::D), 4, 23),
+            0x33 => self.exec_sll(bus, WithCopy(mem, Reg8::E), 4, 23),
+            0x34 => self.exec_sll(bus, WithCopy(mem, Reg8::H), 4, 23),
+            0x35 => self.exec_sll(bus, WithCopy(mem, Reg8::L), 4, 23),
+            0x36 => self.exec_sll(bus, mem, 4, 23),
+            0x37 => self.exec_sll(bus, WithCopy(mem, Reg8::A), 4, 23),
+            0x38 => self.exec_srl(bus, WithCopy(mem, Reg8::B), 4, 23),
+            0x39 => self.exec_srl(bus, WithCopy(mem, Reg8::C), 4, 23),
+            0x3A => self.exec_srl(bus, WithCopy(mem, Reg8::D), 4, 23),
+            0x3B => self.exec_srl(bus, WithCopy(mem, Reg8::E), 4, 23),
+            0x3C => self.exec_srl(bus, WithCopy(mem, Reg8::H), 4, 23),
+            0x3D => self.exec_srl(bus, WithCopy(mem, Reg8::L), 4, 23),
+            0x3E => self.exec_srl(bus, mem, 4, 23),
+            0x3F => self.exec_srl(bus, WithCopy(mem, Reg8::A), 4, 23),
+
+            0x40 => self.exec_bit_indexed(bus, 0, mem, 4, 20),
+            0x41 => self.exec_bit_indexed(bus, 0, mem, 4, 20),
+            0x42 => self.exec_bit_indexed(bus, 0, mem, 4, 20),
+            0x43 => self.exec_bit_indexed(bus, 0, mem, 4, 20),
+            0x44 => self.exec_bit_indexed(bus, 0, mem, 4, 20),
+            0x45 => self.exec_bit_indexed(bus, 0, mem, 4, 20),
+            0x46 => self.exec_bit_indexed(bus, 0, mem, 4, 20),
+            0x47 => self.exec_bit_indexed(bus, 0, mem, 4, 20),
+            0x48 => self.exec_bit_indexed(bus, 1, mem, 4, 20),
+            0x49 => self.exec_bit_indexed(bus, 1, mem, 4, 20),
+            0x4A => self.exec_bit_indexed(bus, 1, mem, 4, 20),
+            0x4B => self.exec_bit_indexed(bus, 1, mem, 4, 20),
+            0x4C => self.exec_bit_indexed(bus, 1, mem, 4, 20),
+            0x4D => self.exec_bit_indexed(bus, 1, mem, 4, 20),
+            0x4E => self.exec_bit_indexed(bus, 1, mem, 4, 20),
+            0x4F => self.exec_bit_indexed(bus, 1, mem, 4, 20),
+
+            0x50 => self.exec_bit_indexed(bus, 2, mem, 4, 20),
+            0x51 => self.exec_bit_indexed(bus, 2, mem, 4, 20),
+            0x52 => self.exec_bit_indexed(bus, 2, mem, 4, 20),
+            0x53 => self.exec_bit_indexed(bus, 2, mem, 4, 20),
+            0x54 => self.exec_bit_indexed(bus, 2, mem, 4, 20),
+            0x55 => self.exec_bit_indexed(bus, 2, mem, 4, 20),
+            0x56 => self.exec_bit_indexed(bus, 2, mem, 4, 20),
+            0x57 => self.exec_bit_indexed(bus, 2, mem, 4, 20),
+            0x58 => self.exec_bit_indexed(bus, 3, mem, 4, 20),
+            0x59 => self.exec_bit_indexed(bus, 3, mem, 4, 20),
+            0x5A => self.exec_bit_indexed(bus, 3, mem, 4, 20),
+            0x5B => self.exec_bit_indexed(bus, 3, mem, 4, 20),
+            0x5C => self.exec_bit_indexed(bus, 3, mem, 4, 20),
+            0x5D => self.exec_bit_indexed(bus, 3, mem, 4, 20),
+            0x5E => self.exec_bit_indexed(bus, 3, mem, 4, 20),
+            0x5F => self.exec_bit_indexed(bus, 3, mem, 4, 20),
+
+            0x60 => self.exec_bit_indexed(bus, 4, mem, 4, 20),
+            0x61 => self.exec_bit_indexed(bus, 4, mem, 4, 20),
+            0x62 => self.exec_bit_indexed(bus, 4, mem, 4, 20),
+            0x63 => self.exec_bit_indexed(bus, 4, mem, 4, 20),
+            0x64 => self.exec_bit_indexed(bus, 4, mem, 4, 20),
+            0x65 => self.exec_bit_indexed(bus, 4, mem, 4, 20),
+            0x66 => self.exec_bit_indexed(bus, 4, mem, 4, 20),
+            0x67 => self.exec_bit_indexed(bus, 4, mem, 4, 20),
+            0x68 => self.exec_bit_indexed(bus, 5, mem, 4, 20),
+            0x69 => self.exec_bit_indexed(bus, 5, mem, 4, 20),
+            0x6A => self.exec_bit_indexed(bus, 5, mem, 4, 20),
+            0x6B => self.exec_bit_indexed(bus, 5, mem, 4, 20),
+            0x6C => self.exec_bit_indexed(bus, 5, mem, 4, 20),
+            0x6D => self.exec_bit_indexed(bus, 5, mem, 4, 20),
+            0x6E => self.exec_bit_indexed(bus, 5, mem, 4, 20),
+            0x6F => self.exec_bit_indexed(bus, 5, mem, 4, 20),
+
+            0x70 => self.exec_bit_indexed(bus, 6, mem, 4, 20),
+            0x71 => self.exec_bit_indexed(bus, 6, mem, 4, 20),
+            0x72 => self.exec_bit_indexed(bus, 6, mem, 4, 20),
+            0x73 => self.exec_bit_indexed(bus, 6, mem, 4, 20),
+            0x74 => self.exec_bit_indexed(bus, 6, mem, 4, 20),
+            0x75 => self.exec_bit_indexed(bus, 6, mem, 4, 20),
+            0x76 => self.exec_bit_indexed(bus, 6, mem, 4, 20),
+            0x77 => self.exec_bit_indexed(bus, 6, mem, 4, 20),
+            0x78 => self.exec_bit_indexed(bus, 7, mem, 4, 20),
+            0x79 => self.exec_bit_indexed(bus, 7, mem, 4, 20),
+            0x7A => self.exec_bit_indexed(bus, 7, mem, 4, 20),
+            0x7B => self.exec_bit_indexed(bus, 7, mem, 4, 20),
+            0x7C => self.exec_bit_indexed(bus, 7, mem, 4, 20),
+            0x7D => self.exec_bit_indexed(bus, 7, mem, 4, 20),
+            0x7E => self.exec_bit_indexed(bus, 7, mem, 4, 20),
+            0x7F => self.exec_bit_indexed(bus, 7, mem, 4, 20),
+
+            0x80 => self.exec_res(bus, 0, WithCopy(mem, Reg8::B), 4, 23),
+            0x81 => self.exec_res(bus, 0, WithCopy(mem, Reg8::C), 4, 23),
+            0x82 => self.exec_res(bus, 0, WithCopy(mem, Reg8::D), 4, 23),
+            0x83 => self.exec_res(bus, 0, WithCopy(mem, Reg8::E), 4, 23),
+            0x84 => self.exec_res(bus, 0, WithCopy(mem, Reg8::H), 4, 23),
+            0x85 => self.exec_res(bus, 0, WithCopy(mem, Reg8::L), 4, 23),
+            0x86 => self.exec_res(bus, 0, mem, 4, 23),
+            0x87 => self.exec_res(bus, 0, WithCopy(mem, Reg8::A), 4, 23),
+            0x88 => self.exec_res(bus, 1, WithCopy(mem, Reg8::B), 4, 23),
+            0x89 => self.exec_res(bus, 1, WithCopy(mem, Reg8::C), 4, 23),
+            0x8A => self.exec_res(bus, 1, WithCopy(mem, Reg8::D), 4, 23),
+            0x8B => self.exec_res(bus, 1, WithCopy(mem, Reg8::E), 4, 23),
+            0x8C => self.exec_res(bus, 1, WithCopy(mem, Reg8::H), 4, 23),
+            0x8D => self.exec_res(bus, 1, WithCopy(mem, Reg8::L), 4, 23),
+            0x8E => self.exec_res(bus, 1, mem, 4, 23),
+            0x8F => self.exec_res(bus, 1, WithCopy(mem, Reg8::A), 4, 23),
+
+            0x90 => self.exec_res(bus, 2, WithCopy(mem, Reg8::B), 4, 23),
+            0x91 => self.exec_res(bus, 2, WithCopy(mem, Reg8::C), 4, 23),
+            0x92 => self.exec_res(bus, 2, WithCopy(mem, Reg8::D), 4, 23),
+            0x93 => self.exec_res(bus, 2, WithCopy(mem, Reg8::E), 4, 23),
+            0x94 => self.exec_res(bus, 2, WithCopy(mem, Reg8::H), 4, 23),
+            0x95 => self.exec_res(bus, 2, WithCopy(mem, Reg8::L), 4, 23),
+            0x96 => self.exec_res(bus, 2, mem, 4, 23),
+            0x97 => self.exec_res(bus, 2, WithCopy(mem, Reg8::A), 4, 23),
+            0x98 => self.exec_res(bus, 3, WithCopy(mem, Reg8::B), 4, 23),
+            0x99 => self.exec_res(bus, 3, WithCopy(mem, Reg8::C), 4, 23),
+            0x9A => self.exec_res(bus, 3, WithCopy(mem, Reg8::D), 4, 23),
+            0x9B => self.exec_res(bus, 3, WithCopy(mem, Reg8::E), 4, 23),
+            0x9C => self.exec_res(bus, 3, WithCopy(mem, Reg8::H), 4, 23),
+            0x9D => self.exec_res(bus, 3, WithCopy(mem, Reg8::L), 4, 23),
+            0x9E => self.exec_res(bus, 3, mem, 4, 23),
+            0x9F => self.exec_res(bus, 3, WithCopy(mem, Reg8::A), 4, 23),
+
+            0xA0 => self.exec_res(bus, 4, WithCopy(mem, Reg8::B), 4, 23),
+            0xA1 => self.exec_res(bus, 4, WithCopy(mem, Reg8::C), 4, 23),
+            0xA2 => self.exec_res(bus, 4, WithCopy(mem, Reg8::D), 4, 23),
+            0xA3 => self.exec_res(bus, 4, WithCopy(mem, Reg8::E), 4, 23),
+            0xA4 => self.exec_res(bus, 4, WithCopy(mem, Reg8::H), 4, 23),
+            0xA5 => self.exec_res(bus, 4, WithCopy(mem, Reg8::L), 4, 23),
+            0xA6 => self.exec_res(bus, 4, mem, 4, 23),
+            0xA7 => self.exec_res(bus, 4, WithCopy(mem, Reg8::A), 4, 23),
+            0xA8 => self.exec_res(bus, 5, WithCopy(mem, Reg8::B), 4, 23),
+            0xA9 => self.exec_res(bus, 5, WithCopy(mem, Reg8::C), 4, 23),
+            0xAA => self.exec_res(bus, 5, WithCopy(mem, Reg8::D), 4, 23),
+            0xAB => self.exec_res(bus, 5, WithCopy(mem, Reg8::E), 4, 23),
+            0xAC => self.exec_res(bus, 5, WithCopy(mem, Reg8::H), 4, 23),
+            0xAD => self.exec_res(bus, 5, WithCopy(mem, Reg8::L), 4, 23),
+            0xAE => self.exec_res(bus, 5, mem, 4, 23),
+            0xAF => self.exec_res(bus, 5, WithCopy(mem, Reg8::A), 4, 23),
+
+            0xB0 => self.exec_res(bus, 6, WithCopy(mem, Reg8::B), 4, 23),
+            0xB1 => self.exec_res(bus, 6, WithCopy(mem, Reg8::C), 4, 23),
+            0xB2 => self.exec_res(bus, 6, WithCopy(mem, Reg8::D), 4, 23),
+            0xB3 => self.exec_res(bus, 6, WithCopy(mem, Reg8::E), 4, 23),
+            0xB4 => self.exec_res(bus, 6, WithCopy(mem, Reg8::H), 4, 23),
+            0xB5 => self.exec_res(bus, 6, WithCopy(mem, Reg8::L), 4, 23),
+            0xB6 => self.exec_res(bus, 6, mem, 4, 23),
+            0xB7 => self.exec_res(bus, 6, WithCopy(mem, Reg8::A), 4, 23),
+            0xB8 => self.exec_res(bus, 7, WithCopy(mem, Reg8::B), 4, 23),
+            0xB9 => self.exec_res(bus, 7, WithCopy(mem, Reg8::C), 4, 23),
+            0xBA => self.exec_res(bus, 7, WithCopy(mem, Reg8::D), 4, 23),
+            0xBB => self.exec_res(bus, 7, WithCopy(mem, Reg8::E), 4, 23),
+            0xBC => self.exec_res(bus, 7, WithCopy(mem, Reg8::H), 4, 23),
+            0xBD => self.exec_res(bus, 7, WithCopy(mem, Reg8::L), 4, 23),
+            0xBE => self.exec_res(bus, 7, mem, 4, 23),
+            0xBF => self.exec_res(bus, 7, WithCopy(mem, Reg8::A), 4, 23),
+
+            0xC0 => self.exec_set(bus, 0, WithCopy(mem, Reg8::B), 4, 23),
+            0xC1 => self.exec_set(bus, 0, WithCopy(mem, Reg8::C), 4, 23),
+            0xC2 => self.exec_set(bus, 0, WithCopy(mem, Reg8::D), 4, 23),
+            0xC3 => self.exec_set(bus, 0, WithCopy(mem, Reg8::E), 4, 23),
+            0xC4 => self.exec_set(bus, 0, WithCopy(mem, Reg8::H), 4, 23),
+            0xC5 => self.exec_set(bus, 0, WithCopy(mem, Reg8::L), 4, 23),
+            0xC6 => self.exec_set(bus, 0, mem, 4, 23),
+            0xC7 => self.exec_set(bus, 0, WithCopy(mem, Reg8::A), 4, 23),
+            0xC8 => self.exec_set(bus, 1, WithCopy(mem, Reg8::B), 4, 23),
+            0xC9 => self.exec_set(bus, 1, WithCopy(mem, Reg8::C), 4, 23),
+            0xCA => self.exec_set(bus, 1, WithCopy(mem, Reg8::D), 4, 23),
+            0xCB => self.exec_set(bus, 1, WithCopy(mem, Reg8::E), 4, 23),
+            0xCC => self.exec_set(bus, 1, WithCopy(mem, Reg8::H), 4, 23),
+            0xCD => self.exec_set(bus, 1, WithCopy(mem, Reg8::L), 4, 23),
+            0xCE => self.exec_set(bus, 1, mem, 4, 23),
+            0xCF => self.exec_set(bus, 1, WithCopy(mem, Reg8::A), 4, 23),
+
+            0xD0 => self.exec_set(bus, 2, WithCopy(mem, Reg8::B), 4, 23),
+            0xD1 => self.exec_set(bus, 2, WithCopy(mem, Reg8::C), 4, 23),
+            0xD2 => self.exec_set(bus, 2, WithCopy(mem, Reg8::D), 4, 23),
+            0xD3 => self.exec_set(bus, 2, WithCopy(mem, Reg8::E), 4, 23),
+            0xD4 => self.exec_set(bus, 2, WithCopy(mem, Reg8::H), 4, 23),
+            0xD5 => self.exec_set(bus, 2, WithCopy(mem, Reg8::L), 4, 23),
+            0xD6 => self.exec_set(bus, 2, mem, 4, 23),
+            0xD7 => self.exec_set(bus, 2, WithCopy(mem, Reg8::A), 4, 23),
+            0xD8 => self.exec_set(bus, 3, WithCopy(mem, Reg8::B), 4, 23),
+            0xD9 => self.exec_set(bus, 3, WithCopy(mem, Reg8::C), 4, 23),
+            0xDA => self.exec_set(bus, 3, WithCopy(mem, Reg8::D), 4, 23),
+            0xDB => self.exec_set(bus, 3, WithCopy(mem, Reg8::E), 4, 23),
+            0xDC => self.exec_set(bus, 3, WithCopy(mem, Reg8::H), 4, 23),
+            0xDD => self.exec_set(bus, 3, WithCopy(mem, Reg8::L), 4, 23),
+            0xDE => self.exec_set(bus, 3, mem, 4, 23),
+            0xDF => self.exec_set(bus, 3, WithCopy(mem, Reg8::A), 4, 23),
+
+            0xE0 => self.exec_set(bus, 4, WithCopy(mem, Reg8::B), 4, 23),
+            0xE1 => self.exec_set(bus, 4, WithCopy(mem, Reg8::C), 4, 23),
+            0xE2 => self.exec_set(bus, 4, WithCopy(mem, Reg8::D), 4, 23),
+            0xE3 => self.exec_set(bus, 4, WithCopy(mem, Reg8::E), 4, 23),
+            0xE4 => self.exec_set(bus, 4, WithCopy(mem, Reg8::H), 4, 23),
+            0xE5 => self.exec_set(bus, 4, WithCopy(mem, Reg8::L), 4, 23),
+            0xE6 => self.exec_set(bus, 4, mem, 4, 23),
+            0xE7 => self.exec_set(bus, 4, WithCopy(mem, Reg8::A), 4, 23),
+            0xE8 => self.exec_set(bus, 5, WithCopy(mem, Reg8::B), 4, 23),
+            0xE9 => self.exec_set(bus, 5, WithCopy(mem, Reg8::C), 4, 23),
+            0xEA => self.exec_set(bus, 5, WithCopy(mem, Reg8::D), 4, 23),
+            0xEB => self.exec_set(bus, 5, WithCopy(mem, Reg8::E), 4, 23),
+            0xEC => self.exec_set(bus, 5, WithCopy(mem, Reg8::H), 4, 23),
+            0xED => self.exec_set(bus, 5, WithCopy(mem, Reg8::L), 4, 23),
+            0xEE => self.exec_set(bus, 5, mem, 4, 23),
+            0xEF => self.exec_set(bus, 5, WithCopy(mem, Reg8::A), 4, 23),
+
+            0xF0 => self.exec_set(bus, 6, WithCopy(mem, Reg8::B), 4, 23),
+            0xF1 => self.exec_set(bus, 6, WithCopy(mem, Reg8::C), 4, 23),
+            0xF2 => self.exec_set(bus, 6, WithCopy(mem, Reg8::D), 4, 23),
+            0xF3 => self.exec_set(bus, 6, WithCopy(mem, Reg8::E), 4, 23),
+            0xF4 => self.exec_set(bus, 6, WithCopy(mem, Reg8::H), 4, 23),
+            0xF5 => self.exec_set(bus, 6, WithCopy(mem, Reg8::L), 4, 23),
+            0xF6 => self.exec_set(bus, 6, mem, 4, 23),
+            0xF7 => self.exec_set(bus, 6, WithCopy(mem, Reg8::A), 4, 23),
+            0xF8 => self.exec_set(bus, 7, WithCopy(mem, Reg8::B), 4, 23),
+            0xF9 => self.exec_set(bus, 7, WithCopy(mem, Reg8::C), 4, 23),
+            0xFA => self.exec_set(bus, 7, WithCopy(mem, Reg8::D), 4, 23),
+            0xFB => self.exec_set(bus, 7, WithCopy(mem, Reg8::E), 4, 23),
+            0xFC => self.exec_set(bus, 7, WithCopy(mem, Reg8::H), 4, 23),
+            0xFD => self.exec_set(bus, 7, WithCopy(mem, Reg8::L), 4, 23),
+            0xFE => self.exec_set(bus, 7, mem, 4, 23),
+            0xFF => self.exec_set(bus, 7, WithCopy(mem, Reg8::A), 4, 23),
+        }
     }
 
     fn decode_ext(&mut self, bus: &mut impl Bus, opcode: u8) {
+        // The opcode after the prefix takes its own M1 cycle
+        self.regs.inc_r();
         match opcode {
             0x40 => self.exec_in(bus, Some(Reg8::B), Reg8::C, true, 2, 12),
-            0x41 => self.exec_out(bus, Reg8::C, Reg8::B, 2, 12),            
+            0x41 => self.exec_out(bus, Reg8::C, Reg8::B, 2, 12),
+            0x42 => self.exec_sbc16(bus, Reg16::HL, Reg16::BC, 2, 15),
+            0x43 => self.exec_ld(bus, Ind16(Imm16::with_offset(2)), Reg16::BC, 4, 20),
+            0x44 => self.exec_neg(),
+            0x45 => self.exec_retn(bus),
+            0x46 => self.exec_im(0),
+            0x47 => self.exec_ld(bus, Reg8::I, Reg8::A, 2, 9),
             0x48 => self.exec_in(bus, Some(Reg8::C), Reg8::C, true, 2, 12),
             0x49 => self.exec_out(bus, Reg8::C, Reg8::C, 2, 12),
+            0x4A => self.exec_adc16(bus, Reg16::HL, Reg16::BC, 2, 15),
+            0x4B => self.exec_ld(bus, Reg16::BC, Ind16(Imm16::with_offset(2)), 4, 20),
+            0x4C => self.exec_neg(),
+            0x4D => self.exec_retn(bus),
+            0x4E => self.exec_im(0),
+            0x4F => self.exec_ld(bus, Reg8::R, Reg8::A, 2, 9),
             0x50 => self.exec_in(bus, Some(Reg8::D), Reg8::C, true, 2, 12),
             0x51 => self.exec_out(bus, Reg8::C, Reg8::D, 2, 12),
+            0x52 => self.exec_sbc16(bus, Reg16::HL, Reg16::DE, 2, 15),
+            0x53 => self.exec_ld(bus, Ind16(Imm16::with_offset(2)), Reg16::DE, 4, 20),
+            0x54 => self.exec_neg(),
+            0x55 => self.exec_retn(bus),
+            0x56 => self.exec_im(1),
+            0x57 => self.exec_ld_a_ir(bus, Reg8::I),
             0x58 => self.exec_in(bus, Some(Reg8::E), Reg8::C, true, 2, 12),
             0x59 => self.exec_out(bus, Reg8::C, Reg8::E, 2, 12),
+            0x5A => self.exec_adc16(bus, Reg16::HL, Reg16::DE, 2, 15),
+            0x5B => self.exec_ld(bus, Reg16::DE, Ind16(Imm16::with_offset(2)), 4, 20),
+            0x5C => self.exec_neg(),
+            0x5D => self.exec_retn(bus),
+            0x5E => self.exec_im(2),
+            0x5F => self.exec_ld_a_ir(bus, Reg8::R),
             0x60 => self.exec_in(bus, Some(Reg8::H), Reg8::C, true, 2, 12),
             0x61 => self.exec_out(bus, Reg8::C, Reg8::H, 2, 12),
+            0x62 => self.exec_sbc16(bus, Reg16::HL, Reg16::HL, 2, 15),
+            0x63 => self.exec_ld(bus, Ind16(Imm16::with_offset(2)), Reg16::HL, 4, 20),
+            0x64 => self.exec_neg(),
+            0x65 => self.exec_retn(bus),
+            0x66 => self.exec_im(0),
+            0x67 => self.exec_rrd(bus),
             0x68 => self.exec_in(bus, Some(Reg8::L), Reg8::C, true, 2, 12),
             0x69 => self.exec_out(bus, Reg8::C, Reg8::L, 2, 12),
+            0x6A => self.exec_adc16(bus, Reg16::HL, Reg16::HL, 2, 15),
+            0x6B => self.exec_ld(bus, Reg16::HL, Ind16(Imm16::with_offset(2)), 4, 20),
+            0x6C => self.exec_neg(),
+            0x6D => self.exec_retn(bus),
+            0x6E => self.exec_im(0),
+            0x6F => self.exec_rld(bus),
             0x70 => self.exec_in(bus, None, Reg8::C, true, 2, 12),
             0x71 => self.exec_out(bus, Reg8::C, 0, 2, 12),
+            0x72 => self.exec_sbc16(bus, Reg16::HL, Reg16::SP, 2, 15),
+            0x73 => self.exec_ld(bus, Ind16(Imm16::with_offset(2)), Reg16::SP, 4, 20),
+            0x74 => self.exec_neg(),
+            0x75 => self.exec_retn(bus),
+            0x76 => self.exec_im(1),
             0x78 => self.exec_in(bus, Some(Reg8::A), Reg8::C, true, 2, 12),
             0x79 => self.exec_out(bus, Reg8::C, Reg8::A, 2, 12),
-            0xA0 => self.exec_ldi(bus, false),
-            0xB0 => self.exec_ldi(bus, true),    
+            0x7A => self.exec_adc16(bus, Reg16::HL, Reg16::SP, 2, 15),
+            0x7B => self.exec_ld(bus, Reg16::SP, Ind16(Imm16::with_offset(2)), 4, 20),
+            0x7C => self.exec_neg(),
+            0x7D => self.exec_retn(bus),
+            0x7E => self.exec_im(2),
+            0xA0 => self.exec_ldi(bus, false, false),
+            0xA1 => self.exec_cpi(bus, false, false),
+            0xA2 => self.exec_ini(bus, false, false),
+            0xA3 => self.exec_outi(bus, false, false),
+            0xA8 => self.exec_ldi(bus, true, false),
+            0xA9 => self.exec_cpi(bus, true, false),
+            0xAA => self.exec_ini(bus, true, false),
+            0xAB => self.exec_outi(bus, true, false),
+            0xB0 => self.exec_ldi(bus, false, true),
+            0xB1 => self.exec_cpi(bus, false, true),
+            0xB2 => self.exec_ini(bus, false, true),
+            0xB3 => self.exec_outi(bus, false, true),
+            0xB8 => self.exec_ldi(bus, true, true),
+            0xB9 => self.exec_cpi(bus, true, true),
+            0xBA => self.exec_ini(bus, true, true),
+            0xBB => self.exec_outi(bus, true, true),
+            // Undefined opcodes run as NOPs, as in the real CPU
             _ => self.exec_nop(2, 8),
         }
     }
 
+    fn exec_adc16(&mut self, bus: &mut impl Bus, dst: impl DestOp<u16>, src: impl SrcOp<u16>, size: usize, cycles: usize) {
+        let mut ctx = Context::from(bus, &mut self.regs);
+        let a = dst.get(&ctx);
+        let b = src.get(&ctx);
+        let carry = ctx.regs.flag(flag::C) as u16;
+        let c = a.wrapping_add(b).wrapping_add(carry);
+        dst.set(&mut ctx, c);
+
+        let ch = (c >> 8) as u8;
+
+        self.regs.update_flags(
+            (flag::intrinsic_undocumented(ch) &
+            flag::S.on(flag::signed(ch)) &
+            flag::Z.on(c == 0) &
+            flag::H.on((a ^ b ^ c) & 0x1000 != 0) &
+            flag::V.on(!(a ^ b) & (a ^ c) & 0x8000 != 0) &
+            flag::C.on(a as u32 + b as u32 + carry as u32 > 0xFFFF)) - flag::N
+        );
+
+        self.regs.inc_pc(size);
+        self.cycles += cycles;
+    }
+
     fn exec_add8(&mut self, bus: &mut impl Bus, dst: impl DestOp<u8>, src: impl SrcOp<u8>, with_carry: bool, size: usize, cycles: usize) {
         let mut ctx = Context::from(bus, &mut self.regs);
         let a = dst.get(&ctx);
@@ -407,6 +1278,32 @@ impl CPU {
         self.cycles += cycles;
     }
 
+    fn exec_bit(&mut self, bus: &mut impl Bus, bit: u8, src: impl SrcOp<u8>, size: usize, cycles: usize) {
+        let ctx = Context::from(bus, &mut self.regs);
+        let val = src.get(&ctx);
+        let c = val & (1 << bit);
+
+        // F5 and F3 of BIT n,(HL) come from an internal address latch that is not emulated,
+        // so they are taken from the tested value as for the register forms.
+        self.regs.update_flags(
+            (flag::S.on(flag::signed(c)) &
+            flag::Z.on(c == 0) &
+            flag::P.on(c == 0) &
+            flag::intrinsic_undocumented(val)) + flag::H - flag::N
+        );
+        self.regs.inc_pc(size);
+        self.cycles += cycles;
+    }
+
+    fn exec_bit_indexed(&mut self, bus: &mut impl Bus, bit: u8, src: Idx8, size: usize, cycles: usize) {
+        let ctx = Context::from(bus, &mut self.regs);
+        let addr = src.addr(&ctx);
+        self.exec_bit(bus, bit, src, size, cycles);
+
+        // F5 and F3 of the indexed forms come from the high byte of the address
+        self.regs.update_flags(flag::intrinsic_undocumented((addr >> 8) as u8));
+    }
+
     fn exec_call(&mut self, bus: &mut impl Bus, pred: impl flag::Predicate){
         let f = self.regs.flags();
         if pred.eval(f) {
@@ -442,6 +1339,30 @@ impl CPU {
         self.cycles += cycles;
     }
 
+    fn exec_cpi(&mut self, bus: &mut impl Bus, decrement: bool, repeat: bool) {
+        let addr = self.regs.hl();
+        let val = bus.mem_read(addr);
+        let a = self.regs.a();
+        let c = a.wrapping_sub(val);
+        let count = self.regs.bc().wrapping_sub(1);
+
+        self.regs.set_hl(if decrement { addr.wrapping_sub(1) } else { addr.wrapping_add(1) });
+        self.regs.set_bc(count);
+
+        let half = flag::borrow_nibble(a, c);
+        let n = c.wrapping_sub(half as u8);
+        self.regs.update_flags(
+            (flag::S.on(flag::signed(c)) &
+            flag::Z.on(c == 0) &
+            flag::H.on(half) &
+            flag::PV.on(count != 0) &
+            flag::F5.on(n & 0x02 > 0) &
+            flag::F3.on(n & 0x08 > 0)) + flag::N
+        );
+
+        self.block_end(repeat && count != 0 && c != 0);
+    }
+
     fn exec_cpl(&mut self) {
         let a = self.regs.a();
         let c = !a;
@@ -531,6 +1452,8 @@ impl CPU {
     fn exec_ei(&mut self) {
         self.iff1 = true;
         self.iff2 = true;
+        // INT is accepted after the next instruction, so a RET right after EI completes first
+        self.int_delay = true;
         self.regs.inc_pc(1);
         self.cycles += 4;
     }
@@ -557,9 +1480,19 @@ impl CPU {
     }
 
     fn halt(&mut self) {
+        // PC moves past HALT, so the address pushed when the interrupt is serviced is the next
+        // instruction
+        self.halted = true;
+        self.regs.inc_pc(1);
         self.cycles += 4;
     }
 
+    fn exec_im(&mut self, mode: u8) {
+        self.im = mode;
+        self.regs.inc_pc(2);
+        self.cycles += 8;
+    }
+
     fn exec_in(
         &mut self, 
         bus: &mut impl Bus, 
@@ -603,6 +1536,21 @@ impl CPU {
         self.cycles += cycles;
     }
 
+    fn exec_ini(&mut self, bus: &mut impl Bus, decrement: bool, repeat: bool) {
+        let port = self.regs.c();
+        let val = bus.io_read(port);
+        let addr = self.regs.hl();
+        bus.mem_write(addr, val);
+        let count = self.regs.b().wrapping_sub(1);
+
+        self.regs.set_hl(if decrement { addr.wrapping_sub(1) } else { addr.wrapping_add(1) });
+        self.regs.set_b(count);
+
+        let port = if decrement { port.wrapping_sub(1) } else { port.wrapping_add(1) };
+        self.block_io_flags(val, val as u16 + port as u16, repeat && count != 0);
+        self.block_end(repeat && count != 0);
+    }
+
     fn exec_jp(&mut self, bus: &mut impl Bus, pred: impl flag::Predicate, dst: impl SrcOp<u16>, size: usize, cycles: usize) {
         let f = self.regs.flags();
         if pred.eval(f) {
@@ -636,31 +1584,50 @@ impl CPU {
         self.cycles += cycles;
     }
 
-    fn exec_ldi(&mut self, bus: &mut impl Bus, repeat: bool) {
+    fn exec_ld_a_ir(&mut self, bus: &mut impl Bus, src: Reg8) {
+        let ctx = Context::from(bus, &mut self.regs);
+        let val = src.get(&ctx);
+        self.regs.set_a(val);
+        self.regs.update_flags(
+            (flag::intrinsic(val) & flag::PV.on(self.iff2)) - flag::H - flag::N
+        );
+        self.regs.inc_pc(2);
+        self.cycles += 9;
+    }
+
+    fn exec_ldi(&mut self, bus: &mut impl Bus, decrement: bool, repeat: bool) {
         let from = self.regs.hl();
         let to = self.regs.de();
         let byte = bus.mem_read(from);
         bus.mem_write(to, byte);
-        let count = self.regs.bc();
+        let count = self.regs.bc().wrapping_sub(1);
 
-        self.regs.set_hl(from.wrapping_add(1));
-        self.regs.set_de(to.wrapping_add(1));
-        self.regs.set_bc(count.wrapping_sub(1));
+        if decrement {
+            self.regs.set_hl(from.wrapping_sub(1));
+            self.regs.set_de(to.wrapping_sub(1));
+        } else {
+            self.regs.set_hl(from.wrapping_add(1));
+            self.regs.set_de(to.wrapping_add(1));
+        }
+        self.regs.set_bc(count);
 
         let val_plus_a = byte.wrapping_add(self.regs.a());
         self.regs.update_flags(
-            flag::PV.on(count > 0) &
-            flag::F5.on(val_plus_a & 0x01 > 0) &
+            flag::PV.on(count != 0) &
+            flag::F5.on(val_plus_a & 0x02 > 0) &
             flag::F3.on(val_plus_a & 0x08 > 0) - flag::H - flag::N,
         );
 
-        if !repeat || self.regs.bc() == 0 {
-            // LDI or LDIR with BC=0
-            self.regs.inc_pc(2);
-            self.cycles += 16;
-        } else {
-            self.cycles += 21;
-        }
+        self.block_end(repeat && count != 0);
+    }
+
+    fn exec_neg(&mut self) {
+        let a = self.regs.a();
+        self.regs.set_a(0u8.wrapping_sub(a));
+
+        self.regs.update_flags(self.flags_sub8.for_ops(0, a));
+        self.regs.inc_pc(2);
+        self.cycles += 8;
     }
 
     fn exec_nop(&mut self, size: usize, cycles: usize) {
@@ -690,6 +1657,19 @@ impl CPU {
         self.cycles += cycles;
     }
 
+    fn exec_outi(&mut self, bus: &mut impl Bus, decrement: bool, repeat: bool) {
+        let addr = self.regs.hl();
+        let val = bus.mem_read(addr);
+        let count = self.regs.b().wrapping_sub(1);
+        self.regs.set_b(count);
+        bus.io_write(self.regs.c(), val);
+
+        self.regs.set_hl(if decrement { addr.wrapping_sub(1) } else { addr.wrapping_add(1) });
+
+        self.block_io_flags(val, val as u16 + self.regs.l() as u16, repeat && count != 0);
+        self.block_end(repeat && count != 0);
+    }
+
     fn exec_pop(&mut self, bus: &mut impl Bus, dst: impl DestOp<u16>, size: usize, cycles: usize) {
         let val = self.stack_pop(bus);
         let mut ctx = Context::from(bus, &mut self.regs);
@@ -708,6 +1688,15 @@ impl CPU {
         self.cycles += cycles;
     }
 
+    fn exec_res(&mut self, bus: &mut impl Bus, bit: u8, dst: impl DestOp<u8>, size: usize, cycles: usize) {
+        let mut ctx = Context::from(bus, &mut self.regs);
+        let val = dst.get(&ctx) & !(1 << bit);
+        dst.set(&mut ctx, val);
+
+        self.regs.inc_pc(size);
+        self.cycles += cycles;
+    }
+
     fn exec_ret(&mut self, bus: &mut impl Bus, pred: impl flag::Predicate) {
         if pred.eval(self.regs.flags()) {
             let addr = self.stack_pop(bus);
@@ -719,6 +1708,19 @@ impl CPU {
         }
     }
 
+    /// RETN, and RETI that only differs in how it is seen by the devices on the bus.
+    fn exec_retn(&mut self, bus: &mut impl Bus) {
+        let addr = self.stack_pop(bus);
+        self.regs.set_pc(addr);
+        self.iff1 = self.iff2;
+        self.cycles += 14;
+    }
+
+    fn exec_rl(&mut self, bus: &mut impl Bus, dst: impl DestOp<u8>, size: usize, cycles: usize) {
+        let carry = self.regs.flag(flag::C) as u8;
+        self.exec_shift(bus, dst, size, cycles, |a| ((a << 1) | carry, a & 0x80 > 0));
+    }
+
     fn exec_rla(&mut self) {
         let a = self.regs.a();
         let mut c = a << 1;
@@ -733,6 +1735,10 @@ impl CPU {
         self.cycles += 4;
     }
 
+    fn exec_rlc(&mut self, bus: &mut impl Bus, dst: impl DestOp<u8>, size: usize, cycles: usize) {
+        self.exec_shift(bus, dst, size, cycles, |a| (a.rotate_left(1), a & 0x80 > 0));
+    }
+
     fn exec_rlca(&mut self) {
         let a = self.regs.a();
         let c = (a << 1) | (a >> 7);
@@ -744,6 +1750,24 @@ impl CPU {
         self.cycles += 4;
     }
 
+    fn exec_rld(&mut self, bus: &mut impl Bus) {
+        let addr = self.regs.hl();
+        let val = bus.mem_read(addr);
+        let a = self.regs.a();
+        let c = (a & 0xF0) | (val >> 4);
+        bus.mem_write(addr, (val << 4) | (a & 0x0F));
+        self.regs.set_a(c);
+
+        self.regs.update_flags((flag::intrinsic(c) & flag::P.on(flag::parity(c))) - flag::H - flag::N);
+        self.regs.inc_pc(2);
+        self.cycles += 18;
+    }
+
+    fn exec_rr(&mut self, bus: &mut impl Bus, dst: impl DestOp<u8>, size: usize, cycles: usize) {
+        let carry = self.regs.flag(flag::C) as u8;
+        self.exec_shift(bus, dst, size, cycles, |a| ((a >> 1) | (carry << 7), a & 0x01 > 0));
+    }
+
     fn exec_rra(&mut self) {
         let a = self.regs.a();
         let mut c = a >> 1;
@@ -758,6 +1782,10 @@ impl CPU {
         self.cycles += 4;
     }
 
+    fn exec_rrc(&mut self, bus: &mut impl Bus, dst: impl DestOp<u8>, size: usize, cycles: usize) {
+        self.exec_shift(bus, dst, size, cycles, |a| (a.rotate_right(1), a & 0x01 > 0));
+    }
+
     fn exec_rrca(&mut self) {
         let a = self.regs.a();
         let c = (a >> 1) | (a << 7);
@@ -769,12 +1797,48 @@ impl CPU {
         self.cycles += 4;
     }
 
+    fn exec_rrd(&mut self, bus: &mut impl Bus) {
+        let addr = self.regs.hl();
+        let val = bus.mem_read(addr);
+        let a = self.regs.a();
+        let c = (a & 0xF0) | (val & 0x0F);
+        bus.mem_write(addr, (a << 4) | (val >> 4));
+        self.regs.set_a(c);
+
+        self.regs.update_flags((flag::intrinsic(c) & flag::P.on(flag::parity(c))) - flag::H - flag::N);
+        self.regs.inc_pc(2);
+        self.cycles += 18;
+    }
+
     fn exec_rst(&mut self, bus: &mut impl Bus, addr: u16) {
-        self.stack_push(bus, self.regs.pc() + 1);
+        self.stack_push(bus, self.regs.pc().wrapping_add(1));
         self.regs.set_pc(addr);
         self.cycles += 11;
     }
 
+    fn exec_sbc16(&mut self, bus: &mut impl Bus, dst: impl DestOp<u16>, src: impl SrcOp<u16>, size: usize, cycles: usize) {
+        let mut ctx = Context::from(bus, &mut self.regs);
+        let a = dst.get(&ctx);
+        let b = src.get(&ctx);
+        let carry = ctx.regs.flag(flag::C) as u16;
+        let c = a.wrapping_sub(b).wrapping_sub(carry);
+        dst.set(&mut ctx, c);
+
+        let ch = (c >> 8) as u8;
+
+        self.regs.update_flags(
+            (flag::intrinsic_undocumented(ch) &
+            flag::S.on(flag::signed(ch)) &
+            flag::Z.on(c == 0) &
+            flag::H.on((a ^ b ^ c) & 0x1000 != 0) &
+            flag::V.on((a ^ b) & (a ^ c) & 0x8000 != 0) &
+            flag::C.on((a as u32) < b as u32 + carry as u32)) + flag::N
+        );
+
+        self.regs.inc_pc(size);
+        self.cycles += cycles;
+    }
+
     fn exec_scf(&mut self) {
         self.regs.update_flags(
             flag::intrinsic_undocumented(self.regs.a()) + flag::C - flag::N - flag::H
@@ -783,6 +1847,51 @@ impl CPU {
         self.cycles += 4;
     }
 
+    fn exec_set(&mut self, bus: &mut impl Bus, bit: u8, dst: impl DestOp<u8>, size: usize, cycles: usize) {
+        let mut ctx = Context::from(bus, &mut self.regs);
+        let val = dst.get(&ctx) | (1 << bit);
+        dst.set(&mut ctx, val);
+
+        self.regs.inc_pc(size);
+        self.cycles += cycles;
+    }
+
+    /// Shift or rotate the destination using a function that returns the result and the carry.
+    fn exec_shift(
+        &mut self,
+        bus: &mut impl Bus,
+        dst: impl DestOp<u8>,
+        size: usize,
+        cycles: usize,
+        shift: impl FnOnce(u8) -> (u8, bool),
+    ) {
+        let mut ctx = Context::from(bus, &mut self.regs);
+        let a = dst.get(&ctx);
+        let (c, carry) = shift(a);
+        dst.set(&mut ctx, c);
+
+        self.regs.update_flags(self.flags_shift.for_op(c) & flag::C.on(carry));
+        self.regs.inc_pc(size);
+        self.cycles += cycles;
+    }
+
+    fn exec_sla(&mut self, bus: &mut impl Bus, dst: impl DestOp<u8>, size: usize, cycles: usize) {
+        self.exec_shift(bus, dst, size, cycles, |a| (a << 1, a & 0x80 > 0));
+    }
+
+    /// Undocumented SLL, that shifts left like SLA but sets the lowest bit.
+    fn exec_sll(&mut self, bus: &mut impl Bus, dst: impl DestOp<u8>, size: usize, cycles: usize) {
+        self.exec_shift(bus, dst, size, cycles, |a| ((a << 1) | 0x01, a & 0x80 > 0));
+    }
+
+    fn exec_sra(&mut self, bus: &mut impl Bus, dst: impl DestOp<u8>, size: usize, cycles: usize) {
+        self.exec_shift(bus, dst, size, cycles, |a| ((a >> 1) | (a & 0x80), a & 0x01 > 0));
+    }
+
+    fn exec_srl(&mut self, bus: &mut impl Bus, dst: impl DestOp<u8>, size: usize, cycles: usize) {
+        self.exec_shift(bus, dst, size, cycles, |a| (a >> 1, a & 0x01 > 0));
+    }
+
     fn exec_sub8(&mut self, bus: &mut impl Bus, dst: impl DestOp<u8>, src: impl SrcOp<u8>, with_carry: bool, size: usize, cycles: usize) {
         let mut ctx = Context::from(bus, &mut self.regs);
         let a = dst.get(&ctx);
@@ -810,6 +1919,47 @@ impl CPU {
         self.cycles += cycles;
     }
 
+    /// Finish a block instruction, that runs again from the start while it must repeat.
+    fn block_end(&mut self, repeat: bool) {
+        if repeat {
+            // F5 and F3 come from the high byte of the program counter when repeating
+            let pch = (self.regs.pc() >> 8) as u8;
+            self.regs.update_flags(flag::intrinsic_undocumented(pch));
+            self.cycles += 21;
+        } else {
+            self.regs.inc_pc(2);
+            self.cycles += 16;
+        }
+    }
+
+    /// Update the flags after INI, IND, OUTI or OUTD, given the transferred value and its sum `k`
+    /// with the next port (INI and IND) or with L (OUTI and OUTD).
+    fn block_io_flags(&mut self, val: u8, k: u16, repeat: bool) {
+        let b = self.regs.b();
+        let carry = k > 0xFF;
+        let mut parity = flag::parity((k as u8 & 0x07) ^ b);
+        let mut half = carry;
+        if repeat {
+            // The repeating forms leave P/V and H as they are in the middle of the next iteration
+            if carry && flag::signed(val) {
+                parity ^= !flag::parity(b.wrapping_sub(1) & 0x07);
+                half = b & 0x0F == 0x00;
+            } else if carry {
+                parity ^= !flag::parity(b.wrapping_add(1) & 0x07);
+                half = b & 0x0F == 0x0F;
+            } else {
+                parity ^= !flag::parity(b & 0x07);
+            }
+        }
+        self.regs.update_flags(
+            flag::intrinsic(b) &
+            flag::N.on(flag::signed(val)) &
+            flag::H.on(half) &
+            flag::P.on(parity) &
+            flag::C.on(carry)
+        );
+    }
+
     fn stack_pop(&mut self, bus: &impl Bus) -> u16 {
         let val = bus.mem_read_word(self.regs.sp());
         self.regs.inc_sp(2);
@@ -822,6 +1972,20 @@ impl CPU {
     }
 }
 
+/// Whether the opcode is a whole instruction, with no prefix or operands after it.
+fn single_byte(opcode: u8) -> bool {
+    !matches!(opcode,
+        0x01 | 0x11 | 0x21 | 0x31 |
+        0x06 | 0x0E | 0x16 | 0x1E | 0x26 | 0x2E | 0x36 | 0x3E |
+        0x10 | 0x18 | 0x20 | 0x28 | 0x30 | 0x38 |
+        0x22 | 0x2A | 0x32 | 0x3A |
+        0xC2 | 0xC3 | 0xCA | 0xD2 | 0xDA | 0xE2 | 0xEA | 0xF2 | 0xFA |
+        0xC4 | 0xCC | 0xCD | 0xD4 | 0xDC | 0xE4 | 0xEC | 0xF4 | 0xFC |
+        0xC6 | 0xCE | 0xD6 | 0xDE | 0xE6 | 0xEE | 0xF6 | 0xFE |
+        0xD3 | 0xDB |
+        0xCB | 0xDD | 0xED | 0xFD)
+}
+
 #[cfg(test)]
 mod test {
     use std::cell::RefCell;
@@ -838,6 +2002,8 @@ mod test {
     const FIXTURE_DE_ADDR: u16 = 0xF100;
     const FIXTURE_HL_ADDR: u16 = 0xF200;
     const FIXTURE_SP_ADDR: u16 = 0xF300;
+    const FIXTURE_IX_ADDR: u16 = 0xF400;
+    const FIXTURE_IY_ADDR: u16 = 0xF500;
 
     #[fixture]
     fn cpu() -> CPU { 
@@ -846,6 +2012,8 @@ mod test {
         cpu.regs.set_de(FIXTURE_DE_ADDR);
         cpu.regs.set_hl(FIXTURE_HL_ADDR);
         cpu.regs.set_sp(FIXTURE_SP_ADDR);
+        cpu.regs.set_ix(FIXTURE_IX_ADDR);
+        cpu.regs.set_iy(FIXTURE_IY_ADDR);
         cpu
     }
 
@@ -874,7 +2042,7 @@ mod test {
         }
 
         fn when_exec(&self) {
-            self.cpu.borrow_mut().exec(&mut *self.bus.borrow_mut());
+            self.cpu.borrow_mut().exec(&mut *self.bus.borrow_mut()).unwrap();
         }
 
         fn then(&self, f: impl FnOnce(&mut CPU, &mut B) -> TestCaseResult) -> TestCaseResult {
@@ -938,9 +2106,15 @@ mod test {
     fn get_e(cpu: &CPU, _: &impl Bus) -> u8 { cpu.regs.e() }
     fn get_h(cpu: &CPU, _: &impl Bus) -> u8 { cpu.regs.h() }
     fn get_l(cpu: &CPU, _: &impl Bus) -> u8 { cpu.regs.l() }
+    fn get_ixh(cpu: &CPU, _: &impl Bus) -> u8 { cpu.regs.ixh() }
+    fn get_iyl(cpu: &CPU, _: &impl Bus) -> u8 { cpu.regs.iyl() }
+    fn get_i(cpu: &CPU, _: &impl Bus) -> u8 { cpu.regs.i() }
+    fn get_r(cpu: &CPU, _: &impl Bus) -> u8 { cpu.regs.r() }
     fn get_ind_bc(cpu: &CPU, bus: &impl Bus) ->u8 { bus.mem_read(cpu.regs.bc()) }
     fn get_ind_de(cpu: &CPU, bus: &impl Bus) ->u8 { bus.mem_read(cpu.regs.de()) }    
     fn get_ind_hl(cpu: &CPU, bus: &impl Bus) ->u8 { bus.mem_read(cpu.regs.hl()) }
+    fn get_ind_ix(cpu: &CPU, bus: &impl Bus) ->u8 { bus.mem_read(cpu.regs.ix()) }
+    fn get_ind_iy(cpu: &CPU, bus: &impl Bus) ->u8 { bus.mem_read(cpu.regs.iy()) }
     fn get_mem_byte<B: Bus>(addr: u16) -> impl OpGet<u8, B> {
         move |_: &CPU, bus: &B| bus.mem_read(addr)
     }
@@ -949,6 +2123,7 @@ mod test {
     fn get_bc(cpu: &CPU, _: &impl Bus) -> u16 { cpu.regs.bc() }
     fn get_de(cpu: &CPU, _: &impl Bus) -> u16 { cpu.regs.de() }
     fn get_hl(cpu: &CPU, _: &impl Bus) -> u16 { cpu.regs.hl() }
+    fn get_ix(cpu: &CPU, _: &impl Bus) -> u16 { cpu.regs.ix() }
     fn get_sp(cpu: &CPU, _: &impl Bus) -> u16 { cpu.regs.sp() }
     fn get_mem_word<B: Bus>(addr: u16) -> impl OpGet<u16, B> {
         move |_: &CPU, bus: &B| bus.mem_read_word(addr)
@@ -966,6 +2141,7 @@ mod test {
     fn set_ind_bc(_: &mut CPU, bus: &mut impl Bus, v: u8) { bus.mem_write(FIXTURE_BC_ADDR, v) }
     fn set_ind_de(_: &mut CPU, bus: &mut impl Bus, v: u8) { bus.mem_write(FIXTURE_DE_ADDR, v) }
     fn set_ind_hl(_: &mut CPU, bus: &mut impl Bus, v: u8) { bus.mem_write(FIXTURE_HL_ADDR, v) }
+    fn set_ind_ix(_: &mut CPU, bus: &mut impl Bus, v: u8) { bus.mem_write(FIXTURE_IX_ADDR, v) }
     fn set_mem_byte<B: Bus>(addr: u16) -> impl OpSet<u8, B> {
         move |_: &mut CPU, bus: &mut B, v: u8| bus.mem_write(addr, v)
     }
@@ -975,11 +2151,189 @@ mod test {
     fn set_bc(cpu: &mut CPU, _: &mut impl Bus, v: u16) { cpu.regs.set_bc(v) }
     fn set_de(cpu: &mut CPU, _: &mut impl Bus, v: u16) { cpu.regs.set_de(v) }
     fn set_hl(cpu: &mut CPU, _: &mut impl Bus, v: u16) { cpu.regs.set_hl(v) }
+    fn set_iy(cpu: &mut CPU, _: &mut impl Bus, v: u16) { cpu.regs.set_iy(v) }
     fn set_sp(cpu: &mut CPU, _: &mut impl Bus, v: u16) { cpu.regs.set_sp(v) }
     fn set_mem_word<B: Bus>(addr: u16) -> impl OpSet<u16, B> {
         move |_: &mut CPU, bus: &mut B, v: u16| bus.mem_write_word(addr, v)
     }
 
+    #[rstest]
+    /* 1: ADC HL,BC */ #[case(&[0xED, 0x4A], set_bc, false)]
+    /* 2: ADC HL,SP */ #[case(&[0xED, 0x7A], set_sp, false)]
+    /* 3: SBC HL,DE */ #[case(&[0xED, 0x52], set_de, true)]
+    /* 4: SBC HL,SP */ #[case(&[0xED, 0x72], set_sp, true)]
+    fn test_adc16_sbc16<B: Bus>(
+        cpu: CPU,
+        bus: B,
+        #[case] opcode: &[u8],
+        #[case] set: impl OpSet<u16, B>,
+        #[case] sub: bool,
+    ) {
+        let scenario = PropScenario::from(cpu, bus);
+        proptest!(|(a: u16, b: u16, carry: bool)| {
+            scenario.given_code(0x0000, opcode);
+            scenario.given(|cpu, bus| {
+                cpu.regs.set_flags(carry as u8);
+                cpu.reset_cycles();
+                cpu.regs.set_hl(a);
+                set.apply(cpu, bus, b);
+            });
+            scenario.when_exec();
+            scenario.then(|cpu, _| {
+                let c = carry as i32;
+                let (unsigned, signed, half) = if sub {
+                    (a as i32 - b as i32 - c, a as i16 as i32 - b as i16 as i32 - c, (a & 0xFFF) as i32 - (b & 0xFFF) as i32 - c < 0)
+                } else {
+                    (a as i32 + b as i32 + c, a as i16 as i32 + b as i16 as i32 + c, (a & 0xFFF) as i32 + (b & 0xFFF) as i32 + c > 0xFFF)
+                };
+                let result = unsigned as u16;
+                prop_assert_eq!(cpu.regs.hl(), result);
+                prop_assert_eq!(cpu.regs.pc(), 2);
+                prop_assert_eq!(cpu.cycles(), 15);
+                prop_assert_flag_if(cpu.regs.flags(), 0, !(0..=0xFFFF).contains(&unsigned))?;
+                prop_assert_flag_if(cpu.regs.flags(), 1, sub)?;
+                prop_assert_flag_if(cpu.regs.flags(), 2, !(-0x8000..=0x7FFF).contains(&signed))?;
+                prop_assert_flag_if(cpu.regs.flags(), 4, half)?;
+                prop_assert_flag_if(cpu.regs.flags(), 6, result == 0)?;
+                prop_assert_flag_if(cpu.regs.flags(), 7, result & 0x8000 != 0)?;
+                Ok(())
+            })?;
+        });
+    }
+
+    #[rstest]
+    /* 1: BIT 0,B    */ #[case(&[0xCB, 0x40], set_b, 0, 8)]
+    /* 2: BIT 1,C    */ #[case(&[0xCB, 0x49], set_c, 1, 8)]
+    /* 3: BIT 2,D    */ #[case(&[0xCB, 0x52], set_d, 2, 8)]
+    /* 4: BIT 3,E    */ #[case(&[0xCB, 0x5B], set_e, 3, 8)]
+    /* 5: BIT 4,H    */ #[case(&[0xCB, 0x64], set_h, 4, 8)]
+    /* 6: BIT 5,L    */ #[case(&[0xCB, 0x6D], set_l, 5, 8)]
+    /* 7: BIT 6,(HL) */ #[case(&[0xCB, 0x76], set_ind_hl, 6, 12)]
+    /* 8: BIT 7,A    */ #[case(&[0xCB, 0x7F], set_a, 7, 8)]
+    fn test_bit<B: Bus>(
+        cpu: CPU,
+        bus: B,
+        #[case] opcode: &[u8],
+        #[case] set: impl OpSet<u8, B>,
+        #[case] bit: u8,
+        #[case] cycles: usize,
+    ) {
+        let scenario = PropScenario::from(cpu, bus);
+        proptest!(|(val: u8, flags: u8)| {
+            scenario.given_code(0x0000, opcode);
+            scenario.given(|cpu, bus| {
+                cpu.regs.set_flags(flags);
+                cpu.reset_cycles();
+                set.apply(cpu, bus, val);
+            });
+            scenario.when_exec();
+            scenario.then(|cpu, _| {
+                let bit_set = val & (1 << bit) != 0;
+                prop_assert_eq!(cpu.regs.pc(), 2);
+                prop_assert_eq!(cpu.cycles(), cycles);
+                prop_assert_flag_unaffected(cpu.regs.flags(), flags, 0)?;
+                prop_assert_flag_reset(cpu.regs.flags(), 1)?;
+                prop_assert_flag_if(cpu.regs.flags(), 2, !bit_set)?;
+                prop_assert_flag_set(cpu.regs.flags(), 4)?;
+                prop_assert_flag_if(cpu.regs.flags(), 6, !bit_set)?;
+                prop_assert_flag_if(cpu.regs.flags(), 7, bit == 7 && bit_set)?;
+                Ok(())
+            })?;
+        });
+    }
+
+    #[rstest]
+    fn test_block_cp<B: Bus>(mut cpu: CPU, mut bus: B) {
+        mem_write(&mut bus, 0x0000, &[0xED, 0xB1]);
+        mem_write(&mut bus, 0x4000, &[0x01, 0x02, 0x03, 0x04]);
+        cpu.regs.set_hl(0x4000);
+        cpu.regs.set_bc(4);
+        cpu.regs.set_a(0x03);
+
+        // CPIR stops when the value is found
+        for _ in 0..3 {
+            cpu.exec(&mut bus).unwrap();
+        }
+
+        assert_eq!(cpu.regs.pc(), 0x0002);
+        assert_eq!(cpu.regs.hl(), 0x4003);
+        assert_eq!(cpu.regs.bc(), 1);
+        assert_eq!(cpu.regs.flags() & 0b0100_0110, 0b0100_0110);
+        assert_eq!(cpu.cycles(), 21 + 21 + 16);
+    }
+
+    #[rstest]
+    fn test_block_in<B: Bus>(mut cpu: CPU, mut bus: B) {
+        mem_write(&mut bus, 0x0000, &[0xED, 0xB2]);
+        bus.io_write(0x10, 0x42);
+        cpu.regs.set_hl(0x4000);
+        cpu.regs.set_bc(0x0210);
+
+        cpu.exec(&mut bus).unwrap();
+        assert_eq!(cpu.regs.pc(), 0x0000);
+        cpu.exec(&mut bus).unwrap();
+
+        assert_eq!(cpu.regs.pc(), 0x0002);
+        assert_eq!(bus.mem_read(0x4000), 0x42);
+        assert_eq!(bus.mem_read(0x4001), 0x42);
+        assert_eq!(cpu.regs.hl(), 0x4002);
+        assert_eq!(cpu.regs.b(), 0);
+        assert!(cpu.regs.flag(flag::Z));
+        assert_eq!(cpu.cycles(), 21 + 16);
+    }
+
+    #[rstest]
+    /* 1: LDIR */ #[case(&[0xED, 0xB0], 0x4000, 0x5000, 0x4003, 0x5003)]
+    /* 2: LDDR */ #[case(&[0xED, 0xB8], 0x4002, 0x5002, 0x3FFF, 0x4FFF)]
+    fn test_block_ld<B: Bus>(
+        mut cpu: CPU,
+        mut bus: B,
+        #[case] opcode: &[u8],
+        #[case] from: u16,
+        #[case] to: u16,
+        #[case] expected_from: u16,
+        #[case] expected_to: u16,
+    ) {
+        mem_write(&mut bus, 0x0000, opcode);
+        mem_write(&mut bus, 0x4000, &[0x01, 0x02, 0x03]);
+        cpu.regs.set_hl(from);
+        cpu.regs.set_de(to);
+        cpu.regs.set_bc(3);
+
+        cpu.exec(&mut bus).unwrap();
+        assert_eq!(cpu.regs.pc(), 0x0000);
+        assert!(cpu.regs.flag(flag::PV));
+        cpu.exec(&mut bus).unwrap();
+        cpu.exec(&mut bus).unwrap();
+
+        assert_eq!(cpu.regs.pc(), 0x0002);
+        assert_eq!(cpu.regs.hl(), expected_from);
+        assert_eq!(cpu.regs.de(), expected_to);
+        assert_eq!(cpu.regs.bc(), 0);
+        assert!(!cpu.regs.flag(flag::PV));
+        assert_eq!([bus.mem_read(0x5000), bus.mem_read(0x5001), bus.mem_read(0x5002)], [0x01, 0x02, 0x03]);
+        assert_eq!(cpu.cycles(), 21 + 21 + 16);
+    }
+
+    #[rstest]
+    fn test_block_out<B: Bus>(mut cpu: CPU, mut bus: B) {
+        mem_write(&mut bus, 0x0000, &[0xED, 0xB3]);
+        mem_write(&mut bus, 0x4000, &[0x11, 0x22]);
+        cpu.regs.set_hl(0x4000);
+        cpu.regs.set_bc(0x0210);
+
+        cpu.exec(&mut bus).unwrap();
+        assert_eq!(bus.io_read(0x10), 0x11);
+        cpu.exec(&mut bus).unwrap();
+
+        assert_eq!(cpu.regs.pc(), 0x0002);
+        assert_eq!(bus.io_read(0x10), 0x22);
+        assert_eq!(cpu.regs.hl(), 0x4002);
+        assert_eq!(cpu.regs.b(), 0);
+        assert!(cpu.regs.flag(flag::Z));
+        assert_eq!(cpu.cycles(), 21 + 16);
+    }
+
     #[rstest]
     fn test_djnz(cpu: CPU, bus: impl Bus) {
         let scenario = PropScenario::from(cpu, bus);
@@ -1010,6 +2364,62 @@ mod test {
         });
     }
 
+    #[rstest]
+    fn test_ei_delay<B: Bus>(mut cpu: CPU, mut bus: B) {
+        mem_write(&mut bus, 0x0000, &[0xFB, 0x00, 0x00]);
+        cpu.im = 1;
+        cpu.set_int(true);
+
+        // EI and the instruction after it run before the interrupt is accepted
+        cpu.exec(&mut bus).unwrap();
+        cpu.exec(&mut bus).unwrap();
+        assert_eq!(cpu.regs.pc(), 0x0002);
+
+        cpu.exec(&mut bus).unwrap();
+        assert_eq!(cpu.regs.pc(), 0x0038);
+        assert_eq!(bus.mem_read_word(FIXTURE_SP_ADDR - 2), 0x0002);
+        assert!(!cpu.iff1);
+        assert!(!cpu.iff2);
+    }
+
+    #[rstest]
+    fn test_halt<B: Bus>(mut cpu: CPU, mut bus: B) {
+        mem_write(&mut bus, 0x0000, &[0x76]);
+        cpu.im = 1;
+        cpu.iff1 = true;
+
+        cpu.exec(&mut bus).unwrap();
+        cpu.exec(&mut bus).unwrap();
+        cpu.exec(&mut bus).unwrap();
+        assert!(cpu.halted());
+        assert_eq!(cpu.regs.pc(), 0x0001);
+        assert_eq!(cpu.cycles(), 12);
+
+        cpu.set_int(true);
+        cpu.exec(&mut bus).unwrap();
+        assert!(!cpu.halted());
+        assert_eq!(cpu.regs.pc(), 0x0038);
+        assert_eq!(bus.mem_read_word(FIXTURE_SP_ADDR - 2), 0x0001);
+        assert_eq!(cpu.cycles(), 12 + 13);
+    }
+
+    #[rstest]
+    /* 1: IM 0 */ #[case(&[0xED, 0x46], 0)]
+    /* 2: IM 1 */ #[case(&[0xED, 0x56], 1)]
+    /* 3: IM 2 */ #[case(&[0xED, 0x5E], 2)]
+    /* 4: IM 0 */ #[case(&[0xED, 0x6E], 0)]
+    /* 5: IM 2 */ #[case(&[0xED, 0x7E], 2)]
+    fn test_im<B: Bus>(mut cpu: CPU, mut bus: B, #[case] opcode: &[u8], #[case] mode: u8) {
+        mem_write(&mut bus, 0x0000, opcode);
+        cpu.im = 1;
+
+        cpu.exec(&mut bus).unwrap();
+
+        assert_eq!(cpu.im, mode);
+        assert_eq!(cpu.regs.pc(), 0x0002);
+        assert_eq!(cpu.cycles(), 8);
+    }
+
     #[rstest]
     /* 1: INC B    */ #[case(&[0x04], get_b, set_b, 1)]
     /* 2: INC C    */ #[case(&[0x0C], get_c, set_c, 1)]
@@ -1079,6 +2489,185 @@ mod test {
         });
     }
 
+    #[rstest]
+    fn test_indexed_bit<B: Bus>(mut cpu: CPU, mut bus: B) {
+        mem_write(&mut bus, 0x0000, &[0xFD, 0xCB, 0xFE, 0x46]);
+        bus.mem_write(FIXTURE_IY_ADDR - 2, 0xFE);
+        cpu.regs.set_flags(0x00);
+
+        cpu.exec(&mut bus).unwrap();
+
+        // F5 and F3 come from 0xF4, the high byte of IY-2
+        assert_eq!(cpu.regs.flags(), 0b0111_0100);
+        assert_eq!(cpu.regs.pc(), 0x0004);
+        assert_eq!(cpu.cycles(), 20);
+    }
+
+    #[rstest]
+    /* 1: RLC (IX+*)     */ #[case(&[0xDD, 0xCB, 0x05, 0x06], FIXTURE_IX_ADDR + 5, 0x81, 0x03, get_b, 0xF0)]
+    /* 2: RLC (IX+*),B   */ #[case(&[0xDD, 0xCB, 0x05, 0x00], FIXTURE_IX_ADDR + 5, 0x81, 0x03, get_b, 0x03)]
+    /* 3: SRL (IY+*),E   */ #[case(&[0xFD, 0xCB, 0xFF, 0x3B], FIXTURE_IY_ADDR - 1, 0x81, 0x40, get_e, 0x40)]
+    /* 4: RES 0,(IY+*)   */ #[case(&[0xFD, 0xCB, 0x10, 0x86], FIXTURE_IY_ADDR + 16, 0x81, 0x80, get_d, 0xF1)]
+    /* 5: SET 6,(IX+*),A */ #[case(&[0xDD, 0xCB, 0x80, 0xF7], FIXTURE_IX_ADDR - 128, 0x81, 0xC1, get_a, 0xC1)]
+    fn test_indexed_bits<B: Bus>(
+        mut cpu: CPU,
+        mut bus: B,
+        #[case] opcode: &[u8],
+        #[case] addr: u16,
+        #[case] val: u8,
+        #[case] expected: u8,
+        #[case] get_reg: impl OpGet<u8, B>,
+        #[case] expected_reg: u8,
+    ) {
+        mem_write(&mut bus, 0x0000, opcode);
+        bus.mem_write(addr, val);
+
+        cpu.exec(&mut bus).unwrap();
+
+        assert_eq!(bus.mem_read(addr), expected);
+        assert_eq!(get_reg.apply(&cpu, &bus), expected_reg);
+        assert_eq!(cpu.regs.pc(), 0x0004);
+        assert_eq!(cpu.cycles(), 23);
+    }
+
+    #[rstest]
+    /* 1: NOP          */ #[case(&[0xDD, 0x00], 0x0002, 8)]
+    /* 2: EX DE,HL     */ #[case(&[0xFD, 0xEB], 0x0002, 8)]
+    /* 3: Chained      */ #[case(&[0xDD, 0xFD, 0x21, 0x00, 0x00], 0x0001, 4)]
+    /* 4: ADD IX,BC    */ #[case(&[0xDD, 0x09], 0x0002, 15)]
+    /* 5: INC (IY+*)   */ #[case(&[0xFD, 0x34, 0x05], 0x0003, 23)]
+    /* 6: JP (IX)      */ #[case(&[0xDD, 0xE9], FIXTURE_IX_ADDR, 8)]
+    /* 7: EX (SP),IY   */ #[case(&[0xFD, 0xE3], 0x0002, 23)]
+    fn test_indexed_prefix<B: Bus>(
+        mut cpu: CPU,
+        mut bus: B,
+        #[case] opcode: &[u8],
+        #[case] expected_pc: u16,
+        #[case] expected_cycles: usize,
+    ) {
+        mem_write(&mut bus, 0x0000, opcode);
+
+        cpu.exec(&mut bus).unwrap();
+
+        assert_eq!(cpu.regs.pc(), expected_pc);
+        assert_eq!(cpu.cycles(), expected_cycles);
+    }
+
+    #[rstest]
+    /* 1: IM 0, RST 10h */ #[case(0, 0xD7, 0x0010, 13)]
+    /* 2: IM 1          */ #[case(1, 0xD7, 0x0038, 13)]
+    /* 3: IM 2          */ #[case(2, 0x20, 0x1234, 19)]
+    fn test_int(
+        mut cpu: CPU,
+        #[case] mode: u8,
+        #[case] data: u8,
+        #[case] expected_pc: u16,
+        #[case] expected_cycles: usize,
+    ) {
+        let mut bus = FakeBus::new();
+        bus.set_int_data(data);
+        bus.mem_write_word(0x8020, 0x1234);
+        cpu.regs.set_i(0x80);
+        cpu.regs.set_pc(0x4000);
+        cpu.im = mode;
+        cpu.iff1 = true;
+        cpu.iff2 = true;
+        cpu.set_int(true);
+
+        cpu.exec(&mut bus).unwrap();
+
+        assert_eq!(cpu.regs.pc(), expected_pc);
+        assert_eq!(cpu.regs.sp(), FIXTURE_SP_ADDR - 2);
+        assert_eq!(bus.mem_read_word(FIXTURE_SP_ADDR - 2), 0x4000);
+        assert!(!cpu.iff1);
+        assert!(!cpu.iff2);
+        assert_eq!(cpu.cycles(), expected_cycles);
+    }
+
+    #[rstest]
+    fn test_int_im0_instruction(mut cpu: CPU) {
+        let mut bus = FakeBus::new();
+        bus.set_int_data(0x3C); // INC A
+        cpu.regs.set_a(0x41);
+        cpu.regs.set_pc(0x4000);
+        cpu.iff1 = true;
+        cpu.iff2 = true;
+        cpu.set_int(true);
+
+        cpu.exec(&mut bus).unwrap();
+
+        assert_eq!(cpu.regs.a(), 0x42);
+        assert_eq!(cpu.regs.pc(), 0x4000);
+        assert_eq!(cpu.regs.sp(), FIXTURE_SP_ADDR);
+        assert!(!cpu.iff1);
+        assert!(!cpu.iff2);
+        assert_eq!(cpu.cycles(), 6);
+    }
+
+    #[rstest]
+    fn test_int_im0_unsupported(mut cpu: CPU) {
+        let mut bus = FakeBus::new();
+        bus.set_int_data(0xCD); // CALL nn
+        cpu.regs.set_pc(0x4000);
+        cpu.iff1 = true;
+        cpu.iff2 = true;
+        cpu.set_int(true);
+
+        let err = cpu.exec(&mut bus).unwrap_err();
+
+        assert_eq!(err, ExecError::new(ExecErrorKind::Unimplemented, 0x4000, &[0xCD]));
+        assert_eq!(cpu.regs.pc(), 0x4000);
+        assert_eq!(cpu.regs.sp(), FIXTURE_SP_ADDR);
+        assert!(cpu.iff1);
+    }
+
+    #[rstest]
+    fn test_int_im2_vector_wraps(mut cpu: CPU) {
+        let mut bus = FakeBus::new();
+        bus.set_int_data(0xFF);
+        bus.mem_write(0xFFFF, 0x34);
+        bus.mem_write(0x0000, 0x12);
+        cpu.regs.set_i(0xFF);
+        cpu.regs.set_pc(0x4000);
+        cpu.im = 2;
+        cpu.iff1 = true;
+        cpu.set_int(true);
+
+        cpu.exec(&mut bus).unwrap();
+
+        assert_eq!(cpu.regs.pc(), 0x1234);
+    }
+
+    #[rstest]
+    /* 1: INT */ #[case(false)]
+    /* 2: NMI */ #[case(true)]
+    fn test_int_stack_wraps(mut cpu: CPU, #[case] nmi: bool) {
+        let mut bus = FakeBus::new();
+        cpu.regs.set_sp(0x0001);
+        cpu.regs.set_pc(0x4000);
+        cpu.im = 1;
+        cpu.iff1 = true;
+        cpu.set_int(!nmi);
+        cpu.set_nmi(nmi);
+
+        cpu.exec(&mut bus).unwrap();
+
+        assert_eq!(cpu.regs.sp(), 0xFFFF);
+        assert_eq!(bus.mem_read(0xFFFF), 0x00);
+        assert_eq!(bus.mem_read(0x0000), 0x40);
+    }
+
+    #[rstest]
+    fn test_int_disabled<B: Bus>(mut cpu: CPU, mut bus: B) {
+        cpu.im = 1;
+        cpu.set_int(true);
+
+        cpu.exec(&mut bus).unwrap();
+
+        assert_eq!(cpu.regs.pc(), 0x0001);
+        assert_eq!(cpu.cycles(), 4);
+    }
+
     #[rstest]
     /* 1: JR n      */ #[case(0x18, None)]
     /* 2: JR NZ,n   */ #[case(0x20, Some(0b0000_0000))]
@@ -1132,6 +2721,37 @@ mod test {
         });
     }
 
+    #[rstest]
+    /* 1: LD A,I */ #[case(&[0xED, 0x57])]
+    /* 2: LD A,R */ #[case(&[0xED, 0x5F])]
+    fn test_ld_a_ir(cpu: CPU, bus: impl Bus, #[case] opcode: &[u8]) {
+        let scenario = PropScenario::from(cpu, bus);
+        proptest!(|(val: u8, iff2: bool, flags: u8)| {
+            scenario.given_code(0x0000, opcode);
+            scenario.given(|cpu, _| {
+                cpu.regs.set_flags(flags);
+                cpu.reset_cycles();
+                cpu.iff2 = iff2;
+                if opcode[1] == 0x57 { cpu.regs.set_i(val) } else { cpu.regs.set_r(val) }
+            });
+            scenario.when_exec();
+            scenario.then(|cpu, _| {
+                // R counts the two M1 cycles of LD A,R before it is read
+                let val = if opcode[1] == 0x57 { val } else { (val & 0x80) | (val.wrapping_add(2) & 0x7F) };
+                prop_assert_eq!(cpu.regs.a(), val);
+                prop_assert_eq!(cpu.regs.pc(), 2);
+                prop_assert_eq!(cpu.cycles(), 9);
+                prop_assert_flag_unaffected(cpu.regs.flags(), flags, 0)?;
+                prop_assert_flag_reset(cpu.regs.flags(), 1)?;
+                prop_assert_flag_if(cpu.regs.flags(), 2, iff2)?;
+                prop_assert_flag_reset(cpu.regs.flags(), 4)?;
+                prop_assert_flag_if(cpu.regs.flags(), 6, val == 0)?;
+                prop_assert_flag_if(cpu.regs.flags(), 7, val & 0x80 != 0)?;
+                Ok(())
+            })?;
+        });
+    }
+
     #[rstest]
     /* 01: LD B,B    */ #[case(&[0x40], get_b, set_b, 0x0001)]      
     /* 02: LD B,C    */ #[case(&[0x41], get_b, set_c, 0x0001)]      
@@ -1210,6 +2830,14 @@ mod test {
     /* 75: LD E,*    */ #[case(&[0x1E, 0x42], get_e, set_no_byte, 0x0002)]
     /* 76: LD L,*    */ #[case(&[0x2E, 0x42], get_l, set_no_byte, 0x0002)]
     /* 77: LD A,*    */ #[case(&[0x3E, 0x42], get_a, set_no_byte, 0x0002)]
+    /* 78: LD B,(IX+*) */ #[case(&[0xDD, 0x46, 0x00], get_b, set_ind_ix, 0x0003)]
+    /* 79: LD H,(IX+*) */ #[case(&[0xDD, 0x66, 0x00], get_h, set_ind_ix, 0x0003)]
+    /* 80: LD (IY+*),A */ #[case(&[0xFD, 0x77, 0x00], get_ind_iy, set_a, 0x0003)]
+    /* 81: LD (IX+*),* */ #[case(&[0xDD, 0x36, 0x00, 0x42], get_ind_ix, set_no_byte, 0x0004)]
+    /* 82: LD IXH,*    */ #[case(&[0xDD, 0x26, 0x42], get_ixh, set_no_byte, 0x0003)]
+    /* 83: LD IYL,A    */ #[case(&[0xFD, 0x6F], get_iyl, set_a, 0x0002)]
+    /* 84: LD I,A      */ #[case(&[0xED, 0x47], get_i, set_a, 0x0002)]
+    /* 85: LD R,A      */ #[case(&[0xED, 0x4F], get_r, set_a, 0x0002)]
     fn test_ld8<B: Bus>(
         mut cpu: CPU,
         mut bus: B,
@@ -1221,7 +2849,7 @@ mod test {
         mem_write(&mut bus, 0x0000, opcode);
         set.apply(&mut cpu, &mut bus, 0x42);
 
-        cpu.exec(&mut bus);
+        cpu.exec(&mut bus).unwrap();
 
         assert_eq!(get.apply(&cpu, &bus), 0x42);
         assert_eq!(cpu.regs.pc(), expected_pc);
@@ -1234,7 +2862,14 @@ mod test {
     /* 04: LD SP,**     */ #[case(&[0x31, 0xCD, 0xAB], get_sp, set_no_word, 0x0003)]      
     /* 05: LD (**),HL   */ #[case(&[0x22, 0x00, 0xD0], get_mem_word(0xD000), set_hl, 0x0003)]      
     /* 06: LD HL,(**)   */ #[case(&[0x2A, 0x00, 0xD0], get_hl, set_mem_word(0xD000), 0x0003)]      
-    /* 07: LD SP,HL     */ #[case(&[0xF9], get_sp, set_hl, 0x0001)]      
+    /* 07: LD SP,HL     */ #[case(&[0xF9], get_sp, set_hl, 0x0001)]
+    /* 08: LD IX,**     */ #[case(&[0xDD, 0x21, 0xCD, 0xAB], get_ix, set_no_word, 0x0004)]
+    /* 09: LD (**),IY   */ #[case(&[0xFD, 0x22, 0x00, 0xD0], get_mem_word(0xD000), set_iy, 0x0004)]
+    /* 10: LD IX,(**)   */ #[case(&[0xDD, 0x2A, 0x00, 0xD0], get_ix, set_mem_word(0xD000), 0x0004)]
+    /* 11: LD SP,IY     */ #[case(&[0xFD, 0xF9], get_sp, set_iy, 0x0002)]
+    /* 12: LD (**),BC   */ #[case(&[0xED, 0x43, 0x00, 0xD0], get_mem_word(0xD000), set_bc, 0x0004)]
+    /* 13: LD DE,(**)   */ #[case(&[0xED, 0x5B, 0x00, 0xD0], get_de, set_mem_word(0xD000), 0x0004)]
+    /* 14: LD (**),SP   */ #[case(&[0xED, 0x73, 0x00, 0xD0], get_mem_word(0xD000), set_sp, 0x0004)]      
     fn test_ld16<B: Bus>(
         mut cpu: CPU,
         mut bus: B,
@@ -1246,9 +2881,197 @@ mod test {
         mem_write(&mut bus, 0x0000, opcode);
         set.apply(&mut cpu, &mut bus, 0xABCD);
 
-        cpu.exec(&mut bus);
+        cpu.exec(&mut bus).unwrap();
 
         assert_eq!(get.apply(&cpu, &bus), 0xABCD);
         assert_eq!(cpu.regs.pc(), expected_pc);
     }
-}
\ No newline at end of file
+
+    #[rstest]
+    fn test_neg(cpu: CPU, bus: impl Bus) {
+        let scenario = PropScenario::from(cpu, bus);
+        proptest!(|(val: u8, flags: u8)| {
+            scenario.given_code(0x0000, &[0xED, 0x44]);
+            scenario.given(|cpu, _| {
+                cpu.regs.set_flags(flags);
+                cpu.reset_cycles();
+                cpu.regs.set_a(val);
+            });
+            scenario.when_exec();
+            scenario.then(|cpu, _| {
+                prop_assert_eq!(cpu.regs.a(), 0u8.wrapping_sub(val));
+                prop_assert_eq!(cpu.regs.pc(), 2);
+                prop_assert_eq!(cpu.cycles(), 8);
+                prop_assert_flag_if(cpu.regs.flags(), 0, val != 0)?;
+                prop_assert_flag_set(cpu.regs.flags(), 1)?;
+                prop_assert_flag_if(cpu.regs.flags(), 2, val == 0x80)?;
+                prop_assert_flag_if(cpu.regs.flags(), 4, val & 0x0F != 0)?;
+                prop_assert_flag_if(cpu.regs.flags(), 6, val == 0)?;
+                Ok(())
+            })?;
+        });
+    }
+
+    #[rstest]
+    fn test_nmi<B: Bus>(mut cpu: CPU, mut bus: B) {
+        cpu.regs.set_pc(0x4000);
+        cpu.iff1 = true;
+        cpu.iff2 = true;
+
+        cpu.set_nmi(true);
+        cpu.exec(&mut bus).unwrap();
+
+        assert_eq!(cpu.regs.pc(), 0x0066);
+        assert_eq!(bus.mem_read_word(FIXTURE_SP_ADDR - 2), 0x4000);
+        assert!(!cpu.iff1);
+        assert!(cpu.iff2);
+        assert_eq!(cpu.cycles(), 11);
+
+        // Holding the line does not raise another interrupt
+        cpu.exec(&mut bus).unwrap();
+        assert_eq!(cpu.regs.pc(), 0x0067);
+    }
+
+    #[rstest]
+    /* 1: RES 0,B    */ #[case(&[0xCB, 0x80], get_b, set_b, 0x01, 8)]
+    /* 2: RES 3,E    */ #[case(&[0xCB, 0x9B], get_e, set_e, 0x08, 8)]
+    /* 3: RES 7,(HL) */ #[case(&[0xCB, 0xBE], get_ind_hl, set_ind_hl, 0x80, 15)]
+    /* 4: SET 0,C    */ #[case(&[0xCB, 0xC1], get_c, set_c, 0x01, 8)]
+    /* 5: SET 4,H    */ #[case(&[0xCB, 0xE4], get_h, set_h, 0x10, 8)]
+    /* 6: SET 7,(HL) */ #[case(&[0xCB, 0xFE], get_ind_hl, set_ind_hl, 0x80, 15)]
+    /* 7: SET 7,A    */ #[case(&[0xCB, 0xFF], get_a, set_a, 0x80, 8)]
+    fn test_res_set<B: Bus>(
+        cpu: CPU,
+        bus: B,
+        #[case] opcode: &[u8],
+        #[case] get: impl OpGet<u8, B>,
+        #[case] set: impl OpSet<u8, B>,
+        #[case] mask: u8,
+        #[case] cycles: usize,
+    ) {
+        let scenario = PropScenario::from(cpu, bus);
+        proptest!(|(val: u8, flags: u8)| {
+            scenario.given_code(0x0000, opcode);
+            scenario.given(|cpu, bus| {
+                cpu.regs.set_flags(flags);
+                cpu.reset_cycles();
+                set.apply(cpu, bus, val);
+            });
+            scenario.when_exec();
+            scenario.then(|cpu, bus| {
+                let expected = if opcode[1] >= 0xC0 { val | mask } else { val & !mask };
+                prop_assert_eq!(get.apply(cpu, bus), expected);
+                prop_assert_eq!(cpu.regs.pc(), 2);
+                prop_assert_eq!(cpu.cycles(), cycles);
+                prop_assert_eq!(cpu.regs.flags(), flags);
+                Ok(())
+            })?;
+        });
+    }
+
+    #[rstest]
+    /* 1: RETN */ #[case(&[0xED, 0x45])]
+    /* 2: RETI */ #[case(&[0xED, 0x4D])]
+    fn test_retn<B: Bus>(mut cpu: CPU, mut bus: B, #[case] opcode: &[u8]) {
+        mem_write(&mut bus, 0x0000, opcode);
+        bus.mem_write_word(FIXTURE_SP_ADDR, 0x1234);
+        cpu.iff1 = false;
+        cpu.iff2 = true;
+
+        cpu.exec(&mut bus).unwrap();
+
+        assert_eq!(cpu.regs.pc(), 0x1234);
+        assert_eq!(cpu.regs.sp(), FIXTURE_SP_ADDR + 2);
+        assert!(cpu.iff1);
+        assert_eq!(cpu.cycles(), 14);
+    }
+
+    #[rstest]
+    /* 1: NOP             */ #[case(&[0x00], 0x00, 0x01)]
+    /* 2: RLC B           */ #[case(&[0xCB, 0x00], 0x00, 0x02)]
+    /* 3: NEG             */ #[case(&[0xED, 0x44], 0x00, 0x02)]
+    /* 4: LD IX,nn        */ #[case(&[0xDD, 0x21, 0x34, 0x12], 0x00, 0x02)]
+    /* 5: RLC (IX+d)      */ #[case(&[0xDD, 0xCB, 0x01, 0x06], 0x00, 0x02)]
+    /* 6: DD prefix chain */ #[case(&[0xDD, 0xFD, 0x21, 0x34, 0x12], 0x00, 0x01)]
+    /* 7: NOP             */ #[case(&[0x00], 0x7F, 0x00)]
+    /* 8: NOP             */ #[case(&[0x00], 0xFF, 0x80)]
+    fn test_r<B: Bus>(mut cpu: CPU, mut bus: B, #[case] opcode: &[u8], #[case] r: u8, #[case] expected_r: u8) {
+        mem_write(&mut bus, 0x0000, opcode);
+        cpu.regs.set_r(r);
+
+        cpu.exec(&mut bus).unwrap();
+
+        assert_eq!(cpu.regs.r(), expected_r);
+    }
+
+    #[rstest]
+    /* 1: RLD */ #[case(&[0xED, 0x6F], 0x7A, 0x31, 0x73, 0x1A)]
+    /* 2: RRD */ #[case(&[0xED, 0x67], 0x84, 0x20, 0x80, 0x42)]
+    fn test_rld_rrd<B: Bus>(
+        mut cpu: CPU,
+        mut bus: B,
+        #[case] opcode: &[u8],
+        #[case] a: u8,
+        #[case] val: u8,
+        #[case] expected_a: u8,
+        #[case] expected_val: u8,
+    ) {
+        mem_write(&mut bus, 0x0000, opcode);
+        bus.mem_write(FIXTURE_HL_ADDR, val);
+        cpu.regs.set_a(a);
+
+        cpu.exec(&mut bus).unwrap();
+
+        assert_eq!(cpu.regs.a(), expected_a);
+        assert_eq!(bus.mem_read(FIXTURE_HL_ADDR), expected_val);
+        assert_eq!(cpu.regs.flag(flag::S), expected_a & 0x80 != 0);
+        assert_eq!(cpu.regs.flag(flag::P), flag::parity(expected_a));
+        assert_eq!(cpu.regs.pc(), 0x0002);
+        assert_eq!(cpu.cycles(), 18);
+    }
+
+    #[rstest]
+    /* 01: RLC B    */ #[case(&[0xCB, 0x00], get_b, set_b, |a: u8, _| (a.rotate_left(1), a & 0x80 > 0), 8)]
+    /* 02: RLC (HL) */ #[case(&[0xCB, 0x06], get_ind_hl, set_ind_hl, |a: u8, _| (a.rotate_left(1), a & 0x80 > 0), 15)]
+    /* 03: RRC C    */ #[case(&[0xCB, 0x09], get_c, set_c, |a: u8, _| (a.rotate_right(1), a & 0x01 > 0), 8)]
+    /* 04: RL D     */ #[case(&[0xCB, 0x12], get_d, set_d, |a: u8, c| ((a << 1) | c as u8, a & 0x80 > 0), 8)]
+    /* 05: RR E     */ #[case(&[0xCB, 0x1B], get_e, set_e, |a: u8, c| ((a >> 1) | ((c as u8) << 7), a & 0x01 > 0), 8)]
+    /* 06: SLA H    */ #[case(&[0xCB, 0x24], get_h, set_h, |a: u8, _| (a << 1, a & 0x80 > 0), 8)]
+    /* 07: SRA L    */ #[case(&[0xCB, 0x2D], get_l, set_l, |a: u8, _| (((a as i8) >> 1) as u8, a & 0x01 > 0), 8)]
+    /* 08: SLL (HL) */ #[case(&[0xCB, 0x36], get_ind_hl, set_ind_hl, |a: u8, _| ((a << 1) | 0x01, a & 0x80 > 0), 15)]
+    /* 09: SRL A    */ #[case(&[0xCB, 0x3F], get_a, set_a, |a: u8, _| (a >> 1, a & 0x01 > 0), 8)]
+    /* 10: SRL (HL) */ #[case(&[0xCB, 0x3E], get_ind_hl, set_ind_hl, |a: u8, _| (a >> 1, a & 0x01 > 0), 15)]
+    fn test_shift<B: Bus>(
+        cpu: CPU,
+        bus: B,
+        #[case] opcode: &[u8],
+        #[case] get: impl OpGet<u8, B>,
+        #[case] set: impl OpSet<u8, B>,
+        #[case] shift: fn(u8, bool) -> (u8, bool),
+        #[case] cycles: usize,
+    ) {
+        let scenario = PropScenario::from(cpu, bus);
+        proptest!(|(val: u8, flags: u8)| {
+            scenario.given_code(0x0000, opcode);
+            scenario.given(|cpu, bus| {
+                cpu.regs.set_flags(flags);
+                cpu.reset_cycles();
+                set.apply(cpu, bus, val);
+            });
+            scenario.when_exec();
+            scenario.then(|cpu, bus| {
+                let (c, carry) = shift(val, flags & 0x01 != 0);
+                prop_assert_eq!(get.apply(cpu, bus), c);
+                prop_assert_eq!(cpu.regs.pc(), 2);
+                prop_assert_eq!(cpu.cycles(), cycles);
+                prop_assert_flag_if(cpu.regs.flags(), 0, carry)?;
+                prop_assert_flag_reset(cpu.regs.flags(), 1)?;
+                prop_assert_flag_if(cpu.regs.flags(), 2, c.count_ones() % 2 == 0)?;
+                prop_assert_flag_reset(cpu.regs.flags(), 4)?;
+                prop_assert_flag_if(cpu.regs.flags(), 6, c == 0)?;
+                prop_assert_flag_if(cpu.regs.flags(), 7, c & 0x80 != 0)?;
+                Ok(())
+            })?;
+        });
+    }
+}
@@ -211,6 +211,11 @@ impl PrecomputedUnary {
         })
     }
 
+    /// Return precomputed flags for the result of CB-prefixed rotations and shifts, except carry.
+    pub fn for_shift() -> Self {
+        Self::precompute(|c| (intrinsic(c) & P.on(parity(c))) - H - N)
+    }
+
     fn precompute<F: Fn(u8) -> Affection>(f: F) -> Self {
         let mut affections = Vec::with_capacity(256);
         for i in 0..=255 {
@@ -5,4 +5,5 @@ mod op;
 mod reg;
 
 pub use bus::*;
-pub use cpu::CPU;
\ No newline at end of file
+pub use cpu::CPU;
+pub use reg::Registers;
\ No newline at end of file
@@ -35,7 +35,12 @@ impl SrcOp<u8> for Lit8 {
 }
 
 /// A 8-bit CPU register that can act as source and destination operand.
-pub enum Reg8 { A, B, C, D, E, H, L }
+/// 
+/// IXH, IXL, IYH and IYL are the undocumented halves of the index registers. I and R are the
+/// interrupt vector base and memory refresh registers, only reachable through LD with A.
+#[derive(Clone, Copy)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum Reg8 { A, B, C, D, E, H, L, IXH, IXL, IYH, IYL, I, R }
 
 impl SrcOp<u8> for Reg8 {
     fn get<B: Bus>(&self, ctx: &Context<B>) -> u8 { 
@@ -47,6 +52,12 @@ impl SrcOp<u8> for Reg8 {
             Reg8::E => ctx.regs.e(),
             Reg8::H => ctx.regs.h(),
             Reg8::L => ctx.regs.l(),
+            Reg8::IXH => ctx.regs.ixh(),
+            Reg8::IXL => ctx.regs.ixl(),
+            Reg8::IYH => ctx.regs.iyh(),
+            Reg8::IYL => ctx.regs.iyl(),
+            Reg8::I => ctx.regs.i(),
+            Reg8::R => ctx.regs.r(),
         }
     }
 }
@@ -61,12 +72,19 @@ impl DestOp<u8> for Reg8 {
             Reg8::E => ctx.regs.set_e(val),
             Reg8::H => ctx.regs.set_h(val),
             Reg8::L => ctx.regs.set_l(val),
+            Reg8::IXH => ctx.regs.set_ixh(val),
+            Reg8::IXL => ctx.regs.set_ixl(val),
+            Reg8::IYH => ctx.regs.set_iyh(val),
+            Reg8::IYL => ctx.regs.set_iyl(val),
+            Reg8::I => ctx.regs.set_i(val),
+            Reg8::R => ctx.regs.set_r(val),
         }
     }
 }
 
 /// A 16-bit CPU register that can act as source and destination operand.
-pub enum Reg16 { AF, BC, DE, HL, SP, AF_ }
+#[derive(Clone, Copy)]
+pub enum Reg16 { AF, BC, DE, HL, SP, AF_, IX, IY }
 
 impl SrcOp<u16> for Reg16 {
     fn get<B: Bus>(&self, ctx: &Context<B>) -> u16 { 
@@ -77,6 +95,8 @@ impl SrcOp<u16> for Reg16 {
             Reg16::HL => ctx.regs.hl(),
             Reg16::SP => ctx.regs.sp(),
             Reg16::AF_ => ctx.regs.af_(),
+            Reg16::IX => ctx.regs.ix(),
+            Reg16::IY => ctx.regs.iy(),
         }
     }
 }
@@ -90,6 +110,8 @@ impl DestOp<u16> for Reg16 {
             Reg16::HL => ctx.regs.set_hl(val),
             Reg16::SP => ctx.regs.set_sp(val),
             Reg16::AF_ => ctx.regs.set_af_(val),
+            Reg16::IX => ctx.regs.set_ix(val),
+            Reg16::IY => ctx.regs.set_iy(val),
         }
     }
 }
@@ -143,6 +165,56 @@ impl<T: SrcOp<u16>> DestOp<u8> for Ind8<T> {
     }
 }
 
+/// An 8-bit indexed operand, located in memory at an index register plus the signed displacement
+/// that comes after the opcode of its instruction, as in (IX+d) and (IY+d).
+#[derive(Clone, Copy)]
+pub struct Idx8 { reg: Reg16, offset: u16 }
+
+impl Idx8 {
+    pub fn with_offset(reg: Reg16, offset: u16) -> Self {
+        Self { reg, offset }
+    }
+
+    /// Return the address in memory the operand refers to.
+    pub fn addr<B: Bus>(&self, ctx: &Context<B>) -> u16 {
+        let base: u16 = self.reg.get(ctx);
+        let disp = ctx.bus.mem_read(ctx.regs.pc().wrapping_add(self.offset)) as i8;
+        base.wrapping_add(disp as u16)
+    }
+}
+
+impl SrcOp<u8> for Idx8 {
+    fn get<B: Bus>(&self, ctx: &Context<B>) -> u8 {
+        let addr = self.addr(ctx);
+        ctx.bus.mem_read(addr)
+    }
+}
+
+impl DestOp<u8> for Idx8 {
+    fn set<B: Bus>(&self, ctx: &mut Context<B>, val: u8) {
+        let addr = self.addr(ctx);
+        ctx.bus.mem_write(addr, val)
+    }
+}
+
+/// An 8-bit destination operand whose values are also copied into a register.
+/// 
+/// This is what the undocumented DDCB and FDCB instructions do with their (IX+d) or (IY+d) operand.
+pub struct WithCopy<T: DestOp<u8>>(pub T, pub Reg8);
+
+impl<T: DestOp<u8>> SrcOp<u8> for WithCopy<T> {
+    fn get<B: Bus>(&self, ctx: &Context<B>) -> u8 {
+        self.0.get(ctx)
+    }
+}
+
+impl<T: DestOp<u8>> DestOp<u8> for WithCopy<T> {
+    fn set<B: Bus>(&self, ctx: &mut Context<B>, val: u8) {
+        self.0.set(ctx, val);
+        self.1.set(ctx, val);
+    }
+}
+
 /// A 16-bit indirect operand that indicates an address in memory where data is located.
 pub struct Ind16<T: SrcOp<u16>>(pub T);
 
@@ -193,6 +265,12 @@ mod test {
     #[case(Reg8::E, |regs: &Registers| regs.e(), |regs: &mut Registers, val: u8| regs.set_e(val))]
     #[case(Reg8::H, |regs: &Registers| regs.h(), |regs: &mut Registers, val: u8| regs.set_h(val))]
     #[case(Reg8::L, |regs: &Registers| regs.l(), |regs: &mut Registers, val: u8| regs.set_l(val))]
+    #[case(Reg8::IXH, |regs: &Registers| regs.ixh(), |regs: &mut Registers, val: u8| regs.set_ixh(val))]
+    #[case(Reg8::IXL, |regs: &Registers| regs.ixl(), |regs: &mut Registers, val: u8| regs.set_ixl(val))]
+    #[case(Reg8::IYH, |regs: &Registers| regs.iyh(), |regs: &mut Registers, val: u8| regs.set_iyh(val))]
+    #[case(Reg8::IYL, |regs: &Registers| regs.iyl(), |regs: &mut Registers, val: u8| regs.set_iyl(val))]
+    #[case(Reg8::I, |regs: &Registers| regs.i(), |regs: &mut Registers, val: u8| regs.set_i(val))]
+    #[case(Reg8::R, |regs: &Registers| regs.r(), |regs: &mut Registers, val: u8| regs.set_r(val))]
     fn test_reg8(
         mut fixture: Fixture, 
         #[case] reg: Reg8, 
@@ -201,7 +279,8 @@ mod test {
     ) {
         let mut ctx = fixture.context();
         set(ctx.regs, 0x42);
-        assert_eq!(reg.get(&ctx), 0x42);
+        let val: u8 = reg.get(&ctx);
+        assert_eq!(val, 0x42);
 
         reg.set(&mut ctx, 0x24);
         assert_eq!(get(ctx.regs), 0x24);
@@ -213,6 +292,8 @@ mod test {
     #[case(Reg16::DE, |regs: &Registers| regs.de(), |regs: &mut Registers, val: u16| regs.set_de(val))]
     #[case(Reg16::HL, |regs: &Registers| regs.hl(), |regs: &mut Registers, val: u16| regs.set_hl(val))]
     #[case(Reg16::AF_, |regs: &Registers| regs.af_(), |regs: &mut Registers, val: u16| regs.set_af_(val))]
+    #[case(Reg16::IX, |regs: &Registers| regs.ix(), |regs: &mut Registers, val: u16| regs.set_ix(val))]
+    #[case(Reg16::IY, |regs: &Registers| regs.iy(), |regs: &mut Registers, val: u16| regs.set_iy(val))]
     fn test_reg16(
         mut fixture: Fixture, 
         #[case] reg: Reg16, 
@@ -221,7 +302,8 @@ mod test {
     ) {
         let mut ctx = fixture.context();
         set(ctx.regs, 0x4224);
-        assert_eq!(reg.get(&ctx), 0x4224);
+        let val: u16 = reg.get(&ctx);
+        assert_eq!(val, 0x4224);
 
         reg.set(&mut ctx, 0xABCD);
         assert_eq!(get(ctx.regs), 0xABCD);
@@ -270,6 +352,43 @@ mod test {
         assert_eq!(op.get(&ctx), 0x42);
     }
 
+    #[rstest]
+    #[case(Reg16::IX, 0x05, 0x4005)]
+    #[case(Reg16::IX, 0xFB, 0x3FFB)]
+    #[case(Reg16::IY, 0x7F, 0x407F)]
+    #[case(Reg16::IY, 0x80, 0x3F80)]
+    fn test_idx8(mut fixture: Fixture, #[case] reg: Reg16, #[case] disp: u8, #[case] addr: u16) {
+        let mut ctx = fixture.context();
+
+        ctx.regs.set_pc(0x1000);
+        ctx.bus.mem_write(0x1002, disp);
+        reg.set(&mut ctx, 0x4000);
+        ctx.bus.mem_write(addr, 0x42);
+
+        let op = Idx8::with_offset(reg, 2);
+        let val: u8 = op.get(&ctx);
+        assert_eq!(op.addr(&ctx), addr);
+        assert_eq!(val, 0x42);
+
+        op.set(&mut ctx, 0x24);
+        assert_eq!(ctx.bus.mem_read(addr), 0x24);
+    }
+
+    #[rstest]
+    fn test_with_copy(mut fixture: Fixture) {
+        let mut ctx = fixture.context();
+
+        ctx.regs.set_hl(0x4000);
+        ctx.bus.mem_write(0x4000, 0x42);
+
+        let op = WithCopy(Ind8(Reg16::HL), Reg8::B);
+        assert_eq!(op.get(&ctx), 0x42);
+
+        op.set(&mut ctx, 0x24);
+        assert_eq!(ctx.bus.mem_read(0x4000), 0x24);
+        assert_eq!(ctx.regs.b(), 0x24);
+    }
+
     #[rstest]
     #[case(Reg16::BC)]
     #[case(Reg16::DE)]
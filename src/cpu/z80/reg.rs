@@ -57,12 +57,16 @@ pub struct Registers {
     hl_: Register,
 
     // Index registers
-    _ix: Register,
-    _iy: Register,
+    ix: Register,
+    iy: Register,
 
     // Control registers
     sp: Register,
     pc: Register,
+
+    // Interrupt vector and memory refresh registers
+    i: u8,
+    r: u8,
 }
 
 impl Registers {
@@ -78,12 +82,19 @@ impl Registers {
     #[inline] pub fn bc_(&self) -> u16 { *self.bc_ }
     #[inline] pub fn de_(&self) -> u16 { *self.de_ }
     #[inline] pub fn hl_(&self) -> u16 { *self.hl_ }
+    #[inline] pub fn ix(&self) -> u16 { *self.ix }
+    #[inline] pub fn iy(&self) -> u16 { *self.iy }
 
     #[inline] pub fn set_af(&mut self, val: u16) { *self.af = val }
     #[inline] pub fn set_bc(&mut self, val: u16) { *self.bc = val  }
     #[inline] pub fn set_de(&mut self, val: u16) { *self.de = val  }
     #[inline] pub fn set_hl(&mut self, val: u16) { *self.hl = val  }
     #[inline] pub fn set_af_(&mut self, val: u16) { *self.af_ = val }
+    #[inline] pub fn set_bc_(&mut self, val: u16) { *self.bc_ = val }
+    #[inline] pub fn set_de_(&mut self, val: u16) { *self.de_ = val }
+    #[inline] pub fn set_hl_(&mut self, val: u16) { *self.hl_ = val }
+    #[inline] pub fn set_ix(&mut self, val: u16) { *self.ix = val }
+    #[inline] pub fn set_iy(&mut self, val: u16) { *self.iy = val }
 
     #[inline] pub fn a(&self) -> u8 { self.af.high() }
     #[inline] pub fn b(&self) -> u8 { self.bc.high() }
@@ -92,6 +103,10 @@ impl Registers {
     #[inline] pub fn e(&self) -> u8 { self.de.low() }
     #[inline] pub fn h(&self) -> u8 { self.hl.high() }
     #[inline] pub fn l(&self) -> u8 { self.hl.low() }
+    #[inline] pub fn ixh(&self) -> u8 { self.ix.high() }
+    #[inline] pub fn ixl(&self) -> u8 { self.ix.low() }
+    #[inline] pub fn iyh(&self) -> u8 { self.iy.high() }
+    #[inline] pub fn iyl(&self) -> u8 { self.iy.low() }
 
     #[inline] pub fn set_a(&mut self, val: u8) { self.af.set_high(val) }
     #[inline] pub fn set_b(&mut self, val: u8) { self.bc.set_high(val) }
@@ -100,13 +115,21 @@ impl Registers {
     #[inline] pub fn set_e(&mut self, val: u8) { self.de.set_low(val) }
     #[inline] pub fn set_h(&mut self, val: u8) { self.hl.set_high(val) }
     #[inline] pub fn set_l(&mut self, val: u8) { self.hl.set_low(val) }
+    #[inline] pub fn set_ixh(&mut self, val: u8) { self.ix.set_high(val) }
+    #[inline] pub fn set_ixl(&mut self, val: u8) { self.ix.set_low(val) }
+    #[inline] pub fn set_iyh(&mut self, val: u8) { self.iy.set_high(val) }
+    #[inline] pub fn set_iyl(&mut self, val: u8) { self.iy.set_low(val) }
 
 
     #[inline] pub fn flags(&self) -> u8 { self.af.low() }
+    #[inline] pub fn i(&self) -> u8 { self.i }
+    #[inline] pub fn r(&self) -> u8 { self.r }
     #[inline] pub fn pc(&self) -> u16 { *self.pc }
     #[inline] pub fn sp(&self) -> u16 { *self.sp }
 
     #[inline] pub fn set_flags(&mut self, val: u8) { self.af.set_low(val) }
+    #[inline] pub fn set_i(&mut self, val: u8) { self.i = val }
+    #[inline] pub fn set_r(&mut self, val: u8) { self.r = val }
     #[inline] pub fn set_pc(&mut self, val: u16) { *self.pc = val }
     #[inline] pub fn set_sp(&mut self, val: u16) { *self.sp = val }
 
@@ -117,8 +140,11 @@ impl Registers {
     #[inline] pub fn inc_pc(&mut self, val: usize) -> u16 { *self.pc = self.pc.wrapping_add(val as u16); *self.pc }
     #[inline] pub fn inc_pc_signed(&mut self, val: i8) -> u16 { self.inc_pc(val as usize) }
 
-    #[inline] pub fn inc_sp(&mut self, val: usize) -> u16 { *self.sp += val as u16; *self.sp }
-    #[inline] pub fn dec_sp(&mut self, val: usize) -> u16 { *self.sp -= val as u16; *self.sp }
+    #[inline] pub fn inc_sp(&mut self, val: usize) -> u16 { *self.sp = self.sp.wrapping_add(val as u16); *self.sp }
+    #[inline] pub fn dec_sp(&mut self, val: usize) -> u16 { *self.sp = self.sp.wrapping_sub(val as u16); *self.sp }
+
+    // Only the low 7 bits of R count, bit 7 keeps the value last loaded by LD R,A
+    #[inline] pub fn inc_r(&mut self) { self.r = (self.r & 0x80) | (self.r.wrapping_add(1) & 0x7F) }
 
     #[inline] pub fn flag(&self, f: flag::Flag) -> bool { f.eval(self.flags()) }
 
@@ -8,6 +8,9 @@ pub trait Device {
     fn io_read(&self, port: u8) -> u8;
     fn io_write(&mut self, port: u8, val: u8);
     fn refresh(&mut self);
+
+    /// Whether the device is asserting the IRQ line.
+    fn irq(&self) -> bool { false }
 }
 
 enum Target {
@@ -153,5 +156,9 @@ impl w65c02::Bus for Bus {
             }
             _ => {},
         }
-    }    
+    }
+
+    fn irq(&self) -> bool {
+        self.devs.iter().flatten().any(|dev| dev.irq())
+    }
 }
@@ -12,6 +12,8 @@ pub enum Command {
     BreakShow,
     BreakDelete { addr: Option<u16> },
     MemShow { addr: Option<u16> },
+    CodeShow { addr: Option<u16> },
+    Trace { path: Option<String> },
 }
 
 #[derive(Debug)]
@@ -52,7 +54,9 @@ impl Command {
             Some("show") => Self::parse_show(params),
             Some("st") => Ok(Command::StatusShow),
             Some("m") => Self::parse_show_mem(params),
+            Some("d") => Self::parse_show_code(params),
             Some("reset")  => Ok(Command::Reset),
+            Some("trace") => Self::parse_trace(params),
             Some(other) => Err(ParseError::UnknownCommand(String::from(other))),
             None => Err(ParseError::NoInput),
         }
@@ -69,6 +73,8 @@ impl Command {
         println!("  step | s                        Execute one CPU step");
         println!("  show status | st                Show status of the system");
         println!("  show mem [<addr>] | m           Show memory at <addr> [default:PC]");
+        println!("  show code [<addr>] | d          Disassemble code at <addr> [default:PC]");
+        println!("  trace <file> | trace off        Log executed instructions to <file>");
         println!("Program control commands:");
         println!("  help | ?                        Print this help");
         println!("  exit | x                        Exit and return to shell");
@@ -83,6 +89,7 @@ impl Command {
             "break" => Ok(Command::BreakShow),
             "status" => Ok(Command::StatusShow),
             "mem" => Self::parse_show_mem(params),
+            "code" => Self::parse_show_code(params),
             other => Err(ParseError::InvalidParameter(String::from(other))),
         }
         
@@ -107,6 +114,21 @@ impl Command {
         }
     }
 
+    fn parse_show_code<'a, I: Iterator<Item=&'a str>>(mut params: I) -> Result<Command, ParseError> {
+        match params.next() {
+            Some(addr) => Self::parse_addr(addr).map(|a| Command::CodeShow { addr: Some(a) }),
+            None => Ok(Command::CodeShow { addr: None }),
+        }
+    }
+
+    fn parse_trace<'a, I: Iterator<Item=&'a str>>(mut params: I) -> Result<Command, ParseError> {
+        match params.next() {
+            Some("off") => Ok(Command::Trace { path: None }),
+            Some(path) => Ok(Command::Trace { path: Some(String::from(path)) }),
+            None => Err(ParseError::NotEnoughParameters),
+        }
+    }
+
     fn parse_addr(s: &str) -> Result<u16, ParseError> {
         match u16::from_str_radix(s, 16) {
             Ok(val) => Ok(val),
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::fs::File;
 use std::io;
 use std::path::Path;
 
@@ -9,11 +10,13 @@ use crate::mem;
 use crate::sys::nexus::cmd::Command;
 use crate::sys::nexus::bus::Bus;
 
+// TODO: adjust this to the clock speed, etc.
+const REFRESH_CYCLES: usize = 120_000;
+
 pub struct System {    
     cpu: w65c02::CPU,
     bus: Bus,
     breakpoints: HashMap<u16, ()>,
-    cycles: usize,
 }
 
 impl System {
@@ -21,13 +24,16 @@ impl System {
         let bios = mem::ROM::load_from_file(bios_path)?;
         let vid = nxvid::NXVID::with_window_title(
             "Nexus Computer System emulator");
-        let bus = Bus::new(vid, bios);
+        let mut bus = Bus::new(vid, bios);
+
+        // Random registers, so the BIOS cannot rely on values that real boards do not ensure
+        let mut cpu = w65c02::CPU::new();
+        cpu.power_on_random(&mut bus, &mut rand::thread_rng());
 
         Ok(Self {
-            cpu: w65c02::CPU::new(),
+            cpu,
             bus,
             breakpoints: HashMap::new(),
-            cycles: 0,
         })        
     }
 
@@ -41,6 +47,8 @@ impl System {
             Command::BreakShow => self.exec_break_show(),
             Command::BreakDelete { addr } => self.exec_break_delete(addr),
             Command::MemShow { addr } => self.exec_mem_show(addr),
+            Command::CodeShow { addr } => self.exec_code_show(addr),
+            Command::Trace { path } => self.exec_trace(path),
             _ => unreachable!(),
         }
     }
@@ -83,6 +91,48 @@ impl System {
         println!("");
     }
 
+    fn exec_code_show(&self, addr: Option<u16>) {
+        let mut a = addr.unwrap_or(self.cpu.pc);
+        for _ in 0..16 {
+            match w65c02::disassemble(&self.bus, self.cpu.variant(), a) {
+                Ok(inst) => {
+                    self.print_inst(&inst);
+                    a = inst.next_addr();
+                },
+                Err(err) => {
+                    println!("Error: {}", err);
+                    break;
+                },
+            }
+        }
+        println!("");
+    }
+
+    fn print_inst(&self, inst: &w65c02::Disassembly) {
+        print!("{:04X}:   ", inst.addr);
+        for i in 0..3 {
+            if i < inst.len {
+                print!("{:02X} ", self.bus.mem_read(inst.addr.wrapping_add(i as u16)));
+            } else {
+                print!("   ");
+            }
+        }
+        println!("   {}", inst);
+    }
+
+    fn exec_trace(&mut self, path: Option<String>) {
+        // Removing the previous tracer flushes its output
+        if let Err(err) = self.cpu.remove_observer() {
+            println!("Error: cannot write trace: {}", err);
+        }
+        if let Some(path) = path {
+            match File::create(&path) {
+                Ok(file) => self.cpu.set_observer(w65c02::Tracer::new(io::BufWriter::new(file))),
+                Err(err) => println!("Error: cannot create {}: {}", path, err),
+            }
+        }
+    }
+
     fn exec_status(&self) {
         println!("  CPU   : A={:02X} X={:02X} Y={:02X}  SP={:02X} PC={:04X} P={:02X}", 
             self.cpu.a, self.cpu.x, self.cpu.y, self.cpu.sp, self.cpu.pc, self.cpu.status,
@@ -94,33 +144,35 @@ impl System {
     }
 
     fn exec_step(&mut self) {
-        let pc = self.cpu.pc;
-        let inst = self.cpu.exec(&mut self.bus);
-        print!("{:04X}:   ", pc);
-        for i in 0..3 {
-            if i < inst.len() {
-                print!("{:02X} ", self.bus.mem_read(pc.wrapping_add(i as u16)));
-            } else {
-                print!("   ");
-            }
+        // Show the instruction before it runs and changes the memory it was decoded from
+        if let Ok(inst) = w65c02::disassemble(&self.bus, self.cpu.variant(), self.cpu.pc) {
+            self.print_inst(&inst);
+        }
+        if let Err(err) = self.cpu.exec(&mut self.bus) {
+            println!("Error: {}", err);
+            return;
         }
-        println!("   {}", inst);
         self.bus.refresh_all();
     }
 
     fn exec_resume(&mut self) {
+        let breakpoints = &self.breakpoints;
         loop {
-            let inst = self.cpu.exec(&mut self.bus);
-            self.cycles += inst.cycles;
-            if self.breakpoints.contains_key(&self.cpu.pc) {
-                println!("Breakpoint at {:04X}", self.cpu.pc);
-                break;
-            }
-
-            // TODO: adjust this to the clock speed, etc.
-            if self.cycles > 120_000 {
-                self.bus.refresh_all();
-                self.cycles = 0;
+            let result = self.cpu.run_until(&mut self.bus, REFRESH_CYCLES, |cpu| breakpoints.contains_key(&cpu.pc));
+            match result {
+                Ok(w65c02::StopReason::Budget) => self.bus.refresh_all(),
+                Ok(w65c02::StopReason::Break) => {
+                    println!("Breakpoint at {:04X}", self.cpu.pc);
+                    break;
+                },
+                Ok(w65c02::StopReason::Stopped) => {
+                    println!("CPU stopped at {:04X}", self.cpu.pc.wrapping_sub(1));
+                    break;
+                },
+                Err(err) => {
+                    println!("Error: {}", err);
+                    break;
+                },
             }
         }
         self.bus.refresh_all();
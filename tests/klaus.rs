@@ -0,0 +1,86 @@
+//! Runs Klaus Dormann's 6502 test suites (https://github.com/Klaus2m5/6502_65C02_functional_tests)
+//! against the W65C02 core.
+//!
+//! The test binaries are not part of the repository. The tests are ignored by default, and
+//! `tests/klaus/README.md` tells how to build the binaries, where to put them and how to run the
+//! tests. A missing binary fails its test.
+//!
+//! Each test runs until the CPU traps in a loop that jumps to itself. The suites do that both on
+//! success and on failure, so the trap address tells which one it was: look it up in the listing
+//! of the suite to find the failing check.
+
+use std::fs;
+use std::path::PathBuf;
+
+use vm8::cpu::w65c02::{Bus, CPU, FakeBus, RunState, Variant};
+
+/// Upper bound on the executed instructions, way above what any of the suites needs.
+const MAX_INSTRUCTIONS: usize = 200_000_000;
+
+/// Address of the `success` label in `bin_files/6502_functional_test.lst`.
+const FUNCTIONAL_SUCCESS: u16 = 0x3469;
+
+/// Address of the `success` label in `bin_files/65C02_extended_opcodes_test.lst`.
+const EXTENDED_OPCODES_SUCCESS: u16 = 0x24F1;
+
+/// Address of the status byte of the decimal test, which is zero if all the checks passed.
+const DECIMAL_ERROR: u16 = 0x000B;
+
+/// Load the binary and run it from `start` until it traps.
+fn run(file: &str, variant: Variant, load: u16, start: u16) -> (CPU, FakeBus) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/klaus").join(file);
+    let image = fs::read(&path)
+        .unwrap_or_else(|err| panic!("cannot read {}: {}", path.display(), err));
+
+    let mut bus = FakeBus::new();
+    for (i, byte) in image.iter().enumerate() {
+        bus.mem_write(load.wrapping_add(i as u16), *byte);
+    }
+    let mut cpu = CPU::with_variant(variant);
+    cpu.pc = start;
+    cpu.sp = 0xFF;
+
+    for _ in 0..MAX_INSTRUCTIONS {
+        let pc = cpu.pc;
+        if let Err(err) = cpu.exec(&mut bus) {
+            panic!("{}: {}", file, err);
+        }
+        if cpu.pc == pc || cpu.run_state() == RunState::Stopped {
+            return (cpu, bus);
+        }
+    }
+    panic!("{}: no trap after {} instructions, PC={:04X}", file, MAX_INSTRUCTIONS, cpu.pc);
+}
+
+fn assert_trap(file: &str, variant: Variant, success: u16) {
+    let (cpu, _) = run(file, variant, 0x0000, 0x0400);
+    assert_eq!(cpu.pc, success,
+        "{} on {:?} trapped at {:04X} (A={:02X} X={:02X} Y={:02X} P={:02X} SP={:02X})",
+        file, variant, cpu.pc, cpu.a, cpu.x, cpu.y, cpu.status.bits(), cpu.sp);
+}
+
+#[test]
+#[ignore = "needs the binaries described in tests/klaus/README.md"]
+fn functional_test_nmos() {
+    assert_trap("6502_functional_test.bin", Variant::NMOS6502, FUNCTIONAL_SUCCESS);
+}
+
+#[test]
+#[ignore = "needs the binaries described in tests/klaus/README.md"]
+fn functional_test_cmos() {
+    assert_trap("6502_functional_test.bin", Variant::W65C02, FUNCTIONAL_SUCCESS);
+}
+
+#[test]
+#[ignore = "needs the binaries described in tests/klaus/README.md"]
+fn extended_opcodes_test() {
+    assert_trap("65C02_extended_opcodes_test.bin", Variant::W65C02, EXTENDED_OPCODES_SUCCESS);
+}
+
+#[test]
+#[ignore = "needs the binaries described in tests/klaus/README.md"]
+fn decimal_test() {
+    let (cpu, bus) = run("6502_decimal_test.bin", Variant::W65C02, 0x0200, 0x0200);
+    assert_eq!(bus.mem_read(DECIMAL_ERROR), 0,
+        "decimal test failed at {:04X} (A={:02X} P={:02X})", cpu.pc, cpu.a, cpu.status.bits());
+}
@@ -0,0 +1,283 @@
+//! Runs the SingleStepTests per-opcode vectors (https://github.com/SingleStepTests) against the
+//! W65C02 and Z80 cores.
+//!
+//! Every JSON file holds the tests of one opcode. Each test gives the registers and memory before
+//! and after running a single instruction, and the bus cycles it takes. The files are read from a
+//! directory per CPU, under `tests/single_step/` or the directory given in the
+//! `SINGLE_STEP_TESTS` environment variable:
+//!
+//! - `6502`, `wdc65c02`, `rockwell65c02` and `synertek65c02`, with the `v1` files of the `65x02`
+//!   repository for the corresponding CPU.
+//! - `z80`, with the `v1` files of the `z80` repository.
+//!
+//! `tests/single_step/` holds a sample of a few opcodes per CPU. Point `SINGLE_STEP_TESTS` to a
+//! checkout of the full suites to run them all.
+//!
+//! The mismatches are reported per opcode. A missing directory fails the test, and so does any
+//! opcode the core does not implement.
+
+use std::env;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+
+use serde_json::Value;
+use vm8::cpu::w65c02::{self, Access, BusCycle, Flags, Variant};
+use vm8::cpu::z80;
+
+/// Result of running all the tests of an opcode.
+enum Outcome {
+    Passed,
+    /// Number of failed tests, and the description of the first failure.
+    Failed(usize, String),
+}
+
+/// Run every file in the directory of the CPU and panic if any test fails. Each file is passed
+/// to `run_file` with the tests it contains.
+fn run_suite(cpu: &str, mut run_file: impl FnMut(&[Value]) -> Outcome) {
+    let root = env::var_os("SINGLE_STEP_TESTS")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/single_step"));
+    let dir = root.join(cpu);
+    let mut files: Vec<PathBuf> = fs::read_dir(&dir)
+        .unwrap_or_else(|err| panic!("cannot read {}: {}", dir.display(), err))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+    assert!(!files.is_empty(), "no tests found in {}", dir.display());
+
+    let mut failures = Vec::new();
+    for path in &files {
+        let opcode = path.file_stem().unwrap().to_string_lossy().to_string();
+        let json = fs::read_to_string(path).unwrap();
+        let tests: Vec<Value> = serde_json::from_str(&json)
+            .unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+        match run_file(&tests) {
+            Outcome::Passed => {},
+            Outcome::Failed(count, first) => {
+                failures.push(format!("  {}: {} of {} failed, first: {}", opcode, count, tests.len(), first));
+            },
+        }
+    }
+
+    assert!(failures.is_empty(), "{}: {} of {} opcodes failed\n{}",
+        cpu, failures.len(), files.len(), failures.join("\n"));
+}
+
+/// Run each test with `run_test`. A panic fails the test and skips the rest of the opcode, so
+/// a crashing instruction is reported only once.
+fn run_tests<T>(tests: &[Value], bus: &mut T, mut run_test: impl FnMut(&Value, &mut T) -> Result<(), String>) -> Outcome {
+    let mut failed = 0;
+    let mut first = None;
+    for test in tests {
+        let name = test["name"].as_str().unwrap_or("?");
+        let result = panic::catch_unwind(AssertUnwindSafe(|| run_test(test, bus)))
+            .unwrap_or_else(|_| Err(String::from("panicked")));
+        match result {
+            Ok(()) => continue,
+            Err(err) => {
+                failed += 1;
+                first.get_or_insert_with(|| format!("[{}] {}", name, err));
+                if err == "panicked" {
+                    break;
+                }
+            },
+        }
+    }
+    match first {
+        Some(first) => Outcome::Failed(failed, first),
+        None => Outcome::Passed,
+    }
+}
+
+fn num(state: &Value, name: &str) -> u64 {
+    state[name].as_u64().unwrap_or_else(|| panic!("missing '{}'", name))
+}
+
+/// The `[address, value]` pairs of the RAM of a state.
+fn ram(state: &Value) -> Vec<(u16, u8)> {
+    state["ram"].as_array().unwrap().iter()
+        .map(|entry| (entry[0].as_u64().unwrap() as u16, entry[1].as_u64().unwrap() as u8))
+        .collect()
+}
+
+/// Collect the mismatches between actual and expected values into a single message.
+#[derive(Default)]
+struct Mismatches(Vec<String>);
+
+impl Mismatches {
+    fn check(&mut self, what: &str, actual: u64, expected: u64) {
+        if actual != expected {
+            self.0.push(format!("{} is {:02X}, expected {:02X}", what, actual, expected));
+        }
+    }
+
+    fn result(self) -> Result<(), String> {
+        if self.0.is_empty() { Ok(()) } else { Err(self.0.join(", ")) }
+    }
+}
+
+/// A W65C02 bus that records the cycles reported by the CPU.
+struct RecordingBus {
+    mem: w65c02::FakeBus,
+    cycles: Vec<BusCycle>,
+}
+
+impl w65c02::Bus for RecordingBus {
+    fn mem_read(&self, addr: u16) -> u8 { self.mem.mem_read(addr) }
+    fn mem_write(&mut self, addr: u16, val: u8) { self.mem.mem_write(addr, val) }
+    fn cycle(&mut self, cycle: BusCycle) { self.cycles.push(cycle) }
+}
+
+fn run_w65c02_test(variant: Variant, test: &Value, bus: &mut RecordingBus) -> Result<(), String> {
+    use vm8::cpu::w65c02::Bus;
+
+    let (initial, expected) = (&test["initial"], &test["final"]);
+    let mut cpu = w65c02::CPU::with_variant(variant);
+    cpu.set_cycle_accurate(true);
+    cpu.pc = num(initial, "pc") as u16;
+    cpu.sp = num(initial, "s") as u8;
+    cpu.a = num(initial, "a") as u8;
+    cpu.x = num(initial, "x") as u8;
+    cpu.y = num(initial, "y") as u8;
+    cpu.status = Flags::from_bits_retain(num(initial, "p") as u8);
+
+    // The tests list every address the instruction accesses, so there is no need to clear what
+    // the previous test left in memory
+    bus.cycles.clear();
+    for (addr, val) in ram(initial) {
+        bus.mem_write(addr, val);
+    }
+
+    cpu.exec(bus).map_err(|err| err.to_string())?;
+    let mut mismatches = Mismatches::default();
+    mismatches.check("PC", cpu.pc as u64, num(expected, "pc"));
+    mismatches.check("S", cpu.sp as u64, num(expected, "s"));
+    mismatches.check("A", cpu.a as u64, num(expected, "a"));
+    mismatches.check("X", cpu.x as u64, num(expected, "x"));
+    mismatches.check("Y", cpu.y as u64, num(expected, "y"));
+    mismatches.check("P", cpu.status.bits() as u64, num(expected, "p"));
+    for (addr, val) in ram(expected) {
+        mismatches.check(&format!("[{:04X}]", addr), bus.mem_read(addr) as u64, val as u64);
+    }
+
+    let cycles: Vec<(u16, u8, &str)> = bus.cycles.iter()
+        .map(|c| (c.addr, c.data, if c.access == Access::Read { "read" } else { "write" }))
+        .collect();
+    let expected_cycles: Vec<(u16, u8, &str)> = test["cycles"].as_array().unwrap().iter()
+        .map(|c| (c[0].as_u64().unwrap() as u16, c[1].as_u64().unwrap() as u8, c[2].as_str().unwrap()))
+        .collect();
+    if cycles != expected_cycles {
+        mismatches.0.push(format!("cycles are {:X?}, expected {:X?}", cycles, expected_cycles));
+    }
+    mismatches.result()
+}
+
+fn run_w65c02_suite(dir: &str, variant: Variant) {
+    let mut bus = RecordingBus { mem: w65c02::FakeBus::new(), cycles: Vec::new() };
+    run_suite(dir, |tests| run_tests(tests, &mut bus, |test, bus| run_w65c02_test(variant, test, bus)));
+}
+
+#[test]
+fn single_step_nmos6502() {
+    run_w65c02_suite("6502", Variant::NMOS6502);
+}
+
+#[test]
+fn single_step_w65c02() {
+    run_w65c02_suite("wdc65c02", Variant::W65C02);
+}
+
+#[test]
+fn single_step_r65c02() {
+    run_w65c02_suite("rockwell65c02", Variant::R65C02);
+}
+
+#[test]
+fn single_step_g65sc02() {
+    run_w65c02_suite("synertek65c02", Variant::G65SC02);
+}
+
+fn run_z80_test(test: &Value, bus: &mut z80::FakeBus) -> Result<(), String> {
+    use vm8::cpu::z80::Bus;
+
+    let (initial, expected) = (&test["initial"], &test["final"]);
+    let mut cpu = z80::CPU::new();
+    let regs = cpu.regs_mut();
+    regs.set_pc(num(initial, "pc") as u16);
+    regs.set_sp(num(initial, "sp") as u16);
+    regs.set_a(num(initial, "a") as u8);
+    regs.set_flags(num(initial, "f") as u8);
+    regs.set_b(num(initial, "b") as u8);
+    regs.set_c(num(initial, "c") as u8);
+    regs.set_d(num(initial, "d") as u8);
+    regs.set_e(num(initial, "e") as u8);
+    regs.set_h(num(initial, "h") as u8);
+    regs.set_l(num(initial, "l") as u8);
+    regs.set_af_(num(initial, "af_") as u16);
+    regs.set_bc_(num(initial, "bc_") as u16);
+    regs.set_de_(num(initial, "de_") as u16);
+    regs.set_hl_(num(initial, "hl_") as u16);
+    regs.set_ix(num(initial, "ix") as u16);
+    regs.set_iy(num(initial, "iy") as u16);
+    regs.set_i(num(initial, "i") as u8);
+    regs.set_r(num(initial, "r") as u8);
+
+    for (addr, val) in ram(initial) {
+        bus.mem_write(addr, val);
+    }
+    // Ports read by the instruction, which the fake bus answers by their low address byte
+    let ports = test["ports"].as_array().cloned().unwrap_or_default();
+    for port in &ports {
+        if port[2] == "r" {
+            bus.io_write(port[0].as_u64().unwrap() as u8, port[1].as_u64().unwrap() as u8);
+        }
+    }
+
+    let result = cpu.exec(bus);
+    let mut mismatches = Mismatches::default();
+    match result {
+        Err(err) => mismatches.0.push(err.to_string()),
+        Ok(()) => {
+            let regs = cpu.regs();
+            mismatches.check("PC", regs.pc() as u64, num(expected, "pc"));
+            mismatches.check("SP", regs.sp() as u64, num(expected, "sp"));
+            mismatches.check("A", regs.a() as u64, num(expected, "a"));
+            mismatches.check("F", regs.flags() as u64, num(expected, "f"));
+            mismatches.check("B", regs.b() as u64, num(expected, "b"));
+            mismatches.check("C", regs.c() as u64, num(expected, "c"));
+            mismatches.check("D", regs.d() as u64, num(expected, "d"));
+            mismatches.check("E", regs.e() as u64, num(expected, "e"));
+            mismatches.check("H", regs.h() as u64, num(expected, "h"));
+            mismatches.check("L", regs.l() as u64, num(expected, "l"));
+            mismatches.check("AF'", regs.af_() as u64, num(expected, "af_"));
+            mismatches.check("BC'", regs.bc_() as u64, num(expected, "bc_"));
+            mismatches.check("DE'", regs.de_() as u64, num(expected, "de_"));
+            mismatches.check("HL'", regs.hl_() as u64, num(expected, "hl_"));
+            mismatches.check("IX", regs.ix() as u64, num(expected, "ix"));
+            mismatches.check("IY", regs.iy() as u64, num(expected, "iy"));
+            mismatches.check("I", regs.i() as u64, num(expected, "i"));
+            mismatches.check("R", regs.r() as u64, num(expected, "r"));
+            for (addr, val) in ram(expected) {
+                mismatches.check(&format!("[{:04X}]", addr), bus.mem_read(addr) as u64, val as u64);
+            }
+            for port in &ports {
+                if port[2] == "w" {
+                    let addr = port[0].as_u64().unwrap() as u8;
+                    mismatches.check(&format!("port {:02X}", addr), bus.io_read(addr) as u64, port[1].as_u64().unwrap());
+                }
+            }
+            // Every entry of the cycle list is a T-state
+            let cycles = test["cycles"].as_array().unwrap().len();
+            mismatches.check("T-states", cpu.cycles() as u64, cycles as u64);
+        },
+    }
+    mismatches.result()
+}
+
+#[test]
+fn single_step_z80() {
+    let mut bus = z80::FakeBus::new();
+    run_suite("z80", |tests| run_tests(tests, &mut bus, run_z80_test));
+}